@@ -1,33 +1,30 @@
-use crate::config::Config;
+use crate::config::{Config, SavedSearch, Severity};
 use crate::data::{self, Note};
+use crate::keys::{KeyAction, KeyChord, KeyLookup, Keymap, format_sequence};
+use crate::state::{LayoutState, State};
+use crate::theme::{BUILTIN_THEMES, ColorMode, ThemeColors};
+use crate::{attachments, calendar, doctor, ops, search, tasks};
 use arboard::Clipboard;
-use crossterm::event::{KeyCode, KeyEvent};
-use fuzzy_matcher::FuzzyMatcher;
-use fuzzy_matcher::skim::SkimMatcherV2;
-use ratatui::style::Color;
+use chrono::{DateTime, Days, Local, NaiveDate};
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers, MouseButton, MouseEvent, MouseEventKind};
+use ratatui::layout::{Position, Rect};
 use ratatui::widgets::ListState;
-use std::collections::VecDeque;
-use std::path::PathBuf;
-
-pub struct ThemeColors {
-    pub accent: Color,
-    pub selection: Color,
-    pub header: Color,
-    pub dim: Color,
-    pub bold: Color,
-}
+use std::collections::{BTreeMap, BTreeSet, VecDeque};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+use unicode_width::UnicodeWidthChar;
 
-impl Default for ThemeColors {
-    fn default() -> Self {
-        Self {
-            accent: Color::Rgb(137, 220, 235),
-            selection: Color::Rgb(187, 154, 247),
-            dim: Color::Rgb(108, 112, 134),
-            header: Color::Rgb(137, 180, 250),
-            bold: Color::Rgb(243, 139, 168),
-        }
-    }
-}
+// list pane width in percent: default, bounds and resize step
+pub const LIST_WIDTH: u16 = 30;
+pub const LIST_WIDTH_MIN: u16 = 15;
+pub const LIST_WIDTH_MAX: u16 = 70;
+pub const LIST_WIDTH_STEP: u16 = 5;
+// terminals narrower than this stack the list above the preview
+pub const STACK_BELOW_COLUMNS: u16 = 80;
+// two clicks on the same row within this make a double-click
+const DOUBLE_CLICK: Duration = Duration::from_millis(400);
+// preview lines per scroll wheel notch
+const WHEEL_LINES: u16 = 3;
 
 pub enum Action {
     None,
@@ -38,9 +35,11 @@ pub enum Action {
     EditNote,
     DeleteNote,
     RenameNote,
+    AttachFile,
     CopyContent,
     CopyPath,
     ToggleLogs,
+    OpenLog,
     EnterChar(char),
     Backspace,
     SubmitInput,
@@ -49,6 +48,17 @@ pub enum Action {
     ScrollDown,
     CycleSort,
     CycleTheme,
+    OpenNote(PathBuf),
+    OpenUrl(String),
+    SwitchVault(String),
+    Suspend,
+}
+
+// background git work that is due
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub struct AutoSyncJob {
+    pub commit: bool,
+    pub push: bool,
 }
 
 #[derive(PartialEq, Clone, Copy)]
@@ -67,6 +77,23 @@ impl SortMode {
         }
     }
 
+    pub fn from_config(config: &Config) -> Self {
+        config
+            .sort_mode
+            .as_deref()
+            .and_then(SortMode::from_name)
+            .unwrap_or(SortMode::Date)
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "Date" => Some(SortMode::Date),
+            "Name" => Some(SortMode::Name),
+            "Size" => Some(SortMode::Size),
+            _ => None,
+        }
+    }
+
     pub fn as_str(&self) -> &str {
         match self {
             SortMode::Date => "Date",
@@ -82,13 +109,34 @@ pub enum InputMode {
     Editing,
     CreatingFolder,
     Renaming,
+    Attaching,
     ConfirmDelete,
     Search,
     ContentSearch,
     TagSearch,
+    Palette,
+    VaultSwitcher,
+    Doctor,
+    Tasks,
+    Calendar,
     Help,
 }
 
+// something the command palette can run
+#[derive(Debug, Clone, PartialEq)]
+pub enum PaletteItem {
+    Action(KeyAction),
+    SavedSearch(SavedSearch),
+    RecentNote(PathBuf),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct PaletteEntry {
+    pub item: PaletteItem,
+    pub label: String,
+    pub detail: String,
+}
+
 // main app state
 pub struct App {
     pub notes: Vec<Note>,
@@ -111,6 +159,46 @@ pub struct App {
     pub preview_scroll: u16,
     pub theme: ThemeColors,
     pub sort_mode: SortMode,
+    pub last_change: Option<Instant>,
+    pub last_push: Instant,
+    pub last_sync: Option<DateTime<Local>>,
+    pub keymap: Keymap,
+    pub pending_keys: Vec<KeyChord>,
+    pub palette_query: String,
+    pub palette_entries: Vec<PaletteEntry>,
+    pub palette_state: ListState,
+    pub recent_notes: VecDeque<PathBuf>,
+    pub vault_name: Option<String>,
+    pub vault_state: ListState,
+    pub theme_name: String,
+    // named themes from theme files
+    pub themes: Vec<(String, ThemeColors)>,
+    pub color_mode: ColorMode,
+    // list pane share of the screen, in percent
+    pub list_width: u16,
+    pub show_preview: bool,
+    // preview only
+    pub zen: bool,
+    // where ui state is remembered, none to keep it in memory
+    pub state_file: Option<PathBuf>,
+    // where the list and preview were last drawn, for the mouse
+    pub list_area: Option<Rect>,
+    pub preview_area: Option<Rect>,
+    // each wrapped preview row as (content line, char offset it starts at)
+    pub preview_rows: Vec<(usize, usize)>,
+    pub last_click: Option<(Instant, usize)>,
+    // which notes link to which files, built when first needed
+    pub link_index: Option<BTreeMap<PathBuf, BTreeSet<PathBuf>>>,
+    // problems found by the last vault check
+    pub findings: Vec<doctor::Finding>,
+    pub doctor_state: ListState,
+    // checkboxes from all notes, as of when the tasks view was opened
+    pub tasks: Vec<tasks::Task>,
+    pub task_filter: tasks::TaskFilter,
+    pub tasks_state: ListState,
+    // day picked in the calendar, and the notes of each day
+    pub calendar_date: NaiveDate,
+    pub calendar_days: BTreeMap<NaiveDate, Vec<PathBuf>>,
 }
 
 impl App {
@@ -127,15 +215,9 @@ impl App {
             }
         };
 
-        let initial_sort = if let Some(ref s) = config.sort_mode {
-            match s.as_str() {
-                "Name" => SortMode::Name,
-                "Size" => SortMode::Size,
-                _ => SortMode::Date,
-            }
-        } else {
-            SortMode::Date
-        };
+        let initial_sort = SortMode::from_config(&config);
+
+        let (keymap, key_warnings) = Keymap::from_config(config.keys.as_ref());
 
         let mut app = App {
             notes,
@@ -143,7 +225,7 @@ impl App {
             fs_items: Vec::new(),
             current_dir: PathBuf::new(),
             list_state: state,
-            status_msg: String::from(" Press 'h' for help "),
+            status_msg: String::new(),
             base_path,
             config: config.clone(),
             should_quit: false,
@@ -158,29 +240,48 @@ impl App {
             preview_scroll: 0,
             theme: ThemeColors::default(),
             sort_mode: initial_sort,
+            last_change: None,
+            last_push: Instant::now(),
+            last_sync: None,
+            keymap,
+            pending_keys: Vec::new(),
+            palette_query: String::new(),
+            palette_entries: Vec::new(),
+            palette_state: ListState::default(),
+            recent_notes: VecDeque::with_capacity(10),
+            vault_name: None,
+            vault_state: ListState::default(),
+            theme_name: String::from("default"),
+            themes: Vec::new(),
+            color_mode: ColorMode::TrueColor,
+            list_width: LIST_WIDTH,
+            show_preview: true,
+            zen: false,
+            state_file: None,
+            list_area: None,
+            preview_area: None,
+            preview_rows: Vec::new(),
+            last_click: None,
+            link_index: None,
+            findings: Vec::new(),
+            doctor_state: ListState::default(),
+            tasks: Vec::new(),
+            task_filter: tasks::TaskFilter::Open,
+            tasks_state: ListState::default(),
+            calendar_date: Local::now().date_naive(),
+            calendar_days: BTreeMap::new(),
         };
+        app.status_msg = app.help_hint();
+        if !key_warnings.is_empty() {
+            for w in &key_warnings {
+                log::warn!("Key config: {}", w);
+            }
+            app.status_msg = format!(" Key config: {} ", key_warnings.join("; "));
+        }
 
         if let Some(user_theme) = &config.theme {
-            let parse = |s: &Option<String>, fallback: Color| -> Color {
-                if let Some(hex) = s
-                    && hex.starts_with('#')
-                    && hex.len() == 7
-                    && let (Ok(r), Ok(g), Ok(b)) = (
-                        u8::from_str_radix(&hex[1..3], 16),
-                        u8::from_str_radix(&hex[3..5], 16),
-                        u8::from_str_radix(&hex[5..7], 16),
-                    )
-                {
-                    return Color::Rgb(r, g, b);
-                }
-                fallback
-            };
-
-            app.theme.accent = parse(&user_theme.accent, app.theme.accent);
-            app.theme.selection = parse(&user_theme.selection, app.theme.selection);
-            app.theme.header = parse(&user_theme.header, app.theme.header);
-            app.theme.dim = parse(&user_theme.dim, app.theme.dim);
-            app.theme.bold = parse(&user_theme.bold, app.theme.bold);
+            app.theme = ThemeColors::from_config(user_theme);
+            app.theme_name = String::from("custom");
         }
 
         app.sort_notes();
@@ -192,19 +293,58 @@ impl App {
         app
     }
 
+    // swap in another notebook, resetting per-vault state
+    pub fn load_vault(
+        &mut self,
+        name: Option<String>,
+        base_path: PathBuf,
+        notes: Vec<Note>,
+        config: Config,
+    ) {
+        self.base_path = base_path;
+        self.vault_name = name;
+        self.sort_mode = SortMode::from_config(&config);
+        self.config = config;
+        self.all_notes = notes.clone();
+        self.notes = notes;
+        self.current_dir = PathBuf::new();
+        self.search_query.clear();
+        self.recent_indices.clear();
+        self.recent_notes.clear();
+        self.preview_scroll = 0;
+        self.last_change = None;
+        self.last_push = Instant::now();
+        self.last_sync = None;
+
+        self.fs_items.clear();
+
+        self.sort_notes();
+        self.refresh_fs_view();
+        let first = if self.fs_items.is_empty() {
+            None
+        } else {
+            Some(0)
+        };
+        self.list_state.select(first);
+        self.restore_state();
+    }
+
+    // ignore patterns of the current vault
+    pub fn ignore_rules(&self) -> data::IgnoreRules {
+        data::IgnoreRules::from_config(&self.base_path, &self.config)
+    }
+
     // refresh item list from current dir
     pub fn refresh_fs_view(&mut self) {
         let target_dir = self.base_path.join(&self.current_dir);
-        let items_res = data::load_all_items(&target_dir.to_string_lossy());
+        let items_res =
+            data::load_all_items_with(&target_dir.to_string_lossy(), &self.ignore_rules());
 
         if let Ok(mut items) = items_res {
             let current_depth = self.current_dir.components().count();
 
             items.retain(|item| {
-                let path = match item {
-                    data::FileSystemItem::Note(n) => &n.path,
-                    data::FileSystemItem::Folder(p) => p,
-                };
+                let path = item.path();
 
                 let rel_path = path.strip_prefix(&self.base_path).unwrap_or(path);
 
@@ -220,6 +360,164 @@ impl App {
         }
     }
 
+    // apply a batch of changed paths from the watcher, keeping the
+    // selection; false if none of them matter to the vault
+    pub fn apply_file_changes(&mut self, paths: &[PathBuf]) -> bool {
+        let ignore = self.ignore_rules();
+        // new ignore rules can hide or reveal anything, so start over
+        if paths.iter().any(|p| ignore.is_ignore_file(p)) {
+            self.refresh_notes();
+            return true;
+        }
+
+        let selected = self.selected_path();
+        let selected_index = self.list_state.selected();
+        let current = self.base_path.join(&self.current_dir);
+        let mut changed = false;
+        // events come with absolute paths even when the vault path is relative
+        let root = self
+            .base_path
+            .canonicalize()
+            .unwrap_or(self.base_path.clone());
+
+        for path in paths {
+            let Some(rel) = path
+                .strip_prefix(&self.base_path)
+                .or_else(|_| path.strip_prefix(&root))
+                .ok()
+            else {
+                continue;
+            };
+            let path = &self.base_path.join(rel);
+            if rel.as_os_str().is_empty() || ignore.is_ignored(path, path.is_dir()) {
+                continue;
+            }
+            changed = true;
+
+            // drop what was there, then read back whatever is there now
+            self.all_notes.retain(|n| !n.path.starts_with(path));
+            if path.is_dir() {
+                let notes = data::load_notes_with(&path.to_string_lossy(), &ignore)
+                    .unwrap_or_default()
+                    .into_iter()
+                    .filter_map(|n| data::Note::from_path(n.path, &self.base_path).ok());
+                self.all_notes.extend(notes);
+            } else if ignore.is_note_file(path)
+                && let Ok(note) = data::Note::from_path(path.clone(), &self.base_path)
+            {
+                self.all_notes.push(note);
+            }
+
+            // only entries directly in the open folder are listed
+            if path.parent() != Some(current.as_path()) {
+                continue;
+            }
+            self.fs_items.retain(|item| item.path() != path);
+            let hidden = path
+                .file_name()
+                .is_some_and(|n| n.to_string_lossy().starts_with('.'));
+            if hidden {
+                continue;
+            }
+            if path.is_dir() {
+                self.fs_items
+                    .push(data::FileSystemItem::Folder(path.clone()));
+            } else if let Some(note) = self.all_notes.iter().find(|n| &n.path == path) {
+                self.fs_items.push(data::FileSystemItem::Note(note.clone()));
+            } else if path.is_file() && !ignore.is_note_file(path) {
+                self.fs_items
+                    .push(data::FileSystemItem::Attachment(path.clone()));
+            }
+        }
+
+        if !changed {
+            return false;
+        }
+        self.link_index = None;
+
+        self.all_notes
+            .sort_by_key(|n| std::cmp::Reverse(n.last_modified));
+        data::sort_items(&mut self.fs_items);
+
+        // the open folder itself may be gone
+        if !current.is_dir() {
+            while !self.base_path.join(&self.current_dir).is_dir() && self.current_dir.pop() {}
+            self.refresh_fs_view();
+        }
+
+        self.rebuild_notes(selected, selected_index);
+        true
+    }
+
+    // rescan the whole vault, keeping the selection
+    pub fn refresh_notes(&mut self) {
+        let selected = self.selected_path();
+        let selected_index = self.list_state.selected();
+
+        let path_str = self.base_path.to_string_lossy().to_string();
+        match data::load_notes_with(&path_str, &self.ignore_rules()) {
+            Ok(notes) => self.all_notes = notes,
+            Err(e) => log::error!("Failed to reload notes: {}", e),
+        }
+        self.link_index = None;
+        while !self.base_path.join(&self.current_dir).is_dir() && self.current_dir.pop() {}
+        self.refresh_fs_view();
+
+        self.rebuild_notes(selected, selected_index);
+    }
+
+    // refill the note list from all_notes and select the item that was
+    // selected before, or the row where it was
+    fn rebuild_notes(&mut self, selected: Option<PathBuf>, selected_index: Option<usize>) {
+        if self.search_query.is_empty() {
+            self.notes = self.all_notes.clone();
+            self.sort_notes();
+        } else {
+            // keep the results, with fresh copies of the notes still around
+            let all = &self.all_notes;
+            self.notes = self
+                .notes
+                .iter()
+                .filter_map(|n| all.iter().find(|a| a.path == n.path).cloned())
+                .collect();
+        }
+        self.recent_indices.clear();
+
+        let len = self.list_len();
+        let index = selected
+            .and_then(|path| self.position_of(&path))
+            .or(selected_index.map(|i| i.min(len.saturating_sub(1))))
+            .filter(|_| len > 0);
+        match index {
+            Some(i) => {
+                self.list_state.select(Some(i));
+                if self.search_query.is_empty() {
+                    self.load_fs_item_content(i);
+                } else {
+                    self.load_note_content(i);
+                }
+            }
+            None => self.list_state.select(None),
+        }
+    }
+
+    // path of the selected item
+    pub fn selected_path(&self) -> Option<PathBuf> {
+        let i = self.list_state.selected()?;
+        if !self.search_query.is_empty() {
+            return self.notes.get(i).map(|n| n.path.clone());
+        }
+        self.fs_items.get(i).map(|item| item.path().to_path_buf())
+    }
+
+    // row of a path in the current list
+    fn position_of(&self, path: &Path) -> Option<usize> {
+        if !self.search_query.is_empty() {
+            return self.notes.iter().position(|n| n.path == path);
+        }
+        self.fs_items.iter().position(|item| item.path() == path)
+    }
+
     // sort notes
     pub fn sort_notes(&mut self) {
         if !self.search_query.is_empty() {
@@ -229,14 +527,13 @@ impl App {
         match self.sort_mode {
             SortMode::Date => {
                 self.notes
-                    .sort_by(|a, b| b.last_modified.cmp(&a.last_modified));
+                    .sort_by_key(|n| std::cmp::Reverse(n.last_modified));
             }
             SortMode::Name => {
-                self.notes
-                    .sort_by(|a, b| a.title.to_lowercase().cmp(&b.title.to_lowercase()));
+                self.notes.sort_by_key(|n| n.title.to_lowercase());
             }
             SortMode::Size => {
-                self.notes.sort_by(|a, b| b.size.cmp(&a.size));
+                self.notes.sort_by_key(|n| std::cmp::Reverse(n.size));
             }
         }
     }
@@ -247,28 +544,10 @@ impl App {
             self.notes = self.all_notes.clone();
             self.sort_notes();
         } else {
-            let matcher = SkimMatcherV2::default();
-            let mut matches: Vec<(&Note, i64)> = self
-                .all_notes
-                .iter()
-                .filter_map(|note| {
-                    matcher
-                        .fuzzy_match(&note.title, &self.search_query)
-                        .map(|score| (note, score))
-                })
-                .collect();
-
-            matches.sort_by(|a, b| b.1.cmp(&a.1));
-            self.notes = matches.into_iter().map(|(n, _)| n.clone()).collect();
+            self.notes = search::by_title(&self.all_notes, &self.search_query);
         }
 
-        // reset selection
-        if !self.notes.is_empty() {
-            self.list_state.select(Some(0));
-            self.load_note_content(0);
-        } else {
-            self.list_state.select(None);
-        }
+        self.reset_selection();
     }
 
     // fuzzy search notes by tags
@@ -277,33 +556,10 @@ impl App {
             self.notes = self.all_notes.clone();
             self.sort_notes();
         } else {
-            let matcher = SkimMatcherV2::default();
-            let mut matches: Vec<(&Note, i64)> = self
-                .all_notes
-                .iter()
-                .filter_map(|note| {
-                    // Check if any tag matches
-                    let best_score = note
-                        .tags
-                        .iter()
-                        .filter_map(|tag| matcher.fuzzy_match(tag, &self.search_query))
-                        .max();
-
-                    best_score.map(|score| (note, score))
-                })
-                .collect();
-
-            matches.sort_by(|a, b| b.1.cmp(&a.1));
-            self.notes = matches.into_iter().map(|(n, _)| n.clone()).collect();
+            self.notes = search::by_tag(&self.all_notes, &self.search_query);
         }
 
-        // reset selection
-        if !self.notes.is_empty() {
-            self.list_state.select(Some(0));
-            self.load_note_content(0);
-        } else {
-            self.list_state.select(None);
-        }
+        self.reset_selection();
     }
 
     // fuzzy search notes by content
@@ -312,34 +568,14 @@ impl App {
             self.notes = self.all_notes.clone();
             self.sort_notes();
         } else {
-            let matcher = SkimMatcherV2::default();
-            let query = self.search_query.to_lowercase();
-
-            let mut matches: Vec<(&Note, i64)> = self
-                .all_notes
-                .iter()
-                .filter_map(|note| {
-                    let content = if let Some(ref c) = note.content {
-                        Some(c.clone())
-                    } else {
-                        data::read_note_content(&note.path).ok()
-                    };
-
-                    if let Some(content) = content
-                        && content.to_lowercase().contains(&query)
-                    {
-                        let title_score = matcher.fuzzy_match(&note.title, &query).unwrap_or(0);
-                        return Some((note, 100 + title_score));
-                    }
-                    None
-                })
-                .collect();
-
-            matches.sort_by(|a, b| b.1.cmp(&a.1));
-            self.notes = matches.into_iter().map(|(n, _)| n.clone()).collect();
+            self.notes = search::by_content(&self.all_notes, &self.search_query);
         }
 
-        // reset selection
+        self.reset_selection();
+    }
+
+    // reset selection
+    fn reset_selection(&mut self) {
         if !self.notes.is_empty() {
             self.list_state.select(Some(0));
             self.load_note_content(0);
@@ -462,13 +698,6 @@ impl App {
             return;
         }
 
-        match &self.fs_items[index] {
-            data::FileSystemItem::Note(_note) => {}
-            data::FileSystemItem::Folder(_) => {
-                return;
-            }
-        }
-
         if let data::FileSystemItem::Note(ref mut n) = self.fs_items[index]
             && n.content.is_none()
             && let Ok(c) = data::read_note_content(&n.path)
@@ -483,165 +712,877 @@ impl App {
         }
     }
 
+    // record an external change for the auto-commit debounce
+    pub fn mark_changed(&mut self, now: Instant) {
+        if self.config.auto_commit_minutes.is_some() {
+            self.last_change = Some(now);
+        }
+    }
+
+    // check whether an interval commit or push is due
+    pub fn poll_auto_sync(&mut self, now: Instant) -> Option<AutoSyncJob> {
+        if self.syncing {
+            return None;
+        }
+
+        let commit = match (self.config.auto_commit_minutes, self.last_change) {
+            (Some(mins), Some(changed)) => {
+                now.duration_since(changed) >= Duration::from_secs(mins * 60)
+            }
+            _ => false,
+        };
+        let push = match self.config.auto_push_minutes {
+            Some(mins) => now.duration_since(self.last_push) >= Duration::from_secs(mins * 60),
+            None => false,
+        };
+
+        if !commit && !push {
+            return None;
+        }
+
+        if commit {
+            self.last_change = None;
+        }
+        if push {
+            self.last_push = now;
+        }
+        self.syncing = true;
+        Some(AutoSyncJob { commit, push })
+    }
+
+    // apply the result of a background sync
+    pub fn finish_auto_sync(&mut self, result: Result<String, String>) {
+        self.syncing = false;
+        match result {
+            Ok(msg) => {
+                log::info!("Background sync: {}", msg);
+                self.last_sync = Some(Local::now());
+            }
+            Err(e) => {
+                log::error!("Background sync failed: {}", e);
+                self.status_msg = format!("Auto-sync error: {}", e);
+            }
+        }
+    }
+
     pub fn quit(&mut self) {
         self.should_quit = true;
     }
 
-    pub fn save_config(&self) {
-        let mut config = self.config.clone();
-        config.sort_mode = Some(self.sort_mode.as_str().to_string());
-        if let Err(e) = crate::config::save_config(&config) {
-            log::error!("Failed to save config: {}", e);
+    // themes to cycle through: config, built-in, then theme files
+    pub fn theme_names(&self) -> Vec<&str> {
+        let mut names = Vec::new();
+        if self.config.theme.is_some() {
+            names.push("custom");
         }
+        names.extend(BUILTIN_THEMES);
+        for (name, _) in &self.themes {
+            if !names.contains(&name.as_str()) {
+                names.push(name);
+            }
+        }
+        names
     }
 
-    pub fn cycle_theme(&mut self) {
-        // cycle through themes
-        let current_accent = self.theme.accent;
-
-        // identify current theme by accent
-        // Default: 137, 220, 235 (#89dceb)
-        // Gruvbox: 250, 189, 47  (#fabd2f)
-        // Tokyo:   122, 162, 247 (#7aa2f7)
-
-        let next_theme = if current_accent == Color::Rgb(137, 220, 235) {
-            // Gruvbox
-            ThemeColors {
-                accent: Color::Rgb(250, 189, 47),    // Yellow
-                selection: Color::Rgb(215, 153, 33), // Dark Yellow
-                header: Color::Rgb(251, 73, 52),     // Red
-                dim: Color::Rgb(168, 153, 132),      // Gray
-                bold: Color::Rgb(254, 128, 25),      // Orange
-            }
-        } else if current_accent == Color::Rgb(250, 189, 47) {
-            // Tokyo Night
-            ThemeColors {
-                accent: Color::Rgb(122, 162, 247),    // Blue
-                selection: Color::Rgb(187, 154, 247), // Purple
-                header: Color::Rgb(125, 207, 255),    // Cyan
-                dim: Color::Rgb(86, 95, 137),         // Dark Blue/Gray
-                bold: Color::Rgb(247, 118, 142),      // Red/Pink
+    // switch theme by name; theme files shadow built-in themes
+    pub fn set_theme(&mut self, name: &str) -> bool {
+        let file_theme = self
+            .themes
+            .iter()
+            .find(|(n, _)| n == name)
+            .map(|(_, colors)| *colors);
+        let theme = match (name, &self.config.theme) {
+            ("custom", Some(user_theme)) => Some(ThemeColors::from_config(user_theme)),
+            _ => file_theme.or_else(|| ThemeColors::builtin(name)),
+        };
+        match theme {
+            Some(theme) => {
+                self.theme = theme.for_mode(self.color_mode);
+                self.theme_name = name.to_string();
+                true
             }
+            None => false,
+        }
+    }
+
+    // redraw the current theme with fewer (or more) colors
+    pub fn set_color_mode(&mut self, mode: ColorMode) {
+        self.color_mode = mode;
+        let name = self.theme_name.clone();
+        self.set_theme(&name);
+    }
+
+    pub fn cycle_theme(&mut self) {
+        let names = self.theme_names();
+        let next = names
+            .iter()
+            .position(|n| *n == self.theme_name)
+            .map(|i| names[(i + 1) % names.len()])
+            .unwrap_or(names[0])
+            .to_string();
+
+        self.set_theme(&next);
+        self.status_msg = format!("Theme: {}", next);
+        self.save_state();
+    }
+
+    // grow or shrink the list pane by a step
+    pub fn resize_list(&mut self, grow: bool) {
+        self.list_width = if grow {
+            (self.list_width + LIST_WIDTH_STEP).min(LIST_WIDTH_MAX)
         } else {
-            // Back to Default (Catppuccin Mocha-ish)
-            ThemeColors::default()
+            self.list_width
+                .saturating_sub(LIST_WIDTH_STEP)
+                .max(LIST_WIDTH_MIN)
         };
+        self.show_preview = true;
+        self.zen = false;
+        self.status_msg = format!("List width: {}%", self.list_width);
+        self.save_state();
+    }
 
-        self.theme = next_theme;
+    pub fn toggle_preview(&mut self) {
+        if self.zen {
+            // leaving zen for the list alone
+            self.zen = false;
+            self.show_preview = false;
+        } else {
+            self.show_preview = !self.show_preview;
+        }
+        self.status_msg = if self.show_preview {
+            String::from("Preview on")
+        } else {
+            String::from("Preview off")
+        };
+        self.save_state();
     }
 
-    // handle input
-    pub fn handle_input(&mut self, key: KeyEvent) -> Action {
-        match self.input_mode {
-            InputMode::Normal => match key.code {
-                KeyCode::Char('q') => Action::Quit,
-                KeyCode::Char('j')
-                    if key
-                        .modifiers
-                        .contains(crossterm::event::KeyModifiers::CONTROL) =>
-                {
-                    Action::ScrollDown
+    pub fn toggle_zen(&mut self) {
+        self.zen = !self.zen;
+        if self.zen {
+            self.show_preview = true;
+        }
+        self.status_msg = if self.zen {
+            String::from("Zen mode")
+        } else {
+            self.help_hint()
+        };
+        self.save_state();
+    }
+
+    // apply remembered theme, layout, sort mode and folder
+    pub fn restore_state(&mut self) {
+        let Some(path) = &self.state_file else {
+            return;
+        };
+        let state = State::load(path);
+
+        if let Some(name) = &state.theme
+            && !self.set_theme(name)
+        {
+            log::warn!("Remembered theme '{}' is not available", name);
+        }
+
+        if let Some(width) = state.layout.list_width {
+            self.list_width = width.clamp(LIST_WIDTH_MIN, LIST_WIDTH_MAX);
+        }
+        self.show_preview = state.layout.preview.unwrap_or(self.show_preview);
+        self.zen = state.layout.zen.unwrap_or(self.zen);
+
+        let Some(vault) = state.vault(&self.base_path) else {
+            return;
+        };
+        if let Some(mode) = vault.sort_mode.as_deref().and_then(SortMode::from_name) {
+            self.sort_mode = mode;
+            self.sort_notes();
+        }
+        if let Some(folder) = &vault.folder
+            && self.base_path.join(folder).is_dir()
+        {
+            self.current_dir = PathBuf::from(folder);
+            self.refresh_fs_view();
+            let first = if self.fs_items.is_empty() {
+                None
+            } else {
+                Some(0)
+            };
+            self.list_state.select(first);
+        }
+    }
+
+    // remember theme, layout, vault, sort mode and folder for next time
+    pub fn save_state(&self) {
+        let Some(path) = &self.state_file else {
+            return;
+        };
+        let mut state = State::load(path);
+
+        state.theme = Some(self.theme_name.clone());
+        state.layout = LayoutState {
+            list_width: Some(self.list_width),
+            preview: Some(self.show_preview),
+            zen: Some(self.zen),
+        };
+        if self.vault_name.is_some() {
+            state.last_vault = self.vault_name.clone();
+        }
+        let vault = state.vault_mut(&self.base_path);
+        vault.sort_mode = Some(self.sort_mode.as_str().to_string());
+        vault.folder = if self.current_dir.as_os_str().is_empty() {
+            None
+        } else {
+            Some(self.current_dir.to_string_lossy().to_string())
+        };
+
+        if let Err(e) = state.save(path) {
+            log::error!("Failed to save state: {}", e);
+        }
+    }
+
+    // run a bound action
+    pub fn perform(&mut self, action: KeyAction) -> Action {
+        match action {
+            KeyAction::Quit => Action::Quit,
+            KeyAction::ScrollDown => Action::ScrollDown,
+            KeyAction::ScrollUp => Action::ScrollUp,
+            KeyAction::Down => {
+                self.next();
+                Action::None
+            }
+            KeyAction::Up => {
+                self.previous();
+                Action::None
+            }
+            KeyAction::Top => {
+                self.select_edge(false);
+                Action::None
+            }
+            KeyAction::Bottom => {
+                self.select_edge(true);
+                Action::None
+            }
+            KeyAction::Sync => Action::Sync,
+            KeyAction::NewNote => Action::NewNote,
+            KeyAction::NewFolder => Action::NewFolder,
+            KeyAction::Delete => Action::DeleteNote,
+            KeyAction::Attach => Action::AttachFile,
+            KeyAction::Rename => Action::RenameNote,
+            KeyAction::CycleSort => Action::CycleSort,
+            KeyAction::CycleTheme => Action::CycleTheme,
+            KeyAction::CopyContent => Action::CopyContent,
+            KeyAction::CopyPath => Action::CopyPath,
+            KeyAction::Search => {
+                self.input_mode = InputMode::Search;
+                self.search_query.clear();
+                self.status_msg = String::from("Search: ");
+                Action::None
+            }
+            KeyAction::TagSearch => {
+                self.input_mode = InputMode::TagSearch;
+                self.search_query.clear();
+                self.status_msg = String::from("Tag Search: ");
+                Action::None
+            }
+            KeyAction::ContentSearch => {
+                self.input_mode = InputMode::ContentSearch;
+                self.search_query.clear();
+                self.status_msg = String::from("Content Search: ");
+                Action::None
+            }
+            KeyAction::Palette => {
+                self.input_mode = InputMode::Palette;
+                self.palette_query.clear();
+                self.update_palette();
+                self.status_msg = String::from("Command: ");
+                Action::None
+            }
+            KeyAction::SwitchVault => {
+                let vaults = self.config.vaults.as_deref().unwrap_or_default();
+                if vaults.is_empty() {
+                    self.status_msg = String::from("No vaults configured.");
+                    return Action::None;
                 }
-                KeyCode::Char('k')
-                    if key
-                        .modifiers
-                        .contains(crossterm::event::KeyModifiers::CONTROL) =>
+                let current = self.vault_name.as_deref().and_then(|name| {
+                    vaults
+                        .iter()
+                        .position(|v| v.name.eq_ignore_ascii_case(name))
+                });
+                self.vault_state.select(Some(current.unwrap_or(0)));
+                self.input_mode = InputMode::VaultSwitcher;
+                self.status_msg = String::from("Switch vault: ");
+                Action::None
+            }
+            KeyAction::Doctor => {
+                self.run_doctor();
+                Action::None
+            }
+            KeyAction::Tasks => {
+                self.open_tasks();
+                Action::None
+            }
+            KeyAction::Calendar => {
+                self.open_calendar();
+                Action::None
+            }
+            KeyAction::Help => {
+                self.input_mode = InputMode::Help;
+                self.status_msg = String::from(" Help ");
+                Action::None
+            }
+            KeyAction::Parent => {
+                if self.search_query.is_empty()
+                    && self.current_dir.components().count() > 0
+                    && let Some(parent) = self.current_dir.parent()
                 {
-                    Action::ScrollUp
+                    self.current_dir = parent.to_path_buf();
+                    self.refresh_fs_view();
+                    self.list_state.select(Some(0));
+                    self.status_msg = format!("Dir: {}", self.current_dir.display());
                 }
-                KeyCode::Char('j') => {
-                    self.next();
-                    Action::None
+                Action::None
+            }
+            KeyAction::Open => {
+                // check if selected item is folder
+                if self.search_query.is_empty() {
+                    if let Some(i) = self.list_state.selected() {
+                        if i < self.fs_items.len() {
+                            match &self.fs_items[i] {
+                                data::FileSystemItem::Folder(path) => {
+                                    // enter folder
+                                    let rel_path =
+                                        path.strip_prefix(&self.base_path).unwrap_or(path);
+                                    self.current_dir = rel_path.to_path_buf();
+                                    self.refresh_fs_view();
+                                    self.list_state.select(Some(0));
+                                    self.status_msg =
+                                        format!("Dir: {}", self.current_dir.display());
+                                    Action::None
+                                }
+                                data::FileSystemItem::Note(_) => Action::EditNote,
+                                data::FileSystemItem::Attachment(path) => {
+                                    Action::OpenUrl(path.to_string_lossy().to_string())
+                                }
+                            }
+                        } else {
+                            Action::None
+                        }
+                    } else {
+                        Action::None
+                    }
+                } else {
+                    Action::EditNote
                 }
-                KeyCode::Char('k') => {
-                    self.previous();
-                    Action::None
+            }
+            KeyAction::GrowList => {
+                self.resize_list(true);
+                Action::None
+            }
+            KeyAction::ShrinkList => {
+                self.resize_list(false);
+                Action::None
+            }
+            KeyAction::TogglePreview => {
+                self.toggle_preview();
+                Action::None
+            }
+            KeyAction::ZenMode => {
+                self.toggle_zen();
+                Action::None
+            }
+            KeyAction::ToggleLogs => Action::ToggleLogs,
+            KeyAction::OpenLog => Action::OpenLog,
+            KeyAction::Suspend => Action::Suspend,
+        }
+    }
+
+    // remember a note opened in the editor
+    pub fn remember_recent(&mut self, path: &std::path::Path) {
+        self.recent_notes.retain(|p| p != path);
+        self.recent_notes.push_front(path.to_path_buf());
+        self.recent_notes.truncate(10);
+    }
+
+    // every palette entry before filtering
+    fn palette_candidates(&self) -> Vec<PaletteEntry> {
+        let mut entries = Vec::new();
+
+        for path in &self.recent_notes {
+            let rel = path.strip_prefix(&self.base_path).unwrap_or(path);
+            entries.push(PaletteEntry {
+                item: PaletteItem::RecentNote(path.clone()),
+                label: rel.with_extension("").to_string_lossy().to_string(),
+                detail: String::from("recent"),
+            });
+        }
+
+        for saved in self.config.saved_searches.iter().flatten() {
+            entries.push(PaletteEntry {
+                item: PaletteItem::SavedSearch(saved.clone()),
+                label: saved.name.clone(),
+                detail: format!(
+                    "{} search",
+                    saved.kind.as_deref().unwrap_or("title").to_lowercase()
+                ),
+            });
+        }
+
+        for action in KeyAction::ALL {
+            if action == KeyAction::Palette {
+                continue;
+            }
+            entries.push(PaletteEntry {
+                item: PaletteItem::Action(action),
+                label: action.description().to_string(),
+                detail: self.keymap.describe(action),
+            });
+        }
+
+        entries
+    }
+
+    // fuzzy filter palette entries
+    pub fn update_palette(&mut self) {
+        let candidates = self.palette_candidates();
+        self.palette_entries = if self.palette_query.is_empty() {
+            candidates
+        } else {
+            search::rank(&candidates, &self.palette_query, |e| {
+                let name = match &e.item {
+                    PaletteItem::Action(a) => a.name(),
+                    _ => "",
+                };
+                format!("{} {}", e.label, name)
+            })
+            .into_iter()
+            .cloned()
+            .collect()
+        };
+
+        let selected = if self.palette_entries.is_empty() {
+            None
+        } else {
+            Some(0)
+        };
+        self.palette_state.select(selected);
+    }
+
+    fn palette_move(&mut self, down: bool) {
+        let len = self.palette_entries.len();
+        if len == 0 {
+            return;
+        }
+        let i = self.palette_state.selected().unwrap_or(0);
+        let i = if down {
+            (i + 1) % len
+        } else {
+            (i + len - 1) % len
+        };
+        self.palette_state.select(Some(i));
+    }
+
+    // run the selected palette entry
+    pub fn run_palette_selection(&mut self) -> Action {
+        let entry = self
+            .palette_state
+            .selected()
+            .and_then(|i| self.palette_entries.get(i))
+            .cloned();
+
+        self.input_mode = InputMode::Normal;
+        self.status_msg = self.help_hint();
+
+        match entry.map(|e| e.item) {
+            Some(PaletteItem::Action(action)) => self.perform(action),
+            Some(PaletteItem::SavedSearch(saved)) => {
+                self.search_query = saved.query.clone();
+                match saved.kind.as_deref().map(|k| k.to_lowercase()).as_deref() {
+                    Some("tag") => self.update_tag_search(),
+                    Some("content") => self.update_content_search(),
+                    _ => self.update_search(),
                 }
-                KeyCode::Char('g') => Action::Sync,
-                KeyCode::Char('n') => Action::NewNote,
-                KeyCode::Char('f') => Action::NewFolder,
-                KeyCode::Char('d') => Action::DeleteNote,
-                KeyCode::Char('r') => Action::RenameNote,
-                KeyCode::Char('s') => Action::CycleSort,
-                KeyCode::Char('t') => Action::CycleTheme,
-                KeyCode::Char('y') => Action::CopyContent,
-                KeyCode::Char('Y') => Action::CopyPath,
-                KeyCode::Char('/') => {
-                    self.input_mode = InputMode::Search;
-                    self.search_query.clear();
-                    self.status_msg = String::from("Search: ");
-                    Action::None
+                self.status_msg = format!("{} (Esc to clear)", saved.name);
+                Action::None
+            }
+            Some(PaletteItem::RecentNote(path)) => Action::OpenNote(path),
+            None => Action::None,
+        }
+    }
+
+    // items in the list, search results or folder contents
+    fn list_len(&self) -> usize {
+        if self.search_query.is_empty() {
+            self.fs_items.len()
+        } else {
+            self.notes.len()
+        }
+    }
+
+    // select a list row and load its preview
+    fn select_index(&mut self, i: usize) {
+        self.list_state.select(Some(i));
+        if self.search_query.is_empty() {
+            self.load_fs_item_content(i);
+        } else {
+            self.load_note_content(i);
+        }
+        self.preview_scroll = 0;
+    }
+
+    // jump to first or last item
+    fn select_edge(&mut self, last: bool) {
+        let len = self.list_len();
+        if len == 0 {
+            return;
+        }
+        self.select_index(if last { len - 1 } else { 0 });
+    }
+
+    // check the whole vault and show what was found
+    pub fn run_doctor(&mut self) {
+        self.findings = doctor::check_vault(&self.base_path, &self.all_notes, &self.ignore_rules());
+        if self.findings.is_empty() {
+            self.status_msg = String::from("No problems found.");
+            return;
+        }
+        let errors = self
+            .findings
+            .iter()
+            .filter(|f| f.severity == Severity::Error)
+            .count();
+        self.doctor_state.select(Some(0));
+        self.input_mode = InputMode::Doctor;
+        self.status_msg = format!(
+            "{} error(s), {} warning(s)",
+            errors,
+            self.findings.len() - errors
+        );
+    }
+
+    // clear any search and select a note in its folder, scrolled near a line
+    pub fn reveal(&mut self, path: &Path, line: Option<usize>) {
+        if !self.search_query.is_empty() {
+            self.search_query.clear();
+            self.notes = self.all_notes.clone();
+            self.sort_notes();
+        }
+        let rel = path.strip_prefix(&self.base_path).unwrap_or(path);
+        self.current_dir = rel.parent().map(Path::to_path_buf).unwrap_or_default();
+        self.refresh_fs_view();
+        if let Some(i) = self.position_of(path) {
+            self.select_index(i);
+        }
+        // land a few lines above it
+        let line = line.unwrap_or(1).saturating_sub(3);
+        self.preview_scroll = line.min(u16::MAX as usize) as u16;
+    }
+
+    // collect the vault's tasks and show them
+    pub fn open_tasks(&mut self) {
+        self.tasks = tasks::collect(&self.all_notes);
+        self.input_mode = InputMode::Tasks;
+        self.select_task(0);
+    }
+
+    // tasks the current filter lets through, as indices into tasks
+    pub fn visible_tasks(&self) -> Vec<usize> {
+        let today = Local::now().date_naive();
+        self.tasks
+            .iter()
+            .enumerate()
+            .filter(|(_, task)| self.task_filter.matches(task, today))
+            .map(|(i, _)| i)
+            .collect()
+    }
+
+    fn select_task(&mut self, i: usize) {
+        let len = self.visible_tasks().len();
+        self.tasks_state
+            .select(if len == 0 { None } else { Some(i.min(len - 1)) });
+        self.status_msg = format!("{}: {} task(s)", self.task_filter.label(), len);
+    }
+
+    // tick or untick the selected task in its note
+    fn toggle_task(&mut self) {
+        let visible = self.visible_tasks();
+        let Some(selected) = self.tasks_state.selected() else {
+            return;
+        };
+        let Some(&index) = visible.get(selected) else {
+            return;
+        };
+        match tasks::toggle(&mut self.tasks[index]) {
+            Ok(_) => {
+                // it may have left the filter
+                self.select_task(selected);
+            }
+            Err(e) => {
+                log::error!("Failed to toggle task: {}", e);
+                self.status_msg = format!("Error: {}", e);
+            }
+        }
+    }
+
+    // date the notes and show the month around today
+    pub fn open_calendar(&mut self) {
+        self.calendar_days = calendar::note_dates(&self.base_path, &self.all_notes, &self.config);
+        self.input_mode = InputMode::Calendar;
+        self.calendar_select(Local::now().date_naive());
+    }
+
+    fn calendar_select(&mut self, date: NaiveDate) {
+        self.calendar_date = date;
+        let count = self.calendar_days.get(&date).map_or(0, Vec::len);
+        self.status_msg = format!("{}: {} note(s)", date.format("%a %Y-%m-%d"), count);
+    }
+
+    // the day's journal note, else its first note, else a new journal note
+    fn calendar_open(&mut self) -> Action {
+        let date = self.calendar_date;
+        let journal = match ops::journal_path(&self.base_path, &self.config, date) {
+            Ok(journal) => journal,
+            Err(e) => {
+                self.status_msg = format!("Error: {}", e);
+                return Action::None;
+            }
+        };
+        let path = if journal.exists() {
+            journal
+        } else if let Some(first) = self.calendar_days.get(&date).and_then(|p| p.first()) {
+            first.clone()
+        } else {
+            match ops::ensure_journal_note(&self.base_path, &self.config, date) {
+                Ok(path) => path,
+                Err(e) => {
+                    log::error!("Failed to create journal note: {}", e);
+                    self.status_msg = format!("Error: {}", e);
+                    return Action::None;
                 }
-                KeyCode::Char('#') => {
-                    self.input_mode = InputMode::TagSearch;
-                    self.search_query.clear();
-                    self.status_msg = String::from("Tag Search: ");
-                    Action::None
+            }
+        };
+        self.input_mode = InputMode::Normal;
+        self.reveal(&path, None);
+        Action::OpenNote(path)
+    }
+
+    // notes linking to a file, by path or by file name
+    pub fn linked_from(&mut self, path: &Path) -> Vec<PathBuf> {
+        let index = self
+            .link_index
+            .get_or_insert_with(|| attachments::link_index(&self.base_path, &self.all_notes));
+        let mut notes: BTreeSet<PathBuf> = BTreeSet::new();
+        for (target, sources) in index.iter() {
+            if target == path || target.file_name() == path.file_name() {
+                notes.extend(sources.iter().cloned());
+            }
+        }
+        notes.into_iter().collect()
+    }
+
+    // note under the selection, if it is one
+    pub fn selected_note(&self) -> Option<&Note> {
+        let i = self.list_state.selected()?;
+        if !self.search_query.is_empty() {
+            return self.notes.get(i);
+        }
+        match self.fs_items.get(i)? {
+            data::FileSystemItem::Note(note) => Some(note),
+            _ => None,
+        }
+    }
+
+    // handle a mouse event in the browser
+    pub fn handle_mouse(&mut self, mouse: MouseEvent, now: Instant) -> Action {
+        if self.input_mode != InputMode::Normal {
+            return Action::None;
+        }
+        let at = Position::new(mouse.column, mouse.row);
+        let over = |area: Option<Rect>| area.is_some_and(|a| a.contains(at));
+
+        match mouse.kind {
+            MouseEventKind::ScrollDown if over(self.list_area) => {
+                let len = self.list_len();
+                match self.list_state.selected() {
+                    Some(i) if i + 1 < len => self.select_index(i + 1),
+                    None if len > 0 => self.select_index(0),
+                    _ => {}
                 }
-                KeyCode::Char('?') => {
-                    self.input_mode = InputMode::ContentSearch;
-                    self.search_query.clear();
-                    self.status_msg = String::from("Content Search: ");
-                    Action::None
+            }
+            MouseEventKind::ScrollUp if over(self.list_area) => {
+                if let Some(i) = self.list_state.selected()
+                    && i > 0
+                {
+                    self.select_index(i - 1);
                 }
-                KeyCode::F(1) => {
-                    self.input_mode = InputMode::Help;
-                    self.status_msg = String::from(" Help ");
+            }
+            MouseEventKind::ScrollDown if over(self.preview_area) => {
+                self.preview_scroll = self.preview_scroll.saturating_add(WHEEL_LINES);
+            }
+            MouseEventKind::ScrollUp if over(self.preview_area) => {
+                self.preview_scroll = self.preview_scroll.saturating_sub(WHEEL_LINES);
+            }
+            MouseEventKind::Down(MouseButton::Left) if over(self.list_area) => {
+                return self.click_list(mouse.row, now);
+            }
+            MouseEventKind::Down(MouseButton::Left) if over(self.preview_area) => {
+                return self.click_preview(at);
+            }
+            _ => {}
+        }
+        Action::None
+    }
+
+    // select the clicked row, opening it on a double-click
+    fn click_list(&mut self, row: u16, now: Instant) -> Action {
+        let Some(area) = self.list_area else {
+            return Action::None;
+        };
+        // skip the borders
+        if row <= area.y || row + 1 >= area.bottom() {
+            return Action::None;
+        }
+        let i = self.list_state.offset() + (row - area.y - 1) as usize;
+        if i >= self.list_len() {
+            return Action::None;
+        }
+
+        let double = self
+            .last_click
+            .is_some_and(|(at, last)| last == i && now.duration_since(at) < DOUBLE_CLICK);
+        if double {
+            self.last_click = None;
+            return self.perform(KeyAction::Open);
+        }
+
+        self.last_click = Some((now, i));
+        if self.list_state.selected() != Some(i) {
+            self.select_index(i);
+        }
+        Action::None
+    }
+
+    // follow a link clicked in the preview
+    fn click_preview(&mut self, at: Position) -> Action {
+        let Some(area) = self.preview_area else {
+            return Action::None;
+        };
+        if at.x <= area.x || at.y <= area.y || at.x + 1 >= area.right() {
+            return Action::None;
+        }
+        let row = (at.y - area.y - 1) as usize + self.preview_scroll as usize;
+        let Some(&(line_index, start)) = self.preview_rows.get(row) else {
+            return Action::None;
+        };
+        let Some(note) = self.selected_note() else {
+            return Action::None;
+        };
+        let Some(line) = note
+            .content
+            .as_deref()
+            .and_then(|c| c.lines().nth(line_index))
+        else {
+            return Action::None;
+        };
+
+        // walk the row to the clicked column
+        let column = (at.x - area.x - 1) as usize;
+        let mut width = 0;
+        let mut offset = None;
+        for (byte, c) in line.char_indices().skip(start) {
+            width += c.width().unwrap_or(0);
+            if width > column {
+                offset = Some(byte);
+                break;
+            }
+        }
+        let Some(offset) = offset else {
+            return Action::None;
+        };
+
+        let from = note.path.clone();
+        match data::find_links(line)
+            .into_iter()
+            .find(|l| l.start <= offset && offset < l.end)
+        {
+            Some(link) => self.follow_link(&from, &link),
+            None => Action::None,
+        }
+    }
+
+    // open what a link in a note points at
+    pub fn follow_link(&mut self, from: &Path, link: &data::Link) -> Action {
+        if link.wiki {
+            return match data::resolve_wiki_link(&self.all_notes, &link.target) {
+                Some(note) => Action::OpenNote(note.path.clone()),
+                None => {
+                    self.status_msg = format!("No note named '{}'", link.target);
                     Action::None
                 }
-                KeyCode::Char('h') | KeyCode::Backspace => {
-                    if self.search_query.is_empty()
-                        && self.current_dir.components().count() > 0
-                        && let Some(parent) = self.current_dir.parent()
-                    {
-                        self.current_dir = parent.to_path_buf();
-                        self.refresh_fs_view();
-                        self.list_state.select(Some(0));
-                        self.status_msg = format!("Dir: {}", self.current_dir.display());
+            };
+        }
+        if link.is_external() {
+            return Action::OpenUrl(link.target.clone());
+        }
+
+        // relative to the linking note, dropping any #anchor
+        let target = link.target.split('#').next().unwrap_or("");
+        if target.is_empty() {
+            return Action::None;
+        }
+        let path = from.parent().unwrap_or(&self.base_path).join(target);
+        if !path.is_file() {
+            self.status_msg = format!("Link target not found: {}", target);
+            Action::None
+        } else if data::is_note_file(&path, &self.config.note_extensions()) {
+            Action::OpenNote(path)
+        } else {
+            Action::OpenUrl(path.to_string_lossy().to_string())
+        }
+    }
+
+    // status hint pointing at the help key
+    pub fn help_hint(&self) -> String {
+        match self.keymap.keys_for(KeyAction::Help).first() {
+            Some(key) => format!(" Press '{}' for help ", key),
+            None => String::from(" kiroku "),
+        }
+    }
+
+    // handle input
+    pub fn handle_input(&mut self, key: KeyEvent) -> Action {
+        match self.input_mode {
+            InputMode::Normal => {
+                self.pending_keys.push(KeyChord::from_event(&key));
+                match self.keymap.lookup(&self.pending_keys) {
+                    KeyLookup::Action(action) => {
+                        self.pending_keys.clear();
+                        self.perform(action)
                     }
-                    Action::None
-                }
-                KeyCode::Char('l') | KeyCode::Enter => {
-                    // check if selected item is folder
-                    if self.search_query.is_empty() {
-                        if let Some(i) = self.list_state.selected() {
-                            if i < self.fs_items.len() {
-                                match &self.fs_items[i] {
-                                    data::FileSystemItem::Folder(path) => {
-                                        // enter folder
-                                        let rel_path =
-                                            path.strip_prefix(&self.base_path).unwrap_or(path);
-                                        self.current_dir = rel_path.to_path_buf();
-                                        self.refresh_fs_view();
-                                        self.list_state.select(Some(0));
-                                        self.status_msg =
-                                            format!("Dir: {}", self.current_dir.display());
-                                        Action::None
-                                    }
-                                    data::FileSystemItem::Note(_) => Action::EditNote,
-                                }
-                            } else {
-                                Action::None
-                            }
+                    KeyLookup::Pending => {
+                        self.status_msg = format!("{} ...", format_sequence(&self.pending_keys));
+                        Action::None
+                    }
+                    KeyLookup::Unbound => {
+                        // a broken sequence still lets its last key count on its own
+                        let retry = self.pending_keys.len() > 1;
+                        self.pending_keys.clear();
+                        if retry {
+                            self.handle_input(key)
                         } else {
                             Action::None
                         }
-                    } else {
-                        Action::EditNote
                     }
                 }
-                KeyCode::F(12) => Action::ToggleLogs,
+            }
+            InputMode::Editing
+            | InputMode::CreatingFolder
+            | InputMode::Renaming
+            | InputMode::Attaching => match key.code {
+                KeyCode::Enter => Action::SubmitInput,
+                KeyCode::Esc => Action::CancelInput,
+                KeyCode::Backspace => Action::Backspace,
+                KeyCode::Char(c) => Action::EnterChar(c),
                 _ => Action::None,
             },
-            InputMode::Editing | InputMode::CreatingFolder | InputMode::Renaming => {
-                match key.code {
-                    KeyCode::Enter => Action::SubmitInput,
-                    KeyCode::Esc => Action::CancelInput,
-                    KeyCode::Backspace => Action::Backspace,
-                    KeyCode::Char(c) => Action::EnterChar(c),
-                    _ => Action::None,
-                }
-            }
             InputMode::ConfirmDelete => match key.code {
                 KeyCode::Char('y') => Action::SubmitInput,
                 KeyCode::Char('n') | KeyCode::Esc => Action::CancelInput,
@@ -657,7 +1598,7 @@ impl App {
                     self.input_mode = InputMode::Normal;
                     self.search_query.clear();
                     self.update_search();
-                    self.status_msg = String::from(" Press 'h' for help ");
+                    self.status_msg = self.help_hint();
                     Action::None
                 }
                 KeyCode::Backspace => {
@@ -684,7 +1625,7 @@ impl App {
                     self.input_mode = InputMode::Normal;
                     self.search_query.clear();
                     self.update_tag_search();
-                    self.status_msg = String::from(" Press 'h' for help ");
+                    self.status_msg = self.help_hint();
                     Action::None
                 }
                 KeyCode::Backspace => {
@@ -711,7 +1652,7 @@ impl App {
                     self.input_mode = InputMode::Normal;
                     self.search_query.clear();
                     self.update_search();
-                    self.status_msg = String::from(" Press 'h' for help ");
+                    self.status_msg = self.help_hint();
                     Action::None
                 }
                 KeyCode::Backspace => {
@@ -728,10 +1669,172 @@ impl App {
                 }
                 _ => Action::None,
             },
+            InputMode::Palette => match key.code {
+                KeyCode::Esc => {
+                    self.input_mode = InputMode::Normal;
+                    self.status_msg = self.help_hint();
+                    Action::None
+                }
+                KeyCode::Enter => self.run_palette_selection(),
+                KeyCode::Up => {
+                    self.palette_move(false);
+                    Action::None
+                }
+                KeyCode::Down => {
+                    self.palette_move(true);
+                    Action::None
+                }
+                KeyCode::Char('p' | 'k') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                    self.palette_move(false);
+                    Action::None
+                }
+                KeyCode::Char('n' | 'j') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                    self.palette_move(true);
+                    Action::None
+                }
+                KeyCode::Backspace => {
+                    self.palette_query.pop();
+                    self.update_palette();
+                    Action::None
+                }
+                KeyCode::Char(c) => {
+                    self.palette_query.push(c);
+                    self.update_palette();
+                    Action::None
+                }
+                _ => Action::None,
+            },
+            InputMode::VaultSwitcher => {
+                let len = self.config.vaults.as_ref().map_or(0, |v| v.len());
+                let i = self.vault_state.selected().unwrap_or(0);
+                match key.code {
+                    KeyCode::Esc | KeyCode::Char('q') => {
+                        self.input_mode = InputMode::Normal;
+                        self.status_msg = self.help_hint();
+                        Action::None
+                    }
+                    KeyCode::Down | KeyCode::Char('j') if len > 0 => {
+                        self.vault_state.select(Some((i + 1) % len));
+                        Action::None
+                    }
+                    KeyCode::Up | KeyCode::Char('k') if len > 0 => {
+                        self.vault_state.select(Some((i + len - 1) % len));
+                        Action::None
+                    }
+                    KeyCode::Enter | KeyCode::Char('l') => {
+                        self.input_mode = InputMode::Normal;
+                        match self.config.vaults.as_ref().and_then(|v| v.get(i)) {
+                            Some(vault) => Action::SwitchVault(vault.name.clone()),
+                            None => Action::None,
+                        }
+                    }
+                    _ => Action::None,
+                }
+            }
+            InputMode::Doctor => {
+                let len = self.findings.len();
+                let i = self.doctor_state.selected().unwrap_or(0);
+                match key.code {
+                    KeyCode::Esc | KeyCode::Char('q') => {
+                        self.input_mode = InputMode::Normal;
+                        self.status_msg = self.help_hint();
+                        Action::None
+                    }
+                    KeyCode::Down | KeyCode::Char('j') if len > 0 => {
+                        self.doctor_state.select(Some((i + 1) % len));
+                        Action::None
+                    }
+                    KeyCode::Up | KeyCode::Char('k') if len > 0 => {
+                        self.doctor_state.select(Some((i + len - 1) % len));
+                        Action::None
+                    }
+                    KeyCode::Enter | KeyCode::Char('l') => {
+                        self.input_mode = InputMode::Normal;
+                        if let Some(finding) = self.findings.get(i).cloned() {
+                            self.reveal(&finding.path, finding.line);
+                            self.status_msg =
+                                format!("{} {}", finding.location(&self.base_path), finding);
+                        }
+                        Action::None
+                    }
+                    _ => Action::None,
+                }
+            }
+            InputMode::Tasks => {
+                let len = self.visible_tasks().len();
+                let i = self.tasks_state.selected().unwrap_or(0);
+                match key.code {
+                    KeyCode::Esc | KeyCode::Char('q') => {
+                        self.input_mode = InputMode::Normal;
+                        self.status_msg = self.help_hint();
+                        Action::None
+                    }
+                    KeyCode::Down | KeyCode::Char('j') if len > 0 => {
+                        self.tasks_state.select(Some((i + 1) % len));
+                        Action::None
+                    }
+                    KeyCode::Up | KeyCode::Char('k') if len > 0 => {
+                        self.tasks_state.select(Some((i + len - 1) % len));
+                        Action::None
+                    }
+                    KeyCode::Tab | KeyCode::Char('f') => {
+                        self.task_filter = self.task_filter.next();
+                        self.select_task(0);
+                        Action::None
+                    }
+                    KeyCode::Char(' ' | 'x') => {
+                        self.toggle_task();
+                        Action::None
+                    }
+                    KeyCode::Enter | KeyCode::Char('l') => {
+                        let visible = self.visible_tasks();
+                        if let Some(task) = visible.get(i).map(|&t| self.tasks[t].clone()) {
+                            self.input_mode = InputMode::Normal;
+                            self.reveal(&task.path, Some(task.line));
+                            self.status_msg = format!("Task: {}", task.text);
+                        }
+                        Action::None
+                    }
+                    _ => Action::None,
+                }
+            }
+            InputMode::Calendar => {
+                let date = self.calendar_date;
+                let moved = match key.code {
+                    KeyCode::Left | KeyCode::Char('h') => date.pred_opt(),
+                    KeyCode::Right | KeyCode::Char('l') => date.succ_opt(),
+                    KeyCode::Up | KeyCode::Char('k') => date.checked_sub_days(Days::new(7)),
+                    KeyCode::Down | KeyCode::Char('j') => date.checked_add_days(Days::new(7)),
+                    KeyCode::PageUp | KeyCode::Char('H') => Some(calendar::add_months(date, -1)),
+                    KeyCode::PageDown | KeyCode::Char('L') => Some(calendar::add_months(date, 1)),
+                    KeyCode::Char('t') => Some(Local::now().date_naive()),
+                    _ => None,
+                };
+                if let Some(moved) = moved {
+                    self.calendar_select(moved);
+                    return Action::None;
+                }
+                match key.code {
+                    KeyCode::Esc | KeyCode::Char('q') => {
+                        self.input_mode = InputMode::Normal;
+                        self.status_msg = self.help_hint();
+                        Action::None
+                    }
+                    KeyCode::Enter => self.calendar_open(),
+                    _ => Action::None,
+                }
+            }
             InputMode::Help => match key.code {
-                KeyCode::Esc | KeyCode::Char('q') | KeyCode::Char('h') => {
+                KeyCode::Esc | KeyCode::Char('q') => {
+                    self.input_mode = InputMode::Normal;
+                    self.status_msg = self.help_hint();
+                    Action::None
+                }
+                _ if self.keymap.lookup(&[KeyChord::from_event(&key)])
+                    == KeyLookup::Action(KeyAction::Help) =>
+                {
                     self.input_mode = InputMode::Normal;
-                    self.status_msg = String::from(" Press 'h' for help ");
+                    self.status_msg = self.help_hint();
                     Action::None
                 }
                 _ => Action::None,
@@ -0,0 +1,227 @@
+use crate::config::Config;
+use crate::data::{self, IgnoreRules, Note, NoteFormat};
+use anyhow::{Result, bail};
+use std::collections::{BTreeMap, BTreeSet};
+use std::fs;
+use std::path::{Component, Path, PathBuf};
+use walkdir::WalkDir;
+
+// where attachments go when attachment_dir is not set
+pub const DEFAULT_DIR: &str = "attachments";
+
+// folder a note's attachments are copied to. "./" makes the setting
+// relative to the note's folder, and {note} is the note's file name
+pub fn attachment_dir(base_path: &Path, note: &Path, config: &Config) -> PathBuf {
+    let setting = config.attachment_dir.as_deref().unwrap_or(DEFAULT_DIR);
+    let stem = note
+        .file_stem()
+        .map(|s| s.to_string_lossy().to_string())
+        .unwrap_or_default();
+    let setting = setting.replace("{note}", &stem);
+
+    match setting.strip_prefix("./") {
+        Some(rel) => note.parent().unwrap_or(base_path).join(rel),
+        None => base_path.join(setting),
+    }
+}
+
+// copy a file into the note's attachment folder, without overwriting
+// anything already there, and return where it went
+pub fn attach_file(
+    base_path: &Path,
+    note: &Path,
+    source: &Path,
+    config: &Config,
+) -> Result<PathBuf> {
+    if !source.is_file() {
+        bail!("not a file: {}", source.display());
+    }
+    let dir = attachment_dir(base_path, note, config);
+    fs::create_dir_all(&dir)?;
+
+    let name = source
+        .file_name()
+        .ok_or_else(|| anyhow::anyhow!("no file name in {}", source.display()))?;
+    let mut target = dir.join(name);
+    let stem = source
+        .file_stem()
+        .map(|s| s.to_string_lossy().to_string())
+        .unwrap_or_default();
+    let ext = source
+        .extension()
+        .map(|e| format!(".{}", e.to_string_lossy()))
+        .unwrap_or_default();
+    let mut n = 1;
+    while target.exists() {
+        target = dir.join(format!("{}-{}{}", stem, n, ext));
+        n += 1;
+    }
+
+    fs::copy(source, &target)?;
+    Ok(target)
+}
+
+// link to an attachment in the note's own syntax
+pub fn link_for(note: &Path, attachment: &Path) -> String {
+    let from = note.parent().unwrap_or(Path::new(""));
+    let rel = relative_path(from, attachment);
+    let rel = rel.to_string_lossy().replace('\\', "/");
+    let name = attachment
+        .file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .unwrap_or_default();
+    let image = data::AttachmentKind::from_path(attachment) == data::AttachmentKind::Image;
+
+    match NoteFormat::from_path(note) {
+        NoteFormat::Org => format!("[[file:{}]]", rel),
+        NoteFormat::AsciiDoc if image => format!("image::{}[]", rel),
+        NoteFormat::AsciiDoc => format!("link:{}[{}]", rel, name),
+        NoteFormat::Markdown | NoteFormat::Text => {
+            // angle brackets keep paths with spaces in one piece
+            let target = if rel.contains(' ') {
+                format!("<{}>", rel)
+            } else {
+                rel
+            };
+            let bang = if image { "!" } else { "" };
+            format!("{}[{}]({})", bang, name, target)
+        }
+    }
+}
+
+// every non-note file in the vault
+pub fn list_attachments(base_path: &Path, ignore: &IgnoreRules) -> Vec<PathBuf> {
+    WalkDir::new(base_path)
+        .into_iter()
+        .filter_entry(|e| e.depth() == 0 || !ignore.is_ignored(e.path(), e.file_type().is_dir()))
+        .filter_map(|e| e.ok())
+        .filter(|e| e.file_type().is_file() && !ignore.is_note_file(e.path()))
+        .map(|e| e.into_path())
+        .collect()
+}
+
+// for each linked file, the notes linking to it
+pub fn link_index(base_path: &Path, notes: &[Note]) -> BTreeMap<PathBuf, BTreeSet<PathBuf>> {
+    let mut index: BTreeMap<PathBuf, BTreeSet<PathBuf>> = BTreeMap::new();
+    for note in notes {
+        let Ok(content) = fs::read_to_string(&note.path) else {
+            continue;
+        };
+        for target in link_targets(base_path, &note.path, &content) {
+            index.entry(target).or_default().insert(note.path.clone());
+        }
+    }
+    index
+}
+
+// files a note links to, as paths that may or may not exist
+pub fn link_targets(base_path: &Path, note: &Path, content: &str) -> Vec<PathBuf> {
+    let adoc = NoteFormat::from_path(note) == NoteFormat::AsciiDoc;
+    let mut targets = Vec::new();
+
+    for line in content.lines() {
+        let mut raw: Vec<String> = data::find_links(line)
+            .into_iter()
+            .filter(|l| !l.is_external())
+            .filter_map(|l| match l.target.strip_prefix("file:") {
+                Some(file) => Some(file.to_string()),
+                // plain wiki links name notes, not files
+                None if l.wiki => l.target.contains('.').then_some(l.target),
+                None => Some(l.target),
+            })
+            .collect();
+        if adoc {
+            raw.extend(adoc_targets(line));
+        }
+
+        for target in raw {
+            let target = target.split('#').next().unwrap_or("");
+            if !target.is_empty() {
+                targets.push(resolve_path(base_path, note, target));
+            }
+        }
+    }
+    targets
+}
+
+// file a relative link in a note points at; a leading / means the vault root
+pub fn resolve_path(base_path: &Path, note: &Path, target: &str) -> PathBuf {
+    let path = match target.strip_prefix('/') {
+        Some(rooted) => base_path.join(rooted),
+        None => note.parent().unwrap_or(base_path).join(target),
+    };
+    normalize(&path)
+}
+
+// attachments no note links to; ![[name.png]] style links match by file name
+pub fn orphans(base_path: &Path, notes: &[Note], ignore: &IgnoreRules) -> Vec<PathBuf> {
+    let index = link_index(base_path, notes);
+    let linked_names: BTreeSet<_> = index.keys().filter_map(|p| p.file_name()).collect();
+
+    list_attachments(base_path, ignore)
+        .into_iter()
+        .filter(|path| {
+            !index.contains_key(path) && !path.file_name().is_some_and(|n| linked_names.contains(n))
+        })
+        .collect()
+}
+
+// image::x.png[], link:x.pdf[] and include::x.adoc[] targets
+pub fn adoc_targets(line: &str) -> Vec<String> {
+    let mut targets = Vec::new();
+    for macro_name in ["image::", "image:", "link:", "include::"] {
+        let mut rest = line;
+        while let Some(start) = rest.find(macro_name) {
+            let after = &rest[start + macro_name.len()..];
+            rest = after;
+            // image:: also matches image:, skip the overlap
+            if after.starts_with(':') {
+                continue;
+            }
+            if let Some(end) = after.find('[') {
+                let target = &after[..end];
+                if !target.is_empty() && !target.contains("://") && !target.contains(' ') {
+                    targets.push(target.to_string());
+                }
+            }
+        }
+    }
+    targets.sort();
+    targets.dedup();
+    targets
+}
+
+// resolve . and .. without touching the file system
+fn normalize(path: &Path) -> PathBuf {
+    let mut out = PathBuf::new();
+    for component in path.components() {
+        match component {
+            Component::CurDir => {}
+            Component::ParentDir => {
+                out.pop();
+            }
+            other => out.push(other),
+        }
+    }
+    out
+}
+
+// path of `to` as seen from the folder `from`
+fn relative_path(from: &Path, to: &Path) -> PathBuf {
+    let from: Vec<_> = from.components().collect();
+    let to_parts: Vec<_> = to.components().collect();
+    let common = from
+        .iter()
+        .zip(&to_parts)
+        .take_while(|(a, b)| a == b)
+        .count();
+
+    let mut rel = PathBuf::new();
+    for _ in common..from.len() {
+        rel.push("..");
+    }
+    for part in &to_parts[common..] {
+        rel.push(part);
+    }
+    rel
+}
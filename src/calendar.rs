@@ -0,0 +1,87 @@
+use crate::config::Config;
+use crate::data::{self, Note};
+use chrono::{DateTime, Datelike, Local, Months, NaiveDate};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+// notes by the day they belong to
+pub fn note_dates(
+    base_path: &Path,
+    notes: &[Note],
+    config: &Config,
+) -> BTreeMap<NaiveDate, Vec<PathBuf>> {
+    let mut days: BTreeMap<NaiveDate, Vec<PathBuf>> = BTreeMap::new();
+    for note in notes {
+        days.entry(note_date(base_path, note, config))
+            .or_default()
+            .push(note.path.clone());
+    }
+    for paths in days.values_mut() {
+        paths.sort();
+    }
+    days
+}
+
+// the date in the file name, else the frontmatter date, else when it was modified
+pub fn note_date(base_path: &Path, note: &Note, config: &Config) -> NaiveDate {
+    filename_date(base_path, &note.path, config)
+        .or_else(|| frontmatter_date(&note.path))
+        .unwrap_or_else(|| DateTime::<Local>::from(note.last_modified).date_naive())
+}
+
+// journal notes by journal_format, other notes by a 2026-10-18 in the name
+fn filename_date(base_path: &Path, path: &Path, config: &Config) -> Option<NaiveDate> {
+    let dir = config.journal_dir.as_deref().unwrap_or("journal");
+    let format = config.journal_format.as_deref().unwrap_or("%Y-%m-%d");
+    if let Ok(rel) = path.strip_prefix(base_path.join(dir))
+        && let Ok(date) =
+            NaiveDate::parse_from_str(&rel.with_extension("").to_string_lossy(), format)
+    {
+        return Some(date);
+    }
+
+    let stem = path.file_stem()?.to_str()?;
+    stem.char_indices()
+        .filter_map(|(i, _)| stem.get(i..i + 10))
+        // chrono skips spaces, so " 2026-10-1" would pass as the 1st
+        .filter(|s| s.bytes().all(|b| b.is_ascii_digit() || b == b'-'))
+        .find_map(|s| NaiveDate::parse_from_str(s, "%Y-%m-%d").ok())
+}
+
+// `date: 2026-10-18`, with or without a time after it
+fn frontmatter_date(path: &Path) -> Option<NaiveDate> {
+    let frontmatter = data::read_frontmatter(&path.to_path_buf()).ok()??;
+    let date = frontmatter.get("date")?.as_str()?;
+    NaiveDate::parse_from_str(date.get(..10)?, "%Y-%m-%d").ok()
+}
+
+// the month around a date as weeks from monday to sunday
+pub fn month_weeks(date: NaiveDate) -> Vec<[Option<NaiveDate>; 7]> {
+    let mut weeks = Vec::new();
+    let mut week = [None; 7];
+    let mut day = date.with_day(1);
+    while let Some(d) = day.filter(|d| d.month() == date.month()) {
+        let col = d.weekday().num_days_from_monday() as usize;
+        week[col] = Some(d);
+        if col == 6 {
+            weeks.push(week);
+            week = [None; 7];
+        }
+        day = d.succ_opt();
+    }
+    if week.iter().any(Option::is_some) {
+        weeks.push(week);
+    }
+    weeks
+}
+
+// same day some months away, or the month's last day when it is shorter
+pub fn add_months(date: NaiveDate, months: i32) -> NaiveDate {
+    let delta = Months::new(months.unsigned_abs());
+    let moved = if months < 0 {
+        date.checked_sub_months(delta)
+    } else {
+        date.checked_add_months(delta)
+    };
+    moved.unwrap_or(date)
+}
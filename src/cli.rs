@@ -0,0 +1,644 @@
+use crate::config::{self, Config, Severity};
+use crate::data::{self, Note};
+use crate::export::{
+    self, AttachmentRecord, FindingRecord, NoteRecord, OutputFormat, TagRecord, TaskRecord,
+};
+use crate::tasks::{self, TaskFilter};
+use crate::{attachments, doctor, logging, ops, search, sync};
+use anyhow::{Result, anyhow, bail};
+use chrono::Local;
+use log::LevelFilter;
+use std::collections::{BTreeMap, BTreeSet};
+use std::fs;
+use std::io::{self, IsTerminal};
+use std::path::{Path, PathBuf};
+
+pub const USAGE: &str = "\
+kiroku - terminal journaling and note-taking
+
+Usage:
+  kiroku [--vault <name|path>]            Open the TUI
+  kiroku [--vault <name|path>] <command>
+
+Commands:
+  new <title> [--tag <tag>]... [--folder <dir>]
+                                          Create a note and print its path
+  list [--tag <tag>] [--json|--jsonl]     List notes, newest first
+  search <query> [--json|--jsonl]         Search note titles and content
+  open <title>                            Open a note in your editor
+  today                                   Open (or create) today's journal note
+  capture [text] [--to <note>]            Append a timestamped entry to today's journal
+                                          or another note; reads stdin when no text is given
+  sync                                    Sync the notebook with the configured backend
+  tags [--json|--jsonl]                   List tags with their note counts
+  attachments [--orphans] [--json|--jsonl]
+                                          List non-note files and the notes linking to
+                                          them; --orphans keeps only unlinked ones
+  tasks [--open|--overdue|--done|--all] [--json|--jsonl]
+                                          List checkbox tasks from all notes with their
+                                          due dates (default: --open)
+  check [--json|--jsonl]                  Check notes for broken links, missing attachments,
+                                          bad frontmatter, duplicate titles and empty or
+                                          unreadable files; fails when there are errors
+  config check                            Validate the config files in use
+  config path                             Print the config file location
+  log                                     Open the log file in your editor
+  log path                                Print the log file location
+
+Environment:
+  KIROKU_CONFIG                           Config file to use instead of the default
+  XDG_CONFIG_HOME                         Base directory for the default config
+                                          ($XDG_CONFIG_HOME/kiroku/config.toml)
+  KIROKU_LOG                              Log level when --log-level is not given
+  XDG_STATE_HOME                          Base directory for the log file
+                                          ($XDG_STATE_HOME/kiroku/kiroku.log)
+
+Options:
+  --vault <name|path>                     Configured vault or notebook directory
+                                          (default: last used vault, or ~/kiroku)
+  --log-level <level>                     off, error, warn, info, debug or trace
+                                          (default: info)
+  -h, --help                              Show this help
+
+Options go before the command; after it, `--` marks the rest as text.
+";
+
+#[derive(Debug, PartialEq)]
+pub enum Command {
+    Tui,
+    New {
+        title: String,
+        tags: Vec<String>,
+        folder: Option<String>,
+    },
+    List {
+        tag: Option<String>,
+        format: OutputFormat,
+    },
+    Search {
+        query: String,
+        format: OutputFormat,
+    },
+    Open {
+        title: String,
+    },
+    Today,
+    Capture {
+        text: Option<String>,
+        to: Option<String>,
+    },
+    Sync,
+    Tags {
+        format: OutputFormat,
+    },
+    Attachments {
+        orphans: bool,
+        format: OutputFormat,
+    },
+    Tasks {
+        filter: TaskFilter,
+        format: OutputFormat,
+    },
+    Check {
+        format: OutputFormat,
+    },
+    Config(ConfigCommand),
+    Log(LogCommand),
+    Help,
+}
+
+#[derive(Debug, PartialEq)]
+pub enum ConfigCommand {
+    Check,
+    Path,
+}
+
+#[derive(Debug, PartialEq)]
+pub enum LogCommand {
+    Open,
+    Path,
+}
+
+#[derive(Debug, PartialEq)]
+pub struct Cli {
+    pub vault: Option<PathBuf>,
+    pub log_level: Option<LevelFilter>,
+    pub command: Command,
+}
+
+// flags and positionals after the subcommand
+#[derive(Default)]
+struct Args {
+    positional: Vec<String>,
+    tags: Vec<String>,
+    folder: Option<String>,
+    to: Option<String>,
+    format: Option<OutputFormat>,
+    orphans: bool,
+    filter: Option<TaskFilter>,
+}
+
+impl Args {
+    fn parse(args: Vec<String>, allowed: &[&str]) -> Result<Self> {
+        let mut parsed = Args::default();
+        let mut iter = args.into_iter();
+
+        while let Some(arg) = iter.next() {
+            // `--` ends the options, what follows is text even if it looks like one
+            if arg == "--" {
+                parsed.positional.extend(iter.by_ref());
+                break;
+            }
+            let Some(flag) = arg.strip_prefix("--") else {
+                parsed.positional.push(arg);
+                continue;
+            };
+
+            if !allowed.contains(&flag) {
+                bail!("unexpected option '--{}'", flag);
+            }
+
+            match flag {
+                "json" => parsed.format = Some(OutputFormat::Json),
+                "jsonl" => parsed.format = Some(OutputFormat::JsonLines),
+                "orphans" => parsed.orphans = true,
+                "open" | "overdue" | "done" | "all" => parsed.filter = TaskFilter::from_name(flag),
+                "tag" => parsed
+                    .tags
+                    .push(iter.next().ok_or_else(|| anyhow!("--tag needs a value"))?),
+                "folder" => {
+                    parsed.folder = Some(
+                        iter.next()
+                            .ok_or_else(|| anyhow!("--folder needs a value"))?,
+                    )
+                }
+                "to" => parsed.to = Some(iter.next().ok_or_else(|| anyhow!("--to needs a note"))?),
+                _ => unreachable!(),
+            }
+        }
+
+        Ok(parsed)
+    }
+
+    // the single required positional argument
+    fn one(self, what: &str) -> Result<String> {
+        if self.positional.is_empty() {
+            bail!("missing {}", what);
+        }
+        Ok(self.positional.join(" "))
+    }
+
+    fn format(&self) -> OutputFormat {
+        self.format.unwrap_or(OutputFormat::Text)
+    }
+
+    fn none(self) -> Result<()> {
+        if let Some(extra) = self.positional.first() {
+            bail!("unexpected argument '{}'", extra);
+        }
+        Ok(())
+    }
+}
+
+// parse command line arguments, without the program name
+pub fn parse_args(args: &[String]) -> Result<Cli> {
+    let mut vault = None;
+    let mut log_level = None;
+    let mut help = false;
+    let mut rest = Vec::new();
+
+    // global options only come before the command, so a title or capture
+    // text is never mistaken for one
+    let mut iter = args.iter();
+    while let Some(arg) = iter.next() {
+        match arg.as_str() {
+            "--" => {
+                rest.extend(iter.by_ref().cloned());
+                break;
+            }
+            "--vault" => {
+                let path = iter.next().ok_or_else(|| anyhow!("--vault needs a path"))?;
+                vault = Some(PathBuf::from(path));
+            }
+            "--log-level" => {
+                let level = iter
+                    .next()
+                    .ok_or_else(|| anyhow!("--log-level needs a level"))?;
+                log_level = Some(logging::parse_level(level)?);
+            }
+            "-h" | "--help" => help = true,
+            _ => {
+                if let Some(path) = arg.strip_prefix("--vault=") {
+                    vault = Some(PathBuf::from(path));
+                } else if let Some(level) = arg.strip_prefix("--log-level=") {
+                    log_level = Some(logging::parse_level(level)?);
+                } else {
+                    rest.push(arg.clone());
+                    rest.extend(iter.by_ref().cloned());
+                    break;
+                }
+            }
+        }
+    }
+
+    if help {
+        return Ok(Cli {
+            vault,
+            log_level,
+            command: Command::Help,
+        });
+    }
+
+    if rest.is_empty() {
+        return Ok(Cli {
+            vault,
+            log_level,
+            command: Command::Tui,
+        });
+    }
+
+    let name = rest.remove(0);
+    let command = match name.as_str() {
+        "new" => {
+            let mut args = Args::parse(rest, &["tag", "folder"])?;
+            let tags = std::mem::take(&mut args.tags);
+            let folder = args.folder.take();
+            Command::New {
+                title: args.one("note title")?,
+                tags,
+                folder,
+            }
+        }
+        "list" => {
+            let mut args = Args::parse(rest, &["tag", "json", "jsonl"])?;
+            let tag = args.tags.pop();
+            let format = args.format();
+            args.none()?;
+            Command::List { tag, format }
+        }
+        "search" => {
+            let args = Args::parse(rest, &["json", "jsonl"])?;
+            let format = args.format();
+            Command::Search {
+                query: args.one("search query")?,
+                format,
+            }
+        }
+        "open" => Command::Open {
+            title: Args::parse(rest, &[])?.one("note title")?,
+        },
+        "today" => {
+            Args::parse(rest, &[])?.none()?;
+            Command::Today
+        }
+        "capture" => {
+            let mut args = Args::parse(rest, &["to"])?;
+            let to = args.to.take();
+            let text = if args.positional.is_empty() {
+                None
+            } else {
+                Some(args.one("text")?)
+            };
+            Command::Capture { text, to }
+        }
+        "sync" => {
+            Args::parse(rest, &[])?.none()?;
+            Command::Sync
+        }
+        "tags" => {
+            let args = Args::parse(rest, &["json", "jsonl"])?;
+            let format = args.format();
+            args.none()?;
+            Command::Tags { format }
+        }
+        "attachments" => {
+            let args = Args::parse(rest, &["orphans", "json", "jsonl"])?;
+            let orphans = args.orphans;
+            let format = args.format();
+            args.none()?;
+            Command::Attachments { orphans, format }
+        }
+        "tasks" => {
+            let args = Args::parse(rest, &["open", "overdue", "done", "all", "json", "jsonl"])?;
+            let filter = args.filter.unwrap_or(TaskFilter::Open);
+            let format = args.format();
+            args.none()?;
+            Command::Tasks { filter, format }
+        }
+        "check" => {
+            let args = Args::parse(rest, &["json", "jsonl"])?;
+            let format = args.format();
+            args.none()?;
+            Command::Check { format }
+        }
+        "config" => {
+            let args = Args::parse(rest, &[])?;
+            let action = match args.positional.first().map(String::as_str) {
+                Some("check") => ConfigCommand::Check,
+                Some("path") => ConfigCommand::Path,
+                Some(other) => bail!("unknown config command '{}' (use check or path)", other),
+                None => bail!("missing config command (check or path)"),
+            };
+            if let Some(extra) = args.positional.get(1) {
+                bail!("unexpected argument '{}'", extra);
+            }
+            Command::Config(action)
+        }
+        "log" => {
+            let args = Args::parse(rest, &[])?;
+            let action = match args.positional.first().map(String::as_str) {
+                Some("path") => LogCommand::Path,
+                Some(other) => bail!("unknown log command '{}' (use path)", other),
+                None => LogCommand::Open,
+            };
+            if let Some(extra) = args.positional.get(1) {
+                bail!("unexpected argument '{}'", extra);
+            }
+            Command::Log(action)
+        }
+        "help" => Command::Help,
+        // legacy form: kiroku <path>, only for paths that look like paths so
+        // a mistyped command doesn't create a notebook
+        _ if rest.is_empty()
+            && vault.is_none()
+            && !name.starts_with('-')
+            && (Path::new(&name).exists() || name.contains(['/', std::path::MAIN_SEPARATOR])) =>
+        {
+            vault = Some(PathBuf::from(name));
+            Command::Tui
+        }
+        _ => bail!("unknown command '{}' (see kiroku --help)", name),
+    };
+
+    Ok(Cli {
+        vault,
+        log_level,
+        command,
+    })
+}
+
+// run a non-interactive command against a notebook
+pub fn run(command: Command, vault: &Path, config: &Config) -> Result<()> {
+    match command {
+        Command::Tui => bail!("the tui is not a scripting command"),
+        Command::Help => print!("{}", USAGE),
+        Command::New {
+            title,
+            tags,
+            folder,
+        } => {
+            let target = match folder {
+                Some(f) => vault.join(f),
+                None => vault.to_path_buf(),
+            };
+            let path = ops::create_note(&target, &title, config)?;
+            let mut content = String::new();
+            if !tags.is_empty() {
+                // json strings are valid yaml, so tags with : or # stay tags
+                let quoted: Vec<String> = tags
+                    .iter()
+                    .map(serde_json::to_string)
+                    .collect::<Result<_, _>>()?;
+                content.push_str(&format!("---\ntags: [{}]\n---\n\n", quoted.join(", ")));
+            }
+            if let Some(body) =
+                ops::note_template(vault, config, &title, Local::now().date_naive())?
+            {
+                content.push_str(&body);
+            }
+            if !content.is_empty() {
+                fs::write(&path, content)?;
+            }
+            println!("{}", path.display());
+        }
+        Command::List { tag, format } => {
+            let mut notes = load(vault, config)?;
+            if let Some(tag) = tag {
+                notes.retain(|n| n.tags.iter().any(|t| t.eq_ignore_ascii_case(&tag)));
+            }
+            print_notes(&notes, format)?;
+        }
+        Command::Search { query, format } => {
+            let notes = load(vault, config)?;
+            let mut results = search::by_title(&notes, &query);
+            for note in search::by_content(&notes, &query) {
+                if !results.iter().any(|n| n.path == note.path) {
+                    results.push(note);
+                }
+            }
+            print_notes(&results, format)?;
+        }
+        Command::Open { title } => {
+            let notes = load(vault, config)?;
+            let note =
+                find_note(&notes, &title).ok_or_else(|| anyhow!("no note matching '{}'", title))?;
+            ops::run_editor(vault, Some(&note.path), config.editor_cmd.as_deref())?;
+        }
+        Command::Today => {
+            let path = ops::ensure_journal_note(vault, config, Local::now().date_naive())?;
+            ops::run_editor(vault, Some(&path), config.editor_cmd.as_deref())?;
+        }
+        Command::Capture { text, to } => {
+            let text = match text {
+                Some(t) => t,
+                None if io::stdin().is_terminal() => bail!("nothing to capture"),
+                None => io::read_to_string(io::stdin())?,
+            };
+            if text.trim().is_empty() {
+                bail!("nothing to capture");
+            }
+
+            let now = Local::now();
+            let path = ops::capture_path(vault, config, to.as_deref(), now.date_naive())?;
+            let format = config.capture_format.as_deref().unwrap_or("- %H:%M {text}");
+            ops::append_to_note(&path, &ops::format_capture(format, &text, now)?)?;
+            println!("{}", path.display());
+        }
+        Command::Sync => {
+            let msg = sync::backend_from_config(config).sync(vault)?;
+            println!("{}", msg);
+        }
+        Command::Config(ConfigCommand::Path) => {
+            println!("{}", config::config_path()?.display());
+        }
+        Command::Config(ConfigCommand::Check) => check_config(vault)?,
+        Command::Attachments { orphans, format } => {
+            let notes = load(vault, config)?;
+            let ignore = data::IgnoreRules::from_config(vault, config);
+            let index = attachments::link_index(vault, &notes);
+
+            let mut records = Vec::new();
+            for path in attachments::list_attachments(vault, &ignore) {
+                // links by bare file name count too, as in the tui
+                let linked_from: BTreeSet<&PathBuf> = index
+                    .iter()
+                    .filter(|(target, _)| {
+                        *target == &path || target.file_name() == path.file_name()
+                    })
+                    .flat_map(|(_, sources)| sources)
+                    .collect();
+                if orphans && !linked_from.is_empty() {
+                    continue;
+                }
+                records.push(AttachmentRecord::new(&path, linked_from));
+            }
+            records.sort_by(|a, b| a.path.cmp(&b.path));
+
+            if format == OutputFormat::Text {
+                for record in &records {
+                    let path = Path::new(&record.path);
+                    let rel = path.strip_prefix(vault).unwrap_or(path);
+                    println!("{}\t{} link(s)", rel.display(), record.linked_from.len());
+                }
+            } else {
+                export::write_records(&mut io::stdout().lock(), "attachments", &records, format)?;
+            }
+        }
+        Command::Tasks { filter, format } => {
+            let today = Local::now().date_naive();
+            let tasks: Vec<_> = tasks::collect(&load(vault, config)?)
+                .into_iter()
+                .filter(|task| filter.matches(task, today))
+                .collect();
+
+            if format == OutputFormat::Text {
+                for task in &tasks {
+                    let rel = task.path.strip_prefix(vault).unwrap_or(&task.path);
+                    let mark = if task.done { "[x]" } else { "[ ]" };
+                    let due = task
+                        .due
+                        .map(|d| format!("  (due {})", d))
+                        .unwrap_or_default();
+                    println!(
+                        "{}:{}: {} {}{}",
+                        rel.display(),
+                        task.line,
+                        mark,
+                        task.text,
+                        due
+                    );
+                }
+            } else {
+                let records: Vec<TaskRecord> = tasks.iter().map(TaskRecord::from_task).collect();
+                export::write_records(&mut io::stdout().lock(), "tasks", &records, format)?;
+            }
+        }
+        Command::Check { format } => {
+            let notes = load(vault, config)?;
+            let ignore = data::IgnoreRules::from_config(vault, config);
+            let findings = doctor::check_vault(vault, &notes, &ignore);
+
+            if format == OutputFormat::Text {
+                for finding in &findings {
+                    println!("{}: {}", finding.location(vault), finding);
+                }
+            } else {
+                let records: Vec<FindingRecord> =
+                    findings.iter().map(FindingRecord::from_finding).collect();
+                export::write_records(&mut io::stdout().lock(), "findings", &records, format)?;
+            }
+
+            let errors = findings
+                .iter()
+                .filter(|f| f.severity == Severity::Error)
+                .count();
+            if errors > 0 {
+                bail!(
+                    "{} error(s), {} warning(s)",
+                    errors,
+                    findings.len() - errors
+                );
+            }
+        }
+        Command::Log(LogCommand::Path) => {
+            println!("{}", logging::log_path()?.display());
+        }
+        Command::Log(LogCommand::Open) => {
+            let path = logging::log_path()?;
+            if !path.exists() {
+                bail!("no log file yet at {}", path.display());
+            }
+            ops::run_editor(vault, Some(&path), config.editor_cmd.as_deref())?;
+        }
+        Command::Tags { format } => {
+            let mut counts: BTreeMap<String, usize> = BTreeMap::new();
+            for note in load(vault, config)? {
+                for tag in note.tags {
+                    *counts.entry(tag).or_default() += 1;
+                }
+            }
+
+            let mut records: Vec<TagRecord> = counts
+                .into_iter()
+                .map(|(tag, count)| TagRecord { tag, count })
+                .collect();
+            records.sort_by_key(|r| std::cmp::Reverse(r.count));
+
+            if format == OutputFormat::Text {
+                for r in records {
+                    println!("{}\t{}", r.tag, r.count);
+                }
+            } else {
+                export::write_records(&mut io::stdout().lock(), "tags", &records, format)?;
+            }
+        }
+    }
+
+    Ok(())
+}
+
+// report problems in the global and vault config files
+fn check_config(vault: &Path) -> Result<()> {
+    let mut errors = 0;
+    let mut warnings = 0;
+
+    for path in [config::config_path()?, config::vault_config_path(vault)] {
+        if !path.exists() {
+            println!("{}: not found, using defaults", path.display());
+            continue;
+        }
+
+        let (_, issues) = config::parse_config(&fs::read_to_string(&path)?);
+        if issues.is_empty() {
+            println!("{}: ok", path.display());
+        }
+        for issue in issues {
+            match issue.severity {
+                Severity::Error => errors += 1,
+                Severity::Warning => warnings += 1,
+            }
+            println!("{}: {}", path.display(), issue);
+        }
+    }
+
+    if errors > 0 {
+        bail!("{} error(s), {} warning(s)", errors, warnings);
+    }
+    Ok(())
+}
+
+fn load(vault: &Path, config: &Config) -> Result<Vec<Note>> {
+    let ignore = data::IgnoreRules::from_config(vault, config);
+    data::load_notes_with(&vault.to_string_lossy(), &ignore)
+}
+
+// exact title first, then best fuzzy match
+pub fn find_note<'a>(notes: &'a [Note], title: &str) -> Option<&'a Note> {
+    if let Some(note) = notes.iter().find(|n| n.title.eq_ignore_ascii_case(title)) {
+        return Some(note);
+    }
+    let best = search::by_title(notes, title).into_iter().next()?;
+    notes.iter().find(|n| n.path == best.path)
+}
+
+fn print_notes(notes: &[Note], format: OutputFormat) -> Result<()> {
+    if format == OutputFormat::Text {
+        for note in notes {
+            println!("{}", note.title);
+        }
+        return Ok(());
+    }
+
+    let records: Vec<NoteRecord> = notes.iter().map(NoteRecord::from_note).collect();
+    export::write_records(&mut io::stdout().lock(), "notes", &records, format)
+}
@@ -1,23 +1,112 @@
+use crate::keys::KeySpec;
 use anyhow::Result;
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 use std::fs;
+use std::path::{Component, Path, PathBuf};
 
 // config options
 #[derive(Debug, Deserialize, Serialize, Clone)]
 pub struct Config {
     pub editor_cmd: Option<String>,
     pub auto_sync: Option<bool>,
+    pub auto_commit_minutes: Option<u64>,
+    pub auto_push_minutes: Option<u64>,
+    pub sync_backend: Option<String>,
+    pub mirror_path: Option<String>,
+    pub journal_dir: Option<String>,
+    pub journal_format: Option<String>,
+    pub capture_target: Option<String>,
+    pub capture_format: Option<String>,
     pub theme: Option<Theme>,
+    pub color_mode: Option<String>,
+    pub mouse: Option<bool>,
+    pub note_template: Option<String>,
+    pub journal_template: Option<String>,
+    pub ignore: Option<Vec<String>>,
+    pub respect_gitignore: Option<bool>,
+    pub note_extensions: Option<Vec<String>>,
+    pub default_extension: Option<String>,
+    pub attachment_dir: Option<String>,
     pub sort_mode: Option<String>,
+    pub keys: Option<BTreeMap<String, KeySpec>>,
+    pub saved_searches: Option<Vec<SavedSearch>>,
+    pub vaults: Option<Vec<Vault>>,
 }
 
-#[derive(Debug, Deserialize, Serialize, Clone)]
+// a named notebook; its settings override the global ones
+#[derive(Debug, Deserialize, Serialize, Clone, Default, PartialEq)]
+pub struct Vault {
+    pub name: String,
+    pub path: String,
+    pub editor_cmd: Option<String>,
+    pub auto_sync: Option<bool>,
+    pub auto_commit_minutes: Option<u64>,
+    pub auto_push_minutes: Option<u64>,
+    pub sync_backend: Option<String>,
+    pub mirror_path: Option<String>,
+    pub journal_dir: Option<String>,
+    pub journal_format: Option<String>,
+    pub capture_target: Option<String>,
+    pub capture_format: Option<String>,
+    pub sort_mode: Option<String>,
+}
+
+impl Vault {
+    pub fn path(&self) -> PathBuf {
+        expand_home(&self.path)
+    }
+}
+
+// a named search offered in the command palette
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq)]
+pub struct SavedSearch {
+    pub name: String,
+    pub query: String,
+    // "title" (default), "content" or "tag"
+    pub kind: Option<String>,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, Default)]
 pub struct Theme {
+    // built-in theme to start from
+    pub base: Option<String>,
     pub accent: Option<String>,
     pub selection: Option<String>,
+    pub selection_fg: Option<String>,
     pub header: Option<String>,
     pub dim: Option<String>,
     pub bold: Option<String>,
+    pub border: Option<String>,
+    pub folder: Option<String>,
+    pub quote: Option<String>,
+    pub code: Option<String>,
+    pub link: Option<String>,
+    pub tag: Option<String>,
+    pub status: Option<String>,
+    pub status_bg: Option<String>,
+}
+
+impl Theme {
+    // color slots by name
+    pub fn slots(&self) -> [(&'static str, &Option<String>); 14] {
+        [
+            ("accent", &self.accent),
+            ("selection", &self.selection),
+            ("selection_fg", &self.selection_fg),
+            ("header", &self.header),
+            ("dim", &self.dim),
+            ("bold", &self.bold),
+            ("border", &self.border),
+            ("folder", &self.folder),
+            ("quote", &self.quote),
+            ("code", &self.code),
+            ("link", &self.link),
+            ("tag", &self.tag),
+            ("status", &self.status),
+            ("status_bg", &self.status_bg),
+        ]
+    }
 }
 
 impl Default for Config {
@@ -25,13 +114,222 @@ impl Default for Config {
         Self {
             editor_cmd: None,
             auto_sync: Some(false),
+            auto_commit_minutes: None,
+            auto_push_minutes: None,
+            sync_backend: None,
+            mirror_path: None,
+            journal_dir: None,
+            journal_format: None,
+            capture_target: None,
+            capture_format: None,
+            note_template: None,
+            journal_template: None,
+            ignore: None,
+            respect_gitignore: None,
+            note_extensions: None,
+            default_extension: None,
+            attachment_dir: None,
             theme: None,
+            color_mode: None,
+            mouse: None,
             sort_mode: Some("Date".to_string()),
+            keys: None,
+            saved_searches: None,
+            vaults: None,
+        }
+    }
+}
+
+impl Config {
+    // configured vault by name, ignoring case
+    pub fn vault(&self, name: &str) -> Option<&Vault> {
+        self.vaults
+            .iter()
+            .flatten()
+            .find(|v| v.name.eq_ignore_ascii_case(name))
+    }
+
+    // global settings with a vault's overrides applied
+    pub fn for_vault(&self, vault: &Vault) -> Config {
+        let mut config = self.clone();
+        let over = |global: &mut Option<String>, value: &Option<String>| {
+            if value.is_some() {
+                *global = value.clone();
+            }
+        };
+
+        over(&mut config.editor_cmd, &vault.editor_cmd);
+        over(&mut config.sync_backend, &vault.sync_backend);
+        over(&mut config.mirror_path, &vault.mirror_path);
+        over(&mut config.journal_dir, &vault.journal_dir);
+        over(&mut config.journal_format, &vault.journal_format);
+        over(&mut config.capture_target, &vault.capture_target);
+        over(&mut config.capture_format, &vault.capture_format);
+        over(&mut config.sort_mode, &vault.sort_mode);
+        config.auto_sync = vault.auto_sync.or(config.auto_sync);
+        config.auto_commit_minutes = vault.auto_commit_minutes.or(config.auto_commit_minutes);
+        config.auto_push_minutes = vault.auto_push_minutes.or(config.auto_push_minutes);
+        config
+    }
+
+    // apply settings from a vault's own .kiroku/config.toml
+    pub fn merge_vault_file(&mut self, mut local: Config) {
+        fn take<T>(global: &mut Option<T>, local: Option<T>) {
+            if local.is_some() {
+                *global = local;
+            }
+        }
+
+        // the file travels with the notes, so it may not pick programs to run
+        // or where the notes are copied to and from
+        for (field, set) in [
+            ("editor_cmd", local.editor_cmd.is_some()),
+            ("sync_backend", local.sync_backend.is_some()),
+            ("mirror_path", local.mirror_path.is_some()),
+        ] {
+            if set {
+                log::warn!(
+                    "Ignoring {} in vault config, set it in your own config instead",
+                    field
+                );
+            }
+        }
+
+        // nor write notes or attachments outside the notebook
+        for (field, value) in [
+            ("journal_dir", &mut local.journal_dir),
+            ("journal_format", &mut local.journal_format),
+            ("capture_target", &mut local.capture_target),
+            ("attachment_dir", &mut local.attachment_dir),
+        ] {
+            if let Some(path) = value.take_if(|p| !stays_inside(p)) {
+                log::warn!(
+                    "Ignoring {} in vault config, {:?} is outside the notebook",
+                    field,
+                    path
+                );
+            }
+        }
+
+        take(&mut self.auto_sync, local.auto_sync);
+        take(&mut self.auto_commit_minutes, local.auto_commit_minutes);
+        take(&mut self.auto_push_minutes, local.auto_push_minutes);
+        take(&mut self.journal_dir, local.journal_dir);
+        take(&mut self.journal_format, local.journal_format);
+        take(&mut self.capture_target, local.capture_target);
+        take(&mut self.capture_format, local.capture_format);
+        take(&mut self.note_template, local.note_template);
+        take(&mut self.journal_template, local.journal_template);
+        take(&mut self.ignore, local.ignore);
+        take(&mut self.respect_gitignore, local.respect_gitignore);
+        take(&mut self.note_extensions, local.note_extensions);
+        take(&mut self.default_extension, local.default_extension);
+        take(&mut self.attachment_dir, local.attachment_dir);
+        take(&mut self.sort_mode, local.sort_mode);
+
+        // shared searches are offered next to your own
+        if let Some(shared) = local.saved_searches {
+            self.saved_searches.get_or_insert_default().extend(shared);
+        }
+    }
+
+    // settings in effect for a notebook. later wins: built-in defaults,
+    // the global config, the vault's .kiroku/config.toml, then the
+    // [[vaults]] entry for it in the global config
+    pub fn effective(&self, vault: Option<&str>, path: &Path) -> Config {
+        let mut config = self.clone();
+        match load_vault_config(path) {
+            Ok(Some(local)) => config.merge_vault_file(local),
+            Ok(None) => {}
+            Err(e) => log::warn!("Failed to load vault config: {}", e),
+        }
+
+        match vault.and_then(|name| self.vault(name)) {
+            Some(v) => config.for_vault(v),
+            None => config,
+        }
+    }
+
+    // pick the notebook to open: --vault (a name or a path), then the
+    // last used vault, then the first configured one, then ~/kiroku
+    pub fn resolve_vault(
+        &self,
+        arg: Option<&Path>,
+        last_vault: Option<&str>,
+    ) -> Result<(Option<String>, PathBuf)> {
+        if let Some(arg) = arg {
+            if let Some(v) = arg.to_str().and_then(|name| self.vault(name)) {
+                return Ok((Some(v.name.clone()), v.path()));
+            }
+            return Ok((None, arg.to_path_buf()));
         }
+
+        let remembered = last_vault.and_then(|name| self.vault(name));
+        if let Some(v) = remembered.or(self.vaults.iter().flatten().next()) {
+            return Ok((Some(v.name.clone()), v.path()));
+        }
+
+        let home_dir =
+            dirs::home_dir().ok_or_else(|| anyhow::anyhow!("could not find home directory"))?;
+        Ok((None, home_dir.join("kiroku")))
+    }
+
+    // extensions read as notes, lowercase and without the dot
+    pub fn note_extensions(&self) -> Vec<String> {
+        let extensions: Vec<String> = self
+            .note_extensions
+            .iter()
+            .flatten()
+            .map(|e| normalize_extension(e))
+            .filter(|e| !e.is_empty())
+            .collect();
+        if extensions.is_empty() {
+            vec![crate::data::DEFAULT_EXTENSION.to_string()]
+        } else {
+            extensions
+        }
+    }
+
+    // extension given to new notes: default_extension, else the first
+    // of note_extensions
+    pub fn default_extension(&self) -> String {
+        self.default_extension
+            .as_deref()
+            .map(normalize_extension)
+            .filter(|e| !e.is_empty())
+            .unwrap_or_else(|| self.note_extensions().remove(0))
     }
 }
 
-const DEFAULT_CONFIG: &str = r##"# Kiroku Configuration
+fn normalize_extension(ext: &str) -> String {
+    ext.trim().trim_start_matches('.').to_lowercase()
+}
+
+// expand a leading ~ to the home directory
+// a relative path that never climbs out of the folder it is joined to
+pub fn stays_inside(path: &str) -> bool {
+    let mut depth = 0i32;
+    for component in Path::new(path).components() {
+        match component {
+            Component::Normal(_) => depth += 1,
+            Component::CurDir => {}
+            Component::ParentDir if depth > 0 => depth -= 1,
+            _ => return false,
+        }
+    }
+    true
+}
+
+pub fn expand_home(path: &str) -> PathBuf {
+    if (path == "~" || path.starts_with("~/"))
+        && let Some(home) = dirs::home_dir()
+    {
+        return home.join(path.trim_start_matches('~').trim_start_matches('/'));
+    }
+    PathBuf::from(path)
+}
+
+pub const DEFAULT_CONFIG: &str = r##"# Kiroku Configuration
 
 # Optional: Command to open your text editor.
 # Examples: "vim", "nano", "code --wait", "nvim"
@@ -41,48 +339,471 @@ const DEFAULT_CONFIG: &str = r##"# Kiroku Configuration
 # Default is false.
 auto_sync = false
 
+# Optional: Commit changes in the background after this many minutes
+# without further edits, so a burst of saves ends up in a single commit.
+# auto_commit_minutes = 5
+
+# Optional: Push to the remote in the background every this many minutes.
+# auto_push_minutes = 30
+
+# Optional: How to sync the notebook.
+# "git" (default) commits and pushes; "mirror" keeps a two-way copy of the
+# notebook in mirror_path, e.g. a mounted drive, and reports conflicts.
+# sync_backend = "git"
+# mirror_path = "/mnt/backup/kiroku"
+
+# Optional: Where daily journal notes live, relative to the notebook,
+# and the strftime pattern used for their file names.
+# journal_dir = "journal"
+# journal_format = "%Y-%m-%d"
+
+# Optional: Where `kiroku capture` appends entries: "journal" for today's
+# journal note, or a note path such as "inbox.md".
+# capture_target = "journal"
+
+# Optional: Format of captured entries. strftime codes are expanded and
+# {text} is replaced by the captured text.
+# capture_format = "- %H:%M {text}"
+
+# Optional: Files whose contents seed new notes and journal notes, relative
+# to the notebook. {title} and {date} (YYYY-MM-DD) are filled in.
+# note_template = ".kiroku/templates/note.md"
+# journal_template = ".kiroku/templates/journal.md"
+
+# Optional: Files and folders to leave out, in .gitignore syntax. Patterns
+# in the notebook's .kirokuignore file are applied too; dot-files are always
+# left out.
+# ignore = ["archive/", "*.draft.md"]
+
+# Optional: Also leave out what the notebook's .gitignore ignores.
+# respect_gitignore = false
+
+# Optional: File extensions read as notes (default: ["md"]). Markdown
+# (.md, .markdown), org (.org), AsciiDoc (.adoc) and plain text (.txt) each
+# get their own titles, tags and preview; other extensions show as text.
+# note_extensions = ["md", "markdown", "txt", "org", "adoc"]
+
+# Optional: Extension for new notes (default: the first of note_extensions).
+# default_extension = "md"
+
+# Optional: Where attached files are copied, relative to the notebook.
+# Start with "./" to keep them next to the note; {note} is the note's name.
+# attachment_dir = "attachments"
+# attachment_dir = "./{note}.assets"
+
 # Optional: Default sort mode for notes.
 # Options: "Date", "Name", "Size"
 # sort_mode = "Date"
 
+# Optional: How many colors to use. "auto" (default) checks COLORTERM
+# and TERM and turns colors off when NO_COLOR is set. Force one with
+# "truecolor", "256", "16" or "none" (bold, underline and reverse only).
+# color_mode = "auto"
+
+# Optional: Use the mouse to select, open and scroll, and to follow links
+# in the preview. Turn it off to select text with the mouse as usual.
+# mouse = true
+
 # Optional: Custom Color Theme
-# You can uncomment and customize these hex codes.
+# Colors are "#rrggbb" hex codes or names like "yellow" and "reset".
+# Unset slots come from the base theme ("default", "gruvbox" or
+# "tokyo-night"). Press "t" to cycle between this theme, the built-in
+# ones and any theme files in the themes/ folder next to this file
+# (e.g. themes/nord.toml, using the same keys as below).
 # [theme]
-# accent = "#89dceb"    # Borders and main highlights
-# selection = "#bb9af7" # Selected item in the list
-# header = "#89b4fa"    # Markdown headers
-# dim = "#6c7086"       # Footer and dim text
-# bold = "#f38ba8"      # Bold text and heavy emphasis
+# base = "default"
+# accent = "#89dceb"       # Main highlights
+# selection = "#bb9af7"    # Selected item background
+# selection_fg = "#11111b" # Selected item text
+# header = "#89b4fa"       # Markdown headers
+# dim = "#6c7086"          # Footer and dim text
+# bold = "#f38ba8"         # Bold text and heavy emphasis
+# border = "#89dceb"       # Panel borders (defaults to accent)
+# folder = "#f9e2af"       # Folder icons
+# quote = "#a6e3a1"        # Block quotes
+# code = "#fab387"         # Code blocks and inline code
+# link = "#74c7ec"         # Links
+# tag = "#cba6f7"          # Tags
+# status = "#89dceb"       # Status bar text (defaults to accent)
+# status_bg = "reset"      # Status bar background
+
+# Optional: Key bindings for the browser.
+# Map an action to one key or a list of keys. Keys can have modifiers
+# ("ctrl+p", "alt+x") and be sequences ("gg", "ctrl+x n").
+# Binding an action replaces its default keys; conflicts are reported.
+# Actions: down, up, top, bottom, open, parent, scroll_down, scroll_up,
+# new_note, new_folder, rename, delete, attach, sync, cycle_sort,
+# copy_content, copy_path, search, content_search, tag_search, help,
+# cycle_theme, palette, switch_vault, doctor, tasks, calendar,
+# grow_list, shrink_list, toggle_preview, zen_mode, toggle_logs,
+# open_log, suspend, quit
+# [keys]
+# sync = "S"
+# top = ["gg", "home"]
+# help = ["f1", "ctrl+h"]
+
+# Optional: Searches offered in the command palette (":" or Ctrl+P).
+# kind is "title" (default), "content" or "tag".
+# [[saved_searches]]
+# name = "Work notes"
+# query = "work"
+# kind = "tag"
+
+# Optional: Named vaults, switched with "v" in the browser or opened with
+# `kiroku --vault <name>`. Each vault may override editor_cmd, auto_sync,
+# auto_commit_minutes, auto_push_minutes, sync_backend, mirror_path,
+# journal_dir, journal_format, capture_target, capture_format and sort_mode.
+# The last vault you switched to is opened by default.
+#
+# A vault can also carry shared settings in .kiroku/config.toml, e.g.
+# committed to git for the whole team. It may set auto_sync,
+# auto_commit_minutes, auto_push_minutes, the journal, capture, template,
+# ignore, respect_gitignore, note_extensions, default_extension,
+# attachment_dir and sort_mode options and add saved_searches; its values
+# win over this file, and a [[vaults]] entry here wins over it. Templates
+# must be inside the notebook.
+# [[vaults]]
+# name = "personal"
+# path = "~/kiroku"
+#
+# [[vaults]]
+# name = "work"
+# path = "~/work/notes"
+# sync_backend = "mirror"
+# mirror_path = "/mnt/share/notes"
 "##;
 
-// load config
-pub fn load_config() -> Result<Config> {
+// shared settings kept inside a vault
+pub fn vault_config_path(vault_path: &Path) -> PathBuf {
+    vault_path.join(".kiroku").join("config.toml")
+}
+
+// load a vault's own config, if it has one
+pub fn load_vault_config(vault_path: &Path) -> Result<Option<Config>> {
+    let path = vault_config_path(vault_path);
+    if !path.exists() {
+        return Ok(None);
+    }
+
+    let content = fs::read_to_string(&path)?;
+    let (config, issues) = parse_config(&content);
+    for issue in &issues {
+        log::warn!("{}: {}", path.display(), issue);
+    }
+    match config {
+        Some(config) => Ok(Some(config)),
+        None => anyhow::bail!("{} is invalid", path.display()),
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Severity {
+    Error,
+    Warning,
+}
+
+// a problem found in a config file
+#[derive(Debug, Clone, PartialEq)]
+pub struct ConfigIssue {
+    pub severity: Severity,
+    pub field: String,
+    pub message: String,
+    pub line: Option<usize>,
+}
+
+impl ConfigIssue {
+    pub fn error(field: &str, message: impl Into<String>) -> Self {
+        Self {
+            severity: Severity::Error,
+            field: field.to_string(),
+            message: message.into(),
+            line: None,
+        }
+    }
+
+    pub fn warning(field: &str, message: impl Into<String>) -> Self {
+        Self {
+            severity: Severity::Warning,
+            ..Self::error(field, message)
+        }
+    }
+}
+
+impl std::fmt::Display for ConfigIssue {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let level = match self.severity {
+            Severity::Error => "error",
+            Severity::Warning => "warning",
+        };
+        write!(f, "{}: ", level)?;
+        if let Some(line) = self.line {
+            write!(f, "line {}: ", line)?;
+        }
+        if !self.field.is_empty() {
+            write!(f, "{}: ", self.field)?;
+        }
+        write!(f, "{}", self.message)
+    }
+}
+
+// parse and check config text. the config is none when it cannot be read
+// at all; otherwise invalid values are reported and fall back to defaults
+pub fn parse_config(content: &str) -> (Option<Config>, Vec<ConfigIssue>) {
+    let mut unknown: Vec<String> = Vec::new();
+    // optional values show up as "?" segments in the path
+    let mut track = |path: serde_ignored::Path| {
+        unknown.push(path.to_string().replace("?.", "").replace(".?", ""))
+    };
+    let result: std::result::Result<Config, _> = {
+        let de = toml::Deserializer::new(content);
+        let de = serde_ignored::Deserializer::new(de, &mut track);
+        serde_path_to_error::deserialize(de)
+    };
+
+    let mut issues: Vec<ConfigIssue> = unknown
+        .into_iter()
+        .map(|key| ConfigIssue::warning(&key, "unknown key, ignored"))
+        .collect();
+
+    match result {
+        Ok(mut config) => {
+            let invalid = config.validate();
+            config.drop_invalid(&invalid);
+            issues.extend(invalid);
+            (Some(config), issues)
+        }
+        Err(e) => {
+            let field = e.path().to_string();
+            let inner = e.into_inner();
+            let line = inner
+                .span()
+                .map(|span| content[..span.start].matches('\n').count() + 1);
+            issues.push(ConfigIssue {
+                line,
+                ..ConfigIssue::error(if field == "." { "" } else { &field }, inner.message())
+            });
+            (None, issues)
+        }
+    }
+}
+
+fn valid_strftime(format: &str) -> bool {
+    chrono::format::StrftimeItems::new(format)
+        .all(|item| !matches!(item, chrono::format::Item::Error))
+}
+
+impl Config {
+    // forget the values validate() rejected so the defaults apply
+    fn drop_invalid(&mut self, issues: &[ConfigIssue]) {
+        for issue in issues.iter().filter(|i| i.severity == Severity::Error) {
+            match issue.field.as_str() {
+                "sort_mode" => self.sort_mode = None,
+                "sync_backend" => self.sync_backend = None,
+                "journal_format" => self.journal_format = None,
+                "capture_format" => self.capture_format = None,
+                "color_mode" => self.color_mode = None,
+                "theme.base" => {
+                    if let Some(theme) = &mut self.theme {
+                        theme.base = None;
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+
+    // values that parse but cannot be used
+    pub fn validate(&self) -> Vec<ConfigIssue> {
+        let mut issues = Vec::new();
+
+        if let Some(mode) = &self.sort_mode
+            && !["Date", "Name", "Size"].contains(&mode.as_str())
+        {
+            issues.push(ConfigIssue::error(
+                "sort_mode",
+                format!("'{}' is not one of \"Date\", \"Name\", \"Size\"", mode),
+            ));
+        }
+
+        match self.sync_backend.as_deref() {
+            None | Some("git") => {}
+            Some("mirror") if self.mirror_path.is_none() => issues.push(ConfigIssue::error(
+                "mirror_path",
+                "required when sync_backend is \"mirror\"",
+            )),
+            Some("mirror") => {}
+            Some(other) => issues.push(ConfigIssue::error(
+                "sync_backend",
+                format!("'{}' is not one of \"git\", \"mirror\"", other),
+            )),
+        }
+
+        for (field, format) in [
+            ("journal_format", &self.journal_format),
+            ("capture_format", &self.capture_format),
+        ] {
+            if let Some(format) = format
+                && !valid_strftime(format)
+            {
+                issues.push(ConfigIssue::error(
+                    field,
+                    format!("'{}' is not a valid strftime pattern", format),
+                ));
+            }
+        }
+
+        if let Some(theme) = &self.theme {
+            for (slot, value) in theme.slots() {
+                if let Some(color) = value
+                    && crate::theme::parse_color(color).is_none()
+                {
+                    issues.push(ConfigIssue::error(
+                        &format!("theme.{}", slot),
+                        format!("'{}' is not a color (use \"#rrggbb\" or a name)", color),
+                    ));
+                }
+            }
+            if let Some(base) = &theme.base
+                && crate::theme::ThemeColors::builtin(base).is_none()
+            {
+                issues.push(ConfigIssue::error(
+                    "theme.base",
+                    format!(
+                        "'{}' is not one of {}",
+                        base,
+                        crate::theme::BUILTIN_THEMES.join(", ")
+                    ),
+                ));
+            }
+        }
+
+        if let Some(mode) = &self.color_mode
+            && !crate::theme::COLOR_MODES.contains(&mode.to_lowercase().as_str())
+        {
+            issues.push(ConfigIssue::error(
+                "color_mode",
+                format!(
+                    "'{}' is not one of {}",
+                    mode,
+                    crate::theme::COLOR_MODES.join(", ")
+                ),
+            ));
+        }
+
+        if let Some(ext) = &self.default_extension
+            && !self.note_extensions().contains(&normalize_extension(ext))
+        {
+            issues.push(ConfigIssue::warning(
+                "default_extension",
+                format!(
+                    "'{}' is not in note_extensions, so new notes will not be listed",
+                    ext
+                ),
+            ));
+        }
+
+        for pattern in self.ignore.iter().flatten() {
+            if let Err(e) = ignore::gitignore::GitignoreBuilder::new("").add_line(None, pattern) {
+                issues.push(ConfigIssue::warning("ignore", e.to_string()));
+            }
+        }
+
+        let (_, key_warnings) = crate::keys::Keymap::from_config(self.keys.as_ref());
+        issues.extend(
+            key_warnings
+                .into_iter()
+                .map(|w| ConfigIssue::warning("keys", w)),
+        );
+
+        for (i, search) in self.saved_searches.iter().flatten().enumerate() {
+            if let Some(kind) = &search.kind
+                && !["title", "content", "tag"].contains(&kind.to_lowercase().as_str())
+            {
+                issues.push(ConfigIssue::warning(
+                    &format!("saved_searches[{}].kind", i),
+                    format!("'{}' is not one of title, content, tag", kind),
+                ));
+            }
+        }
+
+        let mut names = Vec::new();
+        for (i, vault) in self.vaults.iter().flatten().enumerate() {
+            let field = format!("vaults[{}]", i);
+            if vault.name.trim().is_empty() {
+                issues.push(ConfigIssue::error(&field, "name is empty"));
+            } else if names.contains(&vault.name.to_lowercase()) {
+                issues.push(ConfigIssue::error(
+                    &field,
+                    format!("duplicate vault name '{}'", vault.name),
+                ));
+            }
+            names.push(vault.name.to_lowercase());
+            if vault.path.trim().is_empty() {
+                issues.push(ConfigIssue::error(&field, "path is empty"));
+            }
+        }
+
+        issues
+    }
+}
+
+// directory holding kiroku's config, honoring XDG_CONFIG_HOME
+pub fn config_dir() -> Result<PathBuf> {
+    if let Some(xdg) = std::env::var_os("XDG_CONFIG_HOME").map(PathBuf::from)
+        && xdg.is_absolute()
+    {
+        return Ok(xdg.join("kiroku"));
+    }
     let home_dir =
         dirs::home_dir().ok_or_else(|| anyhow::anyhow!("Could not find home directory"))?;
-    let config_path = home_dir.join(".config").join("kiroku").join("config.toml");
+    Ok(home_dir.join(".config").join("kiroku"))
+}
+
+// config file in use; KIROKU_CONFIG overrides the default location
+pub fn config_path() -> Result<PathBuf> {
+    match std::env::var_os("KIROKU_CONFIG") {
+        Some(path) if !path.is_empty() => Ok(PathBuf::from(path)),
+        _ => Ok(config_dir()?.join("config.toml")),
+    }
+}
+
+// load config and everything wrong with it. an unreadable file yields
+// the defaults plus the error that made it unreadable
+pub fn load_config_checked() -> Result<(Config, Vec<ConfigIssue>)> {
+    let config_path = config_path()?;
 
     if !config_path.exists() {
+        if std::env::var_os("KIROKU_CONFIG").is_some_and(|p| !p.is_empty()) {
+            anyhow::bail!(
+                "config file {} (from KIROKU_CONFIG) does not exist",
+                config_path.display()
+            );
+        }
         if let Some(parent) = config_path.parent() {
             fs::create_dir_all(parent)?;
         }
         fs::write(&config_path, DEFAULT_CONFIG)?;
-        return Ok(Config::default());
+        return Ok((Config::default(), Vec::new()));
     }
 
     let content = fs::read_to_string(&config_path)?;
-    let config: Config = toml::from_str(&content)?;
-
-    Ok(config)
+    let (config, issues) = parse_config(&content);
+    Ok((config.unwrap_or_default(), issues))
 }
 
-// save config
-pub fn save_config(config: &Config) -> Result<()> {
-    let home_dir =
-        dirs::home_dir().ok_or_else(|| anyhow::anyhow!("Could not find home directory"))?;
-    let config_path = home_dir.join(".config").join("kiroku").join("config.toml");
-
-    let content = toml::to_string_pretty(config)?;
-    fs::write(config_path, content)?;
+// load config, failing if the file cannot be parsed
+pub fn load_config() -> Result<Config> {
+    let config_path = config_path()?;
+    if !config_path.exists() {
+        return Ok(load_config_checked()?.0);
+    }
 
-    Ok(())
+    let content = fs::read_to_string(&config_path)?;
+    match parse_config(&content) {
+        (Some(config), _) => Ok(config),
+        (None, issues) => {
+            let errors: Vec<String> = issues.iter().map(|i| i.to_string()).collect();
+            anyhow::bail!("{}: {}", config_path.display(), errors.join("; "))
+        }
+    }
 }
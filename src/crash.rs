@@ -0,0 +1,61 @@
+use crate::{logging, ops};
+use anyhow::Result;
+use std::backtrace::Backtrace;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+// where crash reports are kept
+pub fn crash_dir() -> Result<PathBuf> {
+    let data_dir =
+        dirs::data_local_dir().ok_or_else(|| anyhow::anyhow!("could not find data directory"))?;
+    Ok(data_dir.join("kiroku").join("crashes"))
+}
+
+// on panic, give the terminal back and leave a report behind
+pub fn install_panic_hook() {
+    std::panic::set_hook(Box::new(|info| {
+        let _ = ops::suspend_tui(true);
+
+        let message = info.to_string();
+        let backtrace = Backtrace::force_capture().to_string();
+        eprintln!("kiroku crashed: {}", message);
+
+        let recent = logging::recent_lines();
+        let written =
+            crash_dir().and_then(|dir| write_report(&dir, &message, &backtrace, recent.as_deref()));
+        match written {
+            Ok(path) => eprintln!("A crash report was written to {}", path.display()),
+            Err(e) => eprintln!("Could not write a crash report ({}):\n{}", e, backtrace),
+        }
+
+        // a panic off the main thread would leave the ui running blind
+        std::process::exit(101);
+    }));
+}
+
+// write a crash report and return its path
+pub fn write_report(
+    dir: &Path,
+    message: &str,
+    backtrace: &str,
+    log_lines: Option<&[String]>,
+) -> Result<PathBuf> {
+    fs::create_dir_all(dir)?;
+    let now = chrono::Local::now();
+    let path = dir.join(format!("crash-{}.txt", now.format("%Y%m%d-%H%M%S")));
+
+    let mut report = format!(
+        "kiroku {} crash report\ntime: {}\nplatform: {} {}\n\n{}\n\nbacktrace:\n{}\n",
+        env!("CARGO_PKG_VERSION"),
+        now.to_rfc3339(),
+        std::env::consts::OS,
+        std::env::consts::ARCH,
+        message,
+        backtrace
+    );
+    if let Some(lines) = log_lines {
+        report.push_str(&format!("\nrecent log:\n{}\n", lines.join("\n")));
+    }
+    fs::write(&path, report)?;
+    Ok(path)
+}
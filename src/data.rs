@@ -1,9 +1,11 @@
+use crate::config::Config;
 use anyhow::{Context, Result};
+use ignore::gitignore::{Gitignore, GitignoreBuilder};
 use log::warn;
 use serde::Deserialize;
 use std::fs;
 use std::io::{BufRead, BufReader};
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::time::SystemTime;
 use walkdir::WalkDir;
 
@@ -17,9 +19,72 @@ struct Frontmatter {
 pub enum FileSystemItem {
     Note(Note),
     Folder(PathBuf),
+    Attachment(PathBuf),
 }
 
-// represents a markdown note
+impl FileSystemItem {
+    pub fn path(&self) -> &Path {
+        match self {
+            FileSystemItem::Note(n) => &n.path,
+            FileSystemItem::Folder(p) | FileSystemItem::Attachment(p) => p,
+        }
+    }
+}
+
+// what kind of file an attachment is, by extension
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum AttachmentKind {
+    Image,
+    Pdf,
+    Audio,
+    Video,
+    Archive,
+    Other,
+}
+
+impl AttachmentKind {
+    pub fn from_path(path: &Path) -> Self {
+        let ext = path
+            .extension()
+            .map(|e| e.to_string_lossy().to_ascii_lowercase())
+            .unwrap_or_default();
+        match ext.as_str() {
+            "png" | "jpg" | "jpeg" | "gif" | "webp" | "svg" | "bmp" | "avif" | "heic" => {
+                AttachmentKind::Image
+            }
+            "pdf" => AttachmentKind::Pdf,
+            "mp3" | "ogg" | "flac" | "wav" | "m4a" | "opus" => AttachmentKind::Audio,
+            "mp4" | "mkv" | "webm" | "mov" | "avi" => AttachmentKind::Video,
+            "zip" | "tar" | "gz" | "tgz" | "xz" | "7z" | "rar" => AttachmentKind::Archive,
+            _ => AttachmentKind::Other,
+        }
+    }
+
+    // single-width marker shown in the file browser
+    pub fn icon(self) -> &'static str {
+        match self {
+            AttachmentKind::Image => "▣",
+            AttachmentKind::Pdf => "▤",
+            AttachmentKind::Audio => "♪",
+            AttachmentKind::Video => "▶",
+            AttachmentKind::Archive => "▦",
+            AttachmentKind::Other => "◇",
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            AttachmentKind::Image => "Image",
+            AttachmentKind::Pdf => "PDF",
+            AttachmentKind::Audio => "Audio",
+            AttachmentKind::Video => "Video",
+            AttachmentKind::Archive => "Archive",
+            AttachmentKind::Other => "File",
+        }
+    }
+}
+
+// represents a note file
 #[derive(Debug, Clone, PartialEq)]
 pub struct Note {
     pub path: PathBuf,
@@ -36,13 +101,15 @@ impl Note {
         let metadata = fs::metadata(&path)
             .with_context(|| format!("Failed to get metadata for: {:?}", path))?;
 
-        let relative_path = path.strip_prefix(root).unwrap_or(&path);
-        let title = relative_path
-            .with_extension("")
-            .to_string_lossy()
-            .to_string();
+        let header = read_header(&path, NoteFormat::from_path(&path)).unwrap_or_default();
 
-        let tags = extract_tags(&path).unwrap_or_default();
+        // path-based titles keep their folder, as do titles set in the file
+        let relative_path = path.strip_prefix(root).unwrap_or(&path);
+        let mut title_path = relative_path.with_extension("");
+        if let Some(title) = header.title {
+            title_path.set_file_name(title.replace('/', "-"));
+        }
+        let title = title_path.to_string_lossy().to_string();
 
         Ok(Self {
             path,
@@ -50,19 +117,166 @@ impl Note {
             content: None,
             last_modified: metadata.modified().unwrap_or(SystemTime::now()),
             size: metadata.len(),
-            tags,
+            tags: header.tags,
         })
     }
 }
 
+// how a note file is written, by extension
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum NoteFormat {
+    Markdown,
+    Org,
+    AsciiDoc,
+    Text,
+}
+
+impl NoteFormat {
+    pub fn from_extension(ext: &str) -> Option<Self> {
+        match ext.to_ascii_lowercase().as_str() {
+            "md" | "markdown" | "mdown" | "mkd" => Some(NoteFormat::Markdown),
+            "org" => Some(NoteFormat::Org),
+            "adoc" | "asciidoc" => Some(NoteFormat::AsciiDoc),
+            "txt" | "text" => Some(NoteFormat::Text),
+            _ => None,
+        }
+    }
+
+    // configured extensions kiroku has no reader for are shown as plain text
+    pub fn from_path(path: &Path) -> Self {
+        path.extension()
+            .and_then(|e| e.to_str())
+            .and_then(NoteFormat::from_extension)
+            .unwrap_or(NoteFormat::Text)
+    }
+
+    // level of a heading line, 1 for the top
+    pub fn heading_level(self, line: &str) -> Option<usize> {
+        let marker = match self {
+            NoteFormat::Markdown => '#',
+            NoteFormat::Org => '*',
+            NoteFormat::AsciiDoc => '=',
+            NoteFormat::Text => return None,
+        };
+        let level = line.chars().take_while(|&c| c == marker).count();
+        (level > 0 && line[level..].starts_with(' ')).then_some(level)
+    }
+
+    // a line that opens or closes a code or literal block
+    pub fn is_fence(self, line: &str) -> bool {
+        let line = line.trim();
+        match self {
+            NoteFormat::Markdown => line.starts_with("```"),
+            NoteFormat::Org => {
+                let lower = line.to_ascii_lowercase();
+                lower.starts_with("#+begin_src")
+                    || lower.starts_with("#+end_src")
+                    || lower.starts_with("#+begin_example")
+                    || lower.starts_with("#+end_example")
+            }
+            NoteFormat::AsciiDoc => line == "----" || line == "....",
+            NoteFormat::Text => false,
+        }
+    }
+}
+
+// title and tags a note declares about itself
+#[derive(Debug, Default)]
+struct Header {
+    title: Option<String>,
+    tags: Vec<String>,
+}
+
+fn read_header(path: &PathBuf, format: NoteFormat) -> Result<Header> {
+    match format {
+        NoteFormat::Markdown | NoteFormat::Text => Ok(Header {
+            title: None,
+            tags: extract_tags(path)?,
+        }),
+        NoteFormat::Org => read_org_header(path),
+        NoteFormat::AsciiDoc => read_adoc_header(path),
+    }
+}
+
 fn extract_tags(path: &PathBuf) -> Result<Vec<String>> {
+    let Some(frontmatter_content) = read_frontmatter_block(path)? else {
+        return Ok(Vec::new());
+    };
+
+    // parse yaml frontmatter
+    // If it fails, we just assume no valid tags were found in that block
+    let fm: Frontmatter =
+        serde_yaml::from_str(&frontmatter_content).unwrap_or(Frontmatter { tags: Vec::new() });
+    Ok(fm.tags)
+}
+
+// #+title: and #+filetags: :a:b: keywords at the top of an org file
+fn read_org_header(path: &PathBuf) -> Result<Header> {
+    let mut header = Header::default();
+    for line in BufReader::new(fs::File::open(path)?).lines() {
+        let line = line?;
+        let line = line.trim();
+        if line.is_empty() {
+            continue;
+        }
+        let Some((key, value)) = line.strip_prefix("#+").and_then(|k| k.split_once(':')) else {
+            break;
+        };
+        match key.to_ascii_lowercase().as_str() {
+            "title" => header.title = Some(value.trim().to_string()).filter(|t| !t.is_empty()),
+            "filetags" | "tags" => header.tags.extend(split_tags(value, &[':', ' '])),
+            _ => {}
+        }
+    }
+    Ok(header)
+}
+
+// "= Title" and a :tags: attribute in an asciidoc document header
+fn read_adoc_header(path: &PathBuf) -> Result<Header> {
+    let mut header = Header::default();
+    let mut started = false;
+    for line in BufReader::new(fs::File::open(path)?).lines() {
+        let line = line?;
+        let line = line.trim();
+        if line.is_empty() {
+            // the header ends at the first blank line
+            if started {
+                break;
+            }
+            continue;
+        }
+        started = true;
+        if line.starts_with("//") {
+            continue;
+        } else if let Some(title) = line.strip_prefix("= ") {
+            header.title = Some(title.trim().to_string());
+        } else if let Some(value) = line.strip_prefix(":tags:") {
+            header.tags.extend(split_tags(value, &[',', ' ']));
+        } else if !line.starts_with(':') {
+            break;
+        }
+    }
+    Ok(header)
+}
+
+fn split_tags(value: &str, separators: &[char]) -> Vec<String> {
+    value
+        .split(separators)
+        .map(str::trim)
+        .filter(|t| !t.is_empty())
+        .map(String::from)
+        .collect()
+}
+
+// raw yaml between the leading --- markers, if any
+fn read_frontmatter_block(path: &PathBuf) -> Result<Option<String>> {
     let file = fs::File::open(path)?;
     let mut reader = BufReader::new(file);
     let mut line = String::new();
 
     // check first line
     if reader.read_line(&mut line)? == 0 || line.trim() != "---" {
-        return Ok(Vec::new());
+        return Ok(None);
     }
 
     let mut frontmatter_content = String::new();
@@ -77,11 +291,15 @@ fn extract_tags(path: &PathBuf) -> Result<Vec<String>> {
         frontmatter_content.push_str(&line);
     }
 
-    // parse yaml frontmatter
-    // If it fails, we just assume no valid tags were found in that block
-    let fm: Frontmatter =
-        serde_yaml::from_str(&frontmatter_content).unwrap_or(Frontmatter { tags: Vec::new() });
-    Ok(fm.tags)
+    Ok(Some(frontmatter_content))
+}
+
+// parsed yaml frontmatter of a note
+pub fn read_frontmatter(path: &PathBuf) -> Result<Option<serde_yaml::Value>> {
+    match read_frontmatter_block(path)? {
+        Some(block) => Ok(Some(serde_yaml::from_str(&block)?)),
+        None => Ok(None),
+    }
 }
 
 // read note content
@@ -89,15 +307,149 @@ pub fn read_note_content(path: &PathBuf) -> Result<String> {
     fs::read_to_string(path).with_context(|| format!("Failed to read file: {:?}", path))
 }
 
+// per-vault ignore file, in gitignore syntax
+pub const IGNORE_FILE: &str = ".kirokuignore";
+
+// extension of notes when none are configured
+pub const DEFAULT_EXTENSION: &str = "md";
+
+// files left out of the vault: dot-files, plus gitignore-style patterns.
+// also knows which extensions the vault reads as notes
+pub struct IgnoreRules {
+    root: Option<PathBuf>,
+    matcher: Option<Gitignore>,
+    extensions: Vec<String>,
+}
+
+impl Default for IgnoreRules {
+    fn default() -> Self {
+        Self {
+            root: None,
+            matcher: None,
+            extensions: vec![DEFAULT_EXTENSION.to_string()],
+        }
+    }
+}
+
+impl IgnoreRules {
+    // patterns are relative to the vault root
+    pub fn new(root: &Path, patterns: &[String]) -> Self {
+        Self::build(root, &[], patterns)
+    }
+
+    // the vault's .kirokuignore (and .gitignore when asked for) plus the
+    // configured patterns; later rules win, so config can re-include
+    pub fn from_config(root: &Path, config: &Config) -> Self {
+        let mut files = Vec::new();
+        if config.respect_gitignore.unwrap_or(false) {
+            files.push(root.join(".gitignore"));
+        }
+        files.push(root.join(IGNORE_FILE));
+        Self {
+            extensions: config.note_extensions(),
+            ..Self::build(root, &files, config.ignore.as_deref().unwrap_or_default())
+        }
+    }
+
+    fn build(root: &Path, files: &[PathBuf], patterns: &[String]) -> Self {
+        let mut builder = GitignoreBuilder::new(root);
+        let mut has_rules = !patterns.is_empty();
+
+        for file in files.iter().filter(|f| f.is_file()) {
+            if let Some(e) = builder.add(file) {
+                warn!("Problem in {:?}: {}", file, e);
+            }
+            has_rules = true;
+        }
+        for pattern in patterns {
+            if let Err(e) = builder.add_line(None, pattern) {
+                warn!("Skipping ignore pattern '{}': {}", pattern, e);
+            }
+        }
+
+        let matcher = if has_rules {
+            builder
+                .build()
+                .inspect_err(|e| warn!("Failed to build ignore rules: {}", e))
+                .ok()
+        } else {
+            None
+        };
+
+        Self {
+            root: Some(root.to_path_buf()),
+            matcher,
+            ..Self::default()
+        }
+    }
+
+    // a file with one of the vault's note extensions
+    pub fn is_note_file(&self, path: &Path) -> bool {
+        is_note_file(path, &self.extensions)
+    }
+
+    // true for files that change what is ignored
+    pub fn is_ignore_file(&self, path: &Path) -> bool {
+        let Some(root) = &self.root else {
+            return false;
+        };
+        path.parent() == Some(root.as_path())
+            && path
+                .file_name()
+                .is_some_and(|name| name == IGNORE_FILE || name == ".gitignore")
+    }
+
+    pub fn is_ignored(&self, path: &Path, is_dir: bool) -> bool {
+        if self.is_hidden(path) {
+            return true;
+        }
+        let Some(matcher) = &self.matcher else {
+            return false;
+        };
+        // the matcher only knows about paths inside the vault
+        if !path.starts_with(matcher.path()) {
+            return false;
+        }
+        matcher
+            .matched_path_or_any_parents(path, is_dir)
+            .is_ignore()
+    }
+
+    // dot-files and anything inside dot-folders, like .git or .kiroku
+    fn is_hidden(&self, path: &Path) -> bool {
+        let rel = match &self.root {
+            Some(root) => match path.strip_prefix(root) {
+                Ok(rel) => rel,
+                Err(_) => return false,
+            },
+            None => match path.file_name() {
+                Some(name) => Path::new(name),
+                None => return false,
+            },
+        };
+        rel.components()
+            .any(|c| c.as_os_str().to_string_lossy().starts_with('.'))
+    }
+}
+
 // scan directory for notes
 pub fn load_notes(directory: &str) -> Result<Vec<Note>> {
+    load_notes_with(directory, &IgnoreRules::default())
+}
+
+// scan directory for notes, skipping ignored paths
+pub fn load_notes_with(directory: &str, ignore: &IgnoreRules) -> Result<Vec<Note>> {
     let mut notes = Vec::new();
     let root = PathBuf::from(directory);
 
-    for entry in WalkDir::new(directory).into_iter().filter_map(|e| e.ok()) {
+    for entry in WalkDir::new(directory)
+        .into_iter()
+        .filter_entry(|e| e.depth() == 0 || !ignore.is_ignored(e.path(), e.file_type().is_dir()))
+        .filter_map(|e| e.ok())
+    {
         let path = entry.path();
 
-        if path.is_file() && path.extension().and_then(|s| s.to_str()) == Some("md") {
+        if path.is_file() && ignore.is_note_file(path) {
             match Note::from_path(path.to_path_buf(), &root) {
                 Ok(note) => notes.push(note),
                 Err(e) => {
@@ -107,16 +459,25 @@ pub fn load_notes(directory: &str) -> Result<Vec<Note>> {
         }
     }
 
-    notes.sort_by(|a, b| b.last_modified.cmp(&a.last_modified));
+    notes.sort_by_key(|n| std::cmp::Reverse(n.last_modified));
     Ok(notes)
 }
 
 // scan directory for all items
 pub fn load_all_items(directory: &str) -> Result<Vec<FileSystemItem>> {
+    load_all_items_with(directory, &IgnoreRules::default())
+}
+
+// scan directory for all items, skipping ignored paths
+pub fn load_all_items_with(directory: &str, ignore: &IgnoreRules) -> Result<Vec<FileSystemItem>> {
     let mut items = Vec::new();
     let root = PathBuf::from(directory);
 
-    for entry in WalkDir::new(directory).into_iter().filter_map(|e| e.ok()) {
+    for entry in WalkDir::new(directory)
+        .into_iter()
+        .filter_entry(|e| e.depth() == 0 || !ignore.is_ignored(e.path(), e.file_type().is_dir()))
+        .filter_map(|e| e.ok())
+    {
         let path = entry.path();
 
         // skip root directory
@@ -124,34 +485,140 @@ pub fn load_all_items(directory: &str) -> Result<Vec<FileSystemItem>> {
             continue;
         }
 
-        // skip hidden files
-        if path
-            .file_name()
-            .and_then(|s| s.to_str())
-            .map(|s| s.starts_with('.'))
-            .unwrap_or(false)
+        if path.is_dir() {
+            // keep relative path
+            items.push(FileSystemItem::Folder(path.to_path_buf()));
+        } else if ignore.is_note_file(path) {
+            if let Ok(note) = Note::from_path(path.to_path_buf(), &root) {
+                items.push(FileSystemItem::Note(note));
+            }
+        } else if path.is_file() {
+            items.push(FileSystemItem::Attachment(path.to_path_buf()));
+        }
+    }
+
+    sort_items(&mut items);
+
+    Ok(items)
+}
+
+// a file kiroku lists as a note, given the extensions to accept
+pub fn is_note_file(path: &Path, extensions: &[String]) -> bool {
+    path.is_file()
+        && path
+            .extension()
+            .and_then(|e| e.to_str())
+            .is_some_and(|ext| extensions.iter().any(|e| e.eq_ignore_ascii_case(ext)))
+}
+
+// sort folders, then notes, then attachments
+pub fn sort_items(items: &mut [FileSystemItem]) {
+    fn rank(item: &FileSystemItem) -> u8 {
+        match item {
+            FileSystemItem::Folder(_) => 0,
+            FileSystemItem::Note(_) => 1,
+            FileSystemItem::Attachment(_) => 2,
+        }
+    }
+
+    items.sort_by(|a, b| {
+        rank(a).cmp(&rank(b)).then_with(|| match (a, b) {
+            (FileSystemItem::Note(na), FileSystemItem::Note(nb)) => na.title.cmp(&nb.title),
+            _ => a.path().cmp(b.path()),
+        })
+    });
+}
+
+// a [[wiki link]] or [text](target) in a line of markdown
+#[derive(Debug, Clone, PartialEq)]
+pub struct Link {
+    // byte range of the whole link in the line
+    pub start: usize,
+    pub end: usize,
+    pub target: String,
+    pub wiki: bool,
+}
+
+impl Link {
+    // points outside the notebook, like a web page or mail address
+    pub fn is_external(&self) -> bool {
+        !self.wiki && (self.target.contains("://") || self.target.starts_with("mailto:"))
+    }
+}
+
+// links in a line, skipping inline code
+pub fn find_links(line: &str) -> Vec<Link> {
+    let mut links = Vec::new();
+    let mut i = 0;
+
+    while i < line.len() {
+        let rest = &line[i..];
+        if let Some(code) = rest.strip_prefix('`') {
+            i += code.find('`').map_or(1, |end| end + 2);
+            continue;
+        }
+
+        if let Some(inner) = rest.strip_prefix("[[")
+            && let Some(end) = inner.find("]]")
         {
+            // [[note#heading|label]] points at "note", org's [[file:x][label]] at "file:x"
+            let target = inner[..end].split("][").next().unwrap_or("");
+            let target = target.split(['|', '#']).next().unwrap_or("").trim();
+            if !target.is_empty() {
+                links.push(Link {
+                    start: i,
+                    end: i + end + 4,
+                    target: target.to_string(),
+                    wiki: true,
+                });
+            }
+            i += end + 4;
             continue;
         }
 
-        if path.is_dir() {
-            // keep relative path
-            items.push(FileSystemItem::Folder(path.to_path_buf()));
-        } else if path.is_file()
-            && path.extension().and_then(|s| s.to_str()) == Some("md")
-            && let Ok(note) = Note::from_path(path.to_path_buf(), &root)
+        if rest.starts_with('[')
+            && let Some(mid) = rest.find("](")
+            && let Some(end) = rest[mid..].find(')')
         {
-            items.push(FileSystemItem::Note(note));
+            // [text](<my file.md> "title") points at "my file.md"
+            let inner = rest[mid + 2..mid + end].trim();
+            let target = match inner.strip_prefix('<') {
+                Some(quoted) => quoted.split('>').next().unwrap_or(""),
+                None => inner.split_whitespace().next().unwrap_or(""),
+            };
+            if !target.is_empty() {
+                links.push(Link {
+                    start: i,
+                    end: i + mid + end + 1,
+                    target: target.replace("%20", " "),
+                    wiki: false,
+                });
+            }
+            i += mid + end + 1;
+            continue;
         }
+
+        i += rest.chars().next().map_or(1, char::len_utf8);
     }
 
-    // sort folders then files
-    items.sort_by(|a, b| match (a, b) {
-        (FileSystemItem::Folder(pa), FileSystemItem::Folder(pb)) => pa.cmp(pb),
-        (FileSystemItem::Folder(_), FileSystemItem::Note(_)) => std::cmp::Ordering::Less,
-        (FileSystemItem::Note(_), FileSystemItem::Folder(_)) => std::cmp::Ordering::Greater,
-        (FileSystemItem::Note(na), FileSystemItem::Note(nb)) => na.title.cmp(&nb.title),
-    });
+    links
+}
 
-    Ok(items)
+// note a [[wiki link]] points at, by title, path or file name
+pub fn resolve_wiki_link<'a>(notes: &'a [Note], target: &str) -> Option<&'a Note> {
+    // [[note.org]] means the same as [[note]]
+    let target = match target.rsplit_once('.') {
+        Some((stem, ext)) if NoteFormat::from_extension(ext).is_some() => stem,
+        _ => target,
+    };
+    notes
+        .iter()
+        .find(|n| n.title.eq_ignore_ascii_case(target))
+        .or_else(|| {
+            notes.iter().find(|n| {
+                n.path
+                    .file_stem()
+                    .is_some_and(|stem| stem.to_string_lossy().eq_ignore_ascii_case(target))
+            })
+        })
 }
@@ -0,0 +1,205 @@
+use crate::attachments;
+use crate::config::Severity;
+use crate::data::{self, IgnoreRules, Note, NoteFormat};
+use std::collections::{BTreeMap, BTreeSet};
+use std::ffi::OsString;
+use std::fmt;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+// a problem with one note
+#[derive(Debug, Clone, PartialEq)]
+pub struct Finding {
+    pub severity: Severity,
+    pub path: PathBuf,
+    // 1-based line the problem is on, when it has one
+    pub line: Option<usize>,
+    pub message: String,
+}
+
+impl Finding {
+    fn new(severity: Severity, path: &Path, line: Option<usize>, message: String) -> Self {
+        Self {
+            severity,
+            path: path.to_path_buf(),
+            line,
+            message,
+        }
+    }
+
+    // vault-relative location, with the line when known
+    pub fn location(&self, base_path: &Path) -> String {
+        let rel = self.path.strip_prefix(base_path).unwrap_or(&self.path);
+        match self.line {
+            Some(line) => format!("{}:{}", rel.display(), line),
+            None => rel.display().to_string(),
+        }
+    }
+}
+
+impl fmt::Display for Finding {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let level = match self.severity {
+            Severity::Error => "error",
+            Severity::Warning => "warning",
+        };
+        write!(f, "{}: {}", level, self.message)
+    }
+}
+
+// check every note in the vault; errors come first, then by path and line
+pub fn check_vault(base_path: &Path, notes: &[Note], ignore: &IgnoreRules) -> Vec<Finding> {
+    // ![[name.png]] style links find files anywhere in the vault
+    let file_names: BTreeSet<OsString> = attachments::list_attachments(base_path, ignore)
+        .iter()
+        .chain(notes.iter().map(|n| &n.path))
+        .filter_map(|p| p.file_name().map(|n| n.to_os_string()))
+        .collect();
+
+    let mut findings = Vec::new();
+    for note in notes {
+        check_note(base_path, note, notes, &file_names, &mut findings);
+    }
+    check_duplicate_titles(base_path, notes, &mut findings);
+
+    findings.sort_by(|a, b| {
+        let rank = |f: &Finding| f.severity != Severity::Error;
+        rank(a)
+            .cmp(&rank(b))
+            .then_with(|| a.path.cmp(&b.path))
+            .then_with(|| a.line.cmp(&b.line))
+    });
+    findings
+}
+
+fn check_note(
+    base_path: &Path,
+    note: &Note,
+    notes: &[Note],
+    file_names: &BTreeSet<OsString>,
+    findings: &mut Vec<Finding>,
+) {
+    let path = &note.path;
+    let content = match fs::read(path) {
+        Ok(bytes) => match String::from_utf8(bytes) {
+            Ok(content) => content,
+            Err(e) => {
+                let at = e.utf8_error().valid_up_to();
+                let message = format!("not valid UTF-8 (bad byte at offset {})", at);
+                findings.push(Finding::new(Severity::Error, path, None, message));
+                return;
+            }
+        },
+        Err(e) => {
+            let message = format!("cannot be read: {}", e);
+            findings.push(Finding::new(Severity::Error, path, None, message));
+            return;
+        }
+    };
+
+    if content.trim().is_empty() {
+        let message = "empty note".to_string();
+        findings.push(Finding::new(Severity::Warning, path, None, message));
+        return;
+    }
+
+    let format = NoteFormat::from_path(path);
+    if matches!(format, NoteFormat::Markdown | NoteFormat::Text)
+        && let Err(e) = data::read_frontmatter(path)
+    {
+        let message = format!("invalid frontmatter: {}", e);
+        findings.push(Finding::new(Severity::Error, path, Some(1), message));
+    }
+
+    let mut in_code_block = false;
+    for (i, line) in content.lines().enumerate() {
+        // examples in code blocks are not links
+        if format.is_fence(line) {
+            in_code_block = !in_code_block;
+            continue;
+        }
+        if in_code_block {
+            continue;
+        }
+
+        let mut file_targets = Vec::new();
+        for link in data::find_links(line) {
+            if link.is_external() {
+                continue;
+            }
+            if let Some(file) = link.target.strip_prefix("file:") {
+                file_targets.push(file.to_string());
+            } else if link.wiki && !names_note(&link.target) {
+                let name = Path::new(&link.target).file_name();
+                let found = name.is_some_and(|n| file_names.contains(n))
+                    || attachments::resolve_path(base_path, path, &link.target).exists();
+                if !found {
+                    let message = format!("missing attachment [[{}]]", link.target);
+                    findings.push(Finding::new(Severity::Error, path, Some(i + 1), message));
+                }
+            } else if link.wiki {
+                if data::resolve_wiki_link(notes, &link.target).is_none() {
+                    let message = format!("broken wiki link [[{}]]", link.target);
+                    findings.push(Finding::new(Severity::Error, path, Some(i + 1), message));
+                }
+            } else {
+                file_targets.push(link.target);
+            }
+        }
+        if format == NoteFormat::AsciiDoc {
+            file_targets.extend(attachments::adoc_targets(line));
+        }
+
+        for target in file_targets {
+            // a bare #anchor points into the note itself
+            let file = target.split('#').next().unwrap_or("");
+            if file.is_empty() || attachments::resolve_path(base_path, path, file).exists() {
+                continue;
+            }
+            let message = if names_note(file) {
+                format!("broken link to {}", target)
+            } else {
+                format!("missing attachment {}", target)
+            };
+            findings.push(Finding::new(Severity::Error, path, Some(i + 1), message));
+        }
+    }
+}
+
+// a link target without an extension, or with a note one, names a note
+fn names_note(target: &str) -> bool {
+    match Path::new(target).extension().and_then(|e| e.to_str()) {
+        Some(ext) => NoteFormat::from_extension(ext).is_some(),
+        None => true,
+    }
+}
+
+// same titles make wiki links ambiguous
+fn check_duplicate_titles(base_path: &Path, notes: &[Note], findings: &mut Vec<Finding>) {
+    let mut by_title: BTreeMap<String, Vec<&Note>> = BTreeMap::new();
+    for note in notes {
+        by_title
+            .entry(note.title.to_lowercase())
+            .or_default()
+            .push(note);
+    }
+
+    for group in by_title.values().filter(|g| g.len() > 1) {
+        for note in group {
+            let others: Vec<String> = group
+                .iter()
+                .filter(|other| other.path != note.path)
+                .map(|other| {
+                    let rel = other.path.strip_prefix(base_path).unwrap_or(&other.path);
+                    rel.display().to_string()
+                })
+                .collect();
+            let message = format!(
+                "duplicate title '{}' (also {})",
+                note.title,
+                others.join(", ")
+            );
+            findings.push(Finding::new(Severity::Warning, &note.path, None, message));
+        }
+    }
+}
@@ -8,6 +8,8 @@ pub enum KirokuError {
     Io(#[from] io::Error),
     #[error("Git error: {0}")]
     Git(String),
+    #[error("Mirror error: {0}")]
+    Mirror(String),
     #[error("Environment error: {0}")]
     Env(String),
 }
@@ -1,5 +1,7 @@
 use anyhow::Result;
-use crossterm::event::{self, Event, KeyEvent};
+use crossterm::event::{self, Event, KeyEvent, MouseEvent};
+use std::collections::BTreeSet;
+use std::path::PathBuf;
 use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::{Arc, mpsc};
 use std::thread;
@@ -7,8 +9,14 @@ use std::time::Duration;
 
 pub enum AppEvent {
     Input(KeyEvent),
+    Mouse(MouseEvent),
+    Resize(u16, u16),
+    FocusGained,
+    FocusLost,
     Tick,
-    FileChanged,
+    // paths touched since the last batch
+    FileChanged(Vec<PathBuf>),
+    AutoSynced(Result<String, String>),
 }
 
 // handle input and ticks
@@ -36,9 +44,20 @@ impl EventHandler {
                 }
 
                 if event::poll(tick_rate).unwrap_or(false) {
-                    if let Event::Key(key) = event::read().unwrap()
-                        && tx_input.send(AppEvent::Input(key)).is_err()
-                    {
+                    let event = match event::read() {
+                        Ok(Event::Key(key)) => AppEvent::Input(key),
+                        Ok(Event::Mouse(mouse)) => AppEvent::Mouse(mouse),
+                        Ok(Event::Resize(width, height)) => AppEvent::Resize(width, height),
+                        Ok(Event::FocusGained) => AppEvent::FocusGained,
+                        Ok(Event::FocusLost) => AppEvent::FocusLost,
+                        Ok(_) => continue,
+                        Err(e) => {
+                            log::error!("Failed to read terminal input: {}", e);
+                            thread::sleep(tick_rate);
+                            continue;
+                        }
+                    };
+                    if tx_input.send(event).is_err() {
                         break;
                     }
                 } else if tx_input.send(AppEvent::Tick).is_err() {
@@ -68,3 +87,34 @@ impl EventHandler {
         self.paused.store(false, Ordering::SeqCst);
     }
 }
+
+// collect paths from the file watcher and pass them on as one
+// FileChanged batch once they have been quiet for a moment
+pub fn debounce_paths(
+    sender: mpsc::Sender<AppEvent>,
+    quiet: Duration,
+) -> mpsc::Sender<Vec<PathBuf>> {
+    let (tx, rx) = mpsc::channel::<Vec<PathBuf>>();
+
+    thread::spawn(move || {
+        while let Ok(first) = rx.recv() {
+            let mut batch: BTreeSet<PathBuf> = first.into_iter().collect();
+            let open = loop {
+                match rx.recv_timeout(quiet) {
+                    Ok(paths) => batch.extend(paths),
+                    Err(mpsc::RecvTimeoutError::Timeout) => break true,
+                    Err(mpsc::RecvTimeoutError::Disconnected) => break false,
+                }
+            };
+            if sender
+                .send(AppEvent::FileChanged(batch.into_iter().collect()))
+                .is_err()
+                || !open
+            {
+                break;
+            }
+        }
+    });
+
+    tx
+}
@@ -0,0 +1,182 @@
+use crate::config::Severity;
+use crate::data::{self, Note};
+use crate::doctor::Finding;
+use crate::tasks::Task;
+use chrono::{DateTime, SecondsFormat, Utc};
+use serde::Serialize;
+use serde::ser::SerializeMap;
+use std::fs;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+// bump only on breaking changes to the records below
+pub const SCHEMA_VERSION: u32 = 1;
+
+// how cli commands print their results
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum OutputFormat {
+    Text,
+    Json,
+    JsonLines,
+}
+
+// machine-readable view of a note
+#[derive(Debug, Serialize, PartialEq)]
+pub struct NoteRecord {
+    pub path: String,
+    pub title: String,
+    pub tags: Vec<String>,
+    pub modified: String,
+    pub size: u64,
+    pub frontmatter: serde_json::Value,
+}
+
+impl NoteRecord {
+    pub fn from_note(note: &Note) -> Self {
+        let modified: DateTime<Utc> = note.last_modified.into();
+
+        // invalid yaml is reported as null rather than failing the export
+        let frontmatter = data::read_frontmatter(&note.path)
+            .ok()
+            .flatten()
+            .and_then(|fm| serde_json::to_value(fm).ok())
+            .unwrap_or(serde_json::Value::Null);
+
+        Self {
+            path: note.path.to_string_lossy().to_string(),
+            title: note.title.clone(),
+            tags: note.tags.clone(),
+            modified: modified.to_rfc3339_opts(SecondsFormat::Secs, true),
+            size: note.size,
+            frontmatter,
+        }
+    }
+}
+
+// machine-readable view of a tag
+#[derive(Debug, Serialize, PartialEq)]
+pub struct TagRecord {
+    pub tag: String,
+    pub count: usize,
+}
+
+// machine-readable view of an attachment
+#[derive(Debug, Serialize, PartialEq)]
+pub struct AttachmentRecord {
+    pub path: String,
+    pub kind: String,
+    pub size: u64,
+    pub linked_from: Vec<String>,
+}
+
+impl AttachmentRecord {
+    pub fn new<'a>(path: &Path, linked_from: impl IntoIterator<Item = &'a PathBuf>) -> Self {
+        Self {
+            path: path.to_string_lossy().to_string(),
+            kind: data::AttachmentKind::from_path(path).label().to_lowercase(),
+            size: fs::metadata(path).map(|m| m.len()).unwrap_or(0),
+            linked_from: linked_from
+                .into_iter()
+                .map(|p| p.to_string_lossy().to_string())
+                .collect(),
+        }
+    }
+}
+
+// machine-readable view of a vault check finding
+#[derive(Debug, Serialize, PartialEq)]
+pub struct FindingRecord {
+    pub severity: String,
+    pub path: String,
+    pub line: Option<usize>,
+    pub message: String,
+}
+
+impl FindingRecord {
+    pub fn from_finding(finding: &Finding) -> Self {
+        let severity = match finding.severity {
+            Severity::Error => "error",
+            Severity::Warning => "warning",
+        };
+        Self {
+            severity: severity.to_string(),
+            path: finding.path.to_string_lossy().to_string(),
+            line: finding.line,
+            message: finding.message.clone(),
+        }
+    }
+}
+
+// machine-readable view of a task
+#[derive(Debug, Serialize, PartialEq)]
+pub struct TaskRecord {
+    pub path: String,
+    pub line: usize,
+    pub text: String,
+    pub done: bool,
+    pub heading: Option<String>,
+    pub due: Option<String>,
+}
+
+impl TaskRecord {
+    pub fn from_task(task: &Task) -> Self {
+        Self {
+            path: task.path.to_string_lossy().to_string(),
+            line: task.line,
+            text: task.text.clone(),
+            done: task.done,
+            heading: task.heading.clone(),
+            due: task.due.map(|d| d.format("%Y-%m-%d").to_string()),
+        }
+    }
+}
+
+// { "schema_version": 1, "<key>": [records] }
+struct Document<'a, T: Serialize> {
+    key: &'a str,
+    records: &'a [T],
+}
+
+impl<T: Serialize> Serialize for Document<'_, T> {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut map = serializer.serialize_map(Some(2))?;
+        map.serialize_entry("schema_version", &SCHEMA_VERSION)?;
+        map.serialize_entry(self.key, self.records)?;
+        map.end()
+    }
+}
+
+#[derive(Serialize)]
+struct Versioned<'a, T: Serialize> {
+    schema_version: u32,
+    #[serde(flatten)]
+    record: &'a T,
+}
+
+// write records as one json document under `key`, or one object per line
+pub fn write_records<T: Serialize>(
+    out: &mut impl Write,
+    key: &str,
+    records: &[T],
+    format: OutputFormat,
+) -> anyhow::Result<()> {
+    match format {
+        OutputFormat::Json => {
+            let doc = Document { key, records };
+            serde_json::to_writer_pretty(&mut *out, &doc)?;
+            writeln!(out)?;
+        }
+        OutputFormat::JsonLines => {
+            for record in records {
+                let line = Versioned {
+                    schema_version: SCHEMA_VERSION,
+                    record,
+                };
+                serde_json::to_writer(&mut *out, &line)?;
+                writeln!(out)?;
+            }
+        }
+        OutputFormat::Text => {}
+    }
+    Ok(())
+}
@@ -0,0 +1,547 @@
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fmt;
+
+// actions that can be bound to keys in normal mode
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum KeyAction {
+    Down,
+    Up,
+    Top,
+    Bottom,
+    Open,
+    Parent,
+    ScrollDown,
+    ScrollUp,
+    NewNote,
+    NewFolder,
+    Rename,
+    Delete,
+    Attach,
+    Sync,
+    CycleSort,
+    CopyContent,
+    CopyPath,
+    Search,
+    ContentSearch,
+    TagSearch,
+    Palette,
+    SwitchVault,
+    Doctor,
+    Tasks,
+    Calendar,
+    Help,
+    CycleTheme,
+    GrowList,
+    ShrinkList,
+    TogglePreview,
+    ZenMode,
+    ToggleLogs,
+    OpenLog,
+    Suspend,
+    Quit,
+}
+
+impl KeyAction {
+    pub const ALL: [KeyAction; 35] = [
+        KeyAction::Down,
+        KeyAction::Up,
+        KeyAction::Top,
+        KeyAction::Bottom,
+        KeyAction::Open,
+        KeyAction::Parent,
+        KeyAction::ScrollDown,
+        KeyAction::ScrollUp,
+        KeyAction::NewNote,
+        KeyAction::NewFolder,
+        KeyAction::Rename,
+        KeyAction::Delete,
+        KeyAction::Attach,
+        KeyAction::Sync,
+        KeyAction::CycleSort,
+        KeyAction::CopyContent,
+        KeyAction::CopyPath,
+        KeyAction::Search,
+        KeyAction::ContentSearch,
+        KeyAction::TagSearch,
+        KeyAction::Palette,
+        KeyAction::SwitchVault,
+        KeyAction::Doctor,
+        KeyAction::Tasks,
+        KeyAction::Calendar,
+        KeyAction::Help,
+        KeyAction::CycleTheme,
+        KeyAction::GrowList,
+        KeyAction::ShrinkList,
+        KeyAction::TogglePreview,
+        KeyAction::ZenMode,
+        KeyAction::ToggleLogs,
+        KeyAction::OpenLog,
+        KeyAction::Suspend,
+        KeyAction::Quit,
+    ];
+
+    // name used in the [keys] config table
+    pub fn name(&self) -> &'static str {
+        match self {
+            KeyAction::Down => "down",
+            KeyAction::Up => "up",
+            KeyAction::Top => "top",
+            KeyAction::Bottom => "bottom",
+            KeyAction::Open => "open",
+            KeyAction::Parent => "parent",
+            KeyAction::ScrollDown => "scroll_down",
+            KeyAction::ScrollUp => "scroll_up",
+            KeyAction::NewNote => "new_note",
+            KeyAction::NewFolder => "new_folder",
+            KeyAction::Rename => "rename",
+            KeyAction::Delete => "delete",
+            KeyAction::Attach => "attach",
+            KeyAction::Sync => "sync",
+            KeyAction::CycleSort => "cycle_sort",
+            KeyAction::CopyContent => "copy_content",
+            KeyAction::CopyPath => "copy_path",
+            KeyAction::Search => "search",
+            KeyAction::ContentSearch => "content_search",
+            KeyAction::TagSearch => "tag_search",
+            KeyAction::Palette => "palette",
+            KeyAction::SwitchVault => "switch_vault",
+            KeyAction::Doctor => "doctor",
+            KeyAction::Tasks => "tasks",
+            KeyAction::Calendar => "calendar",
+            KeyAction::Help => "help",
+            KeyAction::CycleTheme => "cycle_theme",
+            KeyAction::GrowList => "grow_list",
+            KeyAction::ShrinkList => "shrink_list",
+            KeyAction::TogglePreview => "toggle_preview",
+            KeyAction::ZenMode => "zen_mode",
+            KeyAction::ToggleLogs => "toggle_logs",
+            KeyAction::OpenLog => "open_log",
+            KeyAction::Suspend => "suspend",
+            KeyAction::Quit => "quit",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<KeyAction> {
+        KeyAction::ALL.into_iter().find(|a| a.name() == name)
+    }
+
+    pub fn description(&self) -> &'static str {
+        match self {
+            KeyAction::Down => "Move selection down",
+            KeyAction::Up => "Move selection up",
+            KeyAction::Top => "Jump to first item",
+            KeyAction::Bottom => "Jump to last item",
+            KeyAction::Open => "Edit note / Enter folder",
+            KeyAction::Parent => "Go up a directory",
+            KeyAction::ScrollDown => "Scroll preview down",
+            KeyAction::ScrollUp => "Scroll preview up",
+            KeyAction::NewNote => "New note",
+            KeyAction::NewFolder => "New folder",
+            KeyAction::Rename => "Rename item",
+            KeyAction::Delete => "Delete item",
+            KeyAction::Attach => "Attach a file to the note",
+            KeyAction::Sync => "Sync notebook",
+            KeyAction::CycleSort => "Cycle sort mode",
+            KeyAction::CopyContent => "Copy content to clipboard",
+            KeyAction::CopyPath => "Copy path to clipboard",
+            KeyAction::Search => "Search notes by title",
+            KeyAction::ContentSearch => "Search notes by content",
+            KeyAction::TagSearch => "Search notes by tag",
+            KeyAction::Palette => "Command palette",
+            KeyAction::SwitchVault => "Switch vault",
+            KeyAction::Doctor => "Check the vault for problems",
+            KeyAction::Tasks => "Tasks across all notes",
+            KeyAction::Calendar => "Calendar of notes by day",
+            KeyAction::Help => "Toggle help",
+            KeyAction::CycleTheme => "Cycle themes",
+            KeyAction::GrowList => "Widen list pane",
+            KeyAction::ShrinkList => "Narrow list pane",
+            KeyAction::TogglePreview => "Toggle preview pane",
+            KeyAction::ZenMode => "Toggle zen mode (preview only)",
+            KeyAction::ToggleLogs => "Toggle logs",
+            KeyAction::OpenLog => "Open the log file",
+            KeyAction::Suspend => "Suspend to the shell",
+            KeyAction::Quit => "Quit",
+        }
+    }
+
+    // help popup section
+    pub fn section(&self) -> &'static str {
+        match self {
+            KeyAction::Down
+            | KeyAction::Up
+            | KeyAction::Top
+            | KeyAction::Bottom
+            | KeyAction::Open
+            | KeyAction::Parent
+            | KeyAction::ScrollDown
+            | KeyAction::ScrollUp => "Navigation",
+            KeyAction::NewNote
+            | KeyAction::NewFolder
+            | KeyAction::Rename
+            | KeyAction::Delete
+            | KeyAction::Attach
+            | KeyAction::Sync
+            | KeyAction::CycleSort
+            | KeyAction::CopyContent
+            | KeyAction::CopyPath => "Actions",
+            KeyAction::Search | KeyAction::ContentSearch | KeyAction::TagSearch => "Search",
+            KeyAction::GrowList
+            | KeyAction::ShrinkList
+            | KeyAction::TogglePreview
+            | KeyAction::ZenMode => "Layout",
+            KeyAction::Palette
+            | KeyAction::SwitchVault
+            | KeyAction::Doctor
+            | KeyAction::Tasks
+            | KeyAction::Calendar
+            | KeyAction::Help
+            | KeyAction::CycleTheme
+            | KeyAction::ToggleLogs
+            | KeyAction::OpenLog
+            | KeyAction::Suspend
+            | KeyAction::Quit => "General",
+        }
+    }
+
+    fn default_keys(&self) -> &'static [&'static str] {
+        match self {
+            KeyAction::Down => &["j", "down"],
+            KeyAction::Up => &["k", "up"],
+            KeyAction::Top => &["home"],
+            KeyAction::Bottom => &["end", "G"],
+            KeyAction::Open => &["l", "enter"],
+            KeyAction::Parent => &["h", "backspace"],
+            KeyAction::ScrollDown => &["ctrl+j"],
+            KeyAction::ScrollUp => &["ctrl+k"],
+            KeyAction::NewNote => &["n"],
+            KeyAction::NewFolder => &["f"],
+            KeyAction::Rename => &["r"],
+            KeyAction::Delete => &["d"],
+            KeyAction::Attach => &["a"],
+            KeyAction::Sync => &["g"],
+            KeyAction::CycleSort => &["s"],
+            KeyAction::CopyContent => &["y"],
+            KeyAction::CopyPath => &["Y"],
+            KeyAction::Search => &["/"],
+            KeyAction::ContentSearch => &["?"],
+            KeyAction::TagSearch => &["#"],
+            KeyAction::Palette => &[":", "ctrl+p"],
+            KeyAction::SwitchVault => &["v"],
+            KeyAction::Doctor => &["!"],
+            KeyAction::Tasks => &["T"],
+            KeyAction::Calendar => &["c"],
+            KeyAction::Help => &["f1"],
+            KeyAction::CycleTheme => &["t"],
+            KeyAction::GrowList => &[">"],
+            KeyAction::ShrinkList => &["<"],
+            KeyAction::TogglePreview => &["p"],
+            KeyAction::ZenMode => &["z"],
+            KeyAction::ToggleLogs => &["f12"],
+            KeyAction::OpenLog => &["L"],
+            KeyAction::Suspend => &["ctrl+z"],
+            KeyAction::Quit => &["q"],
+        }
+    }
+}
+
+// a single key press with modifiers
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct KeyChord {
+    pub code: KeyCode,
+    pub modifiers: KeyModifiers,
+}
+
+impl KeyChord {
+    pub fn new(code: KeyCode, modifiers: KeyModifiers) -> Self {
+        // shift is already part of the character
+        let modifiers = match code {
+            KeyCode::Char(_) => modifiers - KeyModifiers::SHIFT,
+            _ => modifiers,
+        };
+        Self { code, modifiers }
+    }
+
+    pub fn from_event(key: &KeyEvent) -> Self {
+        Self::new(key.code, key.modifiers)
+    }
+
+    // parse "q", "ctrl+j", "shift+tab", "f1", "enter"
+    pub fn parse(s: &str) -> Result<Self, String> {
+        let mut modifiers = KeyModifiers::NONE;
+        let mut rest = s;
+
+        loop {
+            let lower = rest.to_lowercase();
+            if lower.starts_with("ctrl+") && rest.len() > 5 {
+                modifiers |= KeyModifiers::CONTROL;
+                rest = &rest[5..];
+            } else if lower.starts_with("alt+") && rest.len() > 4 {
+                modifiers |= KeyModifiers::ALT;
+                rest = &rest[4..];
+            } else if lower.starts_with("shift+") && rest.len() > 6 {
+                modifiers |= KeyModifiers::SHIFT;
+                rest = &rest[6..];
+            } else {
+                break;
+            }
+        }
+
+        let code = match named_key(rest) {
+            Some(code) => code,
+            None => {
+                let mut chars = rest.chars();
+                match (chars.next(), chars.next()) {
+                    (Some(c), None) if modifiers.contains(KeyModifiers::SHIFT) => {
+                        KeyCode::Char(c.to_ascii_uppercase())
+                    }
+                    (Some(c), None) => KeyCode::Char(c),
+                    _ => return Err(format!("unknown key '{}'", s)),
+                }
+            }
+        };
+
+        Ok(Self::new(code, modifiers))
+    }
+
+    fn is_plain_char(&self) -> bool {
+        matches!(self.code, KeyCode::Char(c) if c != ' ') && self.modifiers.is_empty()
+    }
+}
+
+fn named_key(s: &str) -> Option<KeyCode> {
+    let lower = s.to_lowercase();
+    let code = match lower.as_str() {
+        "enter" | "return" => KeyCode::Enter,
+        "esc" | "escape" => KeyCode::Esc,
+        "backspace" => KeyCode::Backspace,
+        "tab" => KeyCode::Tab,
+        "backtab" => KeyCode::BackTab,
+        "space" => KeyCode::Char(' '),
+        "up" => KeyCode::Up,
+        "down" => KeyCode::Down,
+        "left" => KeyCode::Left,
+        "right" => KeyCode::Right,
+        "home" => KeyCode::Home,
+        "end" => KeyCode::End,
+        "pageup" => KeyCode::PageUp,
+        "pagedown" => KeyCode::PageDown,
+        "delete" | "del" => KeyCode::Delete,
+        "insert" => KeyCode::Insert,
+        _ => {
+            let n: u8 = lower.strip_prefix('f')?.parse().ok()?;
+            if (1..=24).contains(&n) {
+                KeyCode::F(n)
+            } else {
+                return None;
+            }
+        }
+    };
+    Some(code)
+}
+
+impl fmt::Display for KeyChord {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.modifiers.contains(KeyModifiers::CONTROL) {
+            write!(f, "Ctrl+")?;
+        }
+        if self.modifiers.contains(KeyModifiers::ALT) {
+            write!(f, "Alt+")?;
+        }
+        if self.modifiers.contains(KeyModifiers::SHIFT) {
+            write!(f, "Shift+")?;
+        }
+        match self.code {
+            KeyCode::Char(' ') => write!(f, "Space"),
+            KeyCode::Char(c) => write!(f, "{}", c),
+            KeyCode::F(n) => write!(f, "F{}", n),
+            KeyCode::Enter => write!(f, "Enter"),
+            KeyCode::Esc => write!(f, "Esc"),
+            KeyCode::Backspace => write!(f, "Backspace"),
+            KeyCode::Tab => write!(f, "Tab"),
+            KeyCode::BackTab => write!(f, "BackTab"),
+            KeyCode::Up => write!(f, "Up"),
+            KeyCode::Down => write!(f, "Down"),
+            KeyCode::Left => write!(f, "Left"),
+            KeyCode::Right => write!(f, "Right"),
+            KeyCode::Home => write!(f, "Home"),
+            KeyCode::End => write!(f, "End"),
+            KeyCode::PageUp => write!(f, "PageUp"),
+            KeyCode::PageDown => write!(f, "PageDown"),
+            KeyCode::Delete => write!(f, "Delete"),
+            KeyCode::Insert => write!(f, "Insert"),
+            other => write!(f, "{:?}", other),
+        }
+    }
+}
+
+// parse a binding: "gg" is two presses, "ctrl+x n" is two chords
+pub fn parse_sequence(s: &str) -> Result<Vec<KeyChord>, String> {
+    let mut seq = Vec::new();
+    for token in s.split_whitespace() {
+        if (token.contains('+') && token.len() > 1) || named_key(token).is_some() {
+            seq.push(KeyChord::parse(token)?);
+        } else {
+            for c in token.chars() {
+                seq.push(KeyChord::new(KeyCode::Char(c), KeyModifiers::NONE));
+            }
+        }
+    }
+
+    if seq.is_empty() {
+        return Err("empty key binding".to_string());
+    }
+    Ok(seq)
+}
+
+pub fn format_sequence(seq: &[KeyChord]) -> String {
+    if seq.iter().all(|c| c.is_plain_char()) {
+        seq.iter().map(|c| c.to_string()).collect()
+    } else {
+        seq.iter()
+            .map(|c| c.to_string())
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+}
+
+// one key or a list of keys for an action
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(untagged)]
+pub enum KeySpec {
+    One(String),
+    Many(Vec<String>),
+}
+
+impl KeySpec {
+    fn keys(&self) -> Vec<&str> {
+        match self {
+            KeySpec::One(k) => vec![k.as_str()],
+            KeySpec::Many(ks) => ks.iter().map(|k| k.as_str()).collect(),
+        }
+    }
+}
+
+// result of looking up pressed keys
+#[derive(Debug, PartialEq)]
+pub enum KeyLookup {
+    Action(KeyAction),
+    Pending,
+    Unbound,
+}
+
+// active key bindings for normal mode
+#[derive(Debug, Clone)]
+pub struct Keymap {
+    bindings: Vec<(Vec<KeyChord>, KeyAction)>,
+}
+
+impl Default for Keymap {
+    fn default() -> Self {
+        Self::from_config(None).0
+    }
+}
+
+fn conflicts(a: &[KeyChord], b: &[KeyChord]) -> bool {
+    let n = a.len().min(b.len());
+    a[..n] == b[..n]
+}
+
+impl Keymap {
+    // build from defaults plus [keys] overrides, reporting problems
+    pub fn from_config(overrides: Option<&BTreeMap<String, KeySpec>>) -> (Self, Vec<String>) {
+        let mut warnings = Vec::new();
+        let mut bindings: Vec<(Vec<KeyChord>, KeyAction)> = Vec::new();
+        let mut overridden = Vec::new();
+
+        for (name, spec) in overrides.into_iter().flatten() {
+            let Some(action) = KeyAction::from_name(name) else {
+                warnings.push(format!("unknown action '{}' in [keys]", name));
+                continue;
+            };
+            overridden.push(action);
+
+            for key in spec.keys() {
+                let seq = match parse_sequence(key) {
+                    Ok(seq) => seq,
+                    Err(e) => {
+                        warnings.push(format!("{}: {}", name, e));
+                        continue;
+                    }
+                };
+
+                if let Some((_, other)) = bindings.iter().find(|(s, _)| conflicts(s, &seq)) {
+                    warnings.push(format!(
+                        "'{}' for {} conflicts with {}, ignored",
+                        key,
+                        name,
+                        other.name()
+                    ));
+                    continue;
+                }
+                bindings.push((seq, action));
+            }
+        }
+
+        for action in KeyAction::ALL {
+            if overridden.contains(&action) {
+                continue;
+            }
+            for key in action.default_keys() {
+                let seq = parse_sequence(key).expect("default key bindings parse");
+                if let Some((_, other)) = bindings.iter().find(|(s, _)| conflicts(s, &seq)) {
+                    warnings.push(format!(
+                        "default '{}' for {} is shadowed by {}",
+                        key,
+                        action.name(),
+                        other.name()
+                    ));
+                    continue;
+                }
+                bindings.push((seq, action));
+            }
+        }
+
+        (Self { bindings }, warnings)
+    }
+
+    // match pressed keys against the bindings
+    pub fn lookup(&self, pressed: &[KeyChord]) -> KeyLookup {
+        let mut pending = false;
+        for (seq, action) in &self.bindings {
+            if seq.as_slice() == pressed {
+                return KeyLookup::Action(*action);
+            }
+            if seq.len() > pressed.len() && seq[..pressed.len()] == *pressed {
+                pending = true;
+            }
+        }
+
+        if pending {
+            KeyLookup::Pending
+        } else {
+            KeyLookup::Unbound
+        }
+    }
+
+    // display strings of all keys bound to an action
+    pub fn keys_for(&self, action: KeyAction) -> Vec<String> {
+        self.bindings
+            .iter()
+            .filter(|(_, a)| *a == action)
+            .map(|(seq, _)| format_sequence(seq))
+            .collect()
+    }
+
+    // "j / Down", or "unbound"
+    pub fn describe(&self, action: KeyAction) -> String {
+        let keys = self.keys_for(action);
+        if keys.is_empty() {
+            "unbound".to_string()
+        } else {
+            keys.join(" / ")
+        }
+    }
+}
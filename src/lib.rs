@@ -1,7 +1,20 @@
 pub mod app;
+pub mod attachments;
+pub mod calendar;
+pub mod cli;
 pub mod config;
+pub mod crash;
 pub mod data;
+pub mod doctor;
 pub mod errors;
 pub mod events;
+pub mod export;
+pub mod keys;
+pub mod logging;
 pub mod ops;
+pub mod search;
+pub mod state;
+pub mod sync;
+pub mod tasks;
+pub mod theme;
 pub mod ui;
@@ -0,0 +1,216 @@
+use anyhow::{Result, anyhow};
+use log::{LevelFilter, Log, Metadata, Record};
+use std::collections::VecDeque;
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::thread;
+use std::time::Duration;
+
+// how many lines crash reports get
+const RECENT_LINES: usize = 200;
+
+// the log file is rotated once it grows past this
+pub const MAX_LOG_BYTES: u64 = 1024 * 1024;
+
+// rotated files kept next to the current one (kiroku.log.1 ..)
+pub const KEPT_LOGS: usize = 3;
+
+// feeds the log widget and the log file, and remembers the last lines for crash reports
+struct Logger {
+    recent: Mutex<VecDeque<String>>,
+    file: Mutex<Option<LogFile>>,
+}
+
+// whether the tui's log widget is being fed
+static WIDGET: AtomicBool = AtomicBool::new(false);
+
+static LOGGER: Logger = Logger {
+    recent: Mutex::new(VecDeque::new()),
+    file: Mutex::new(None),
+};
+
+impl Log for Logger {
+    fn enabled(&self, metadata: &Metadata) -> bool {
+        metadata.level() <= log::max_level()
+    }
+
+    fn log(&self, record: &Record) {
+        if !self.enabled(record.metadata()) {
+            return;
+        }
+        if WIDGET.load(Ordering::Relaxed) {
+            tui_logger::Drain::new().log(record);
+        }
+
+        let now = chrono::Local::now();
+        let line = format!(
+            "{:<5} {}: {}",
+            record.level(),
+            record.target(),
+            record.args()
+        );
+
+        if let Ok(mut file) = self.file.lock()
+            && let Some(log_file) = file.as_mut()
+        {
+            // a broken log file must never take the app down
+            let stamped = format!("{} {}", now.format("%Y-%m-%d %H:%M:%S"), line);
+            if log_file.write_line(&stamped).is_err() {
+                *file = None;
+            }
+        }
+
+        if let Ok(mut recent) = self.recent.lock() {
+            if recent.len() == RECENT_LINES {
+                recent.pop_front();
+            }
+            recent.push_back(format!("{} {}", now.format("%H:%M:%S"), line));
+        }
+    }
+
+    fn flush(&self) {
+        if let Ok(mut file) = self.file.lock()
+            && let Some(log_file) = file.as_mut()
+        {
+            let _ = log_file.file.flush();
+        }
+    }
+}
+
+// an append-only log that rotates itself by size
+struct LogFile {
+    path: PathBuf,
+    file: File,
+    size: u64,
+}
+
+impl LogFile {
+    fn open(path: &Path) -> io::Result<Self> {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        if fs::metadata(path).is_ok_and(|m| m.len() >= MAX_LOG_BYTES) {
+            rotate(path)?;
+        }
+        let file = OpenOptions::new().create(true).append(true).open(path)?;
+        let size = file.metadata()?.len();
+        Ok(LogFile {
+            path: path.to_path_buf(),
+            file,
+            size,
+        })
+    }
+
+    fn write_line(&mut self, line: &str) -> io::Result<()> {
+        if self.size > 0 && self.size + line.len() as u64 + 1 > MAX_LOG_BYTES {
+            *self = LogFile::open_rotated(&self.path)?;
+        }
+        writeln!(self.file, "{}", line)?;
+        self.size += line.len() as u64 + 1;
+        Ok(())
+    }
+
+    fn open_rotated(path: &Path) -> io::Result<Self> {
+        rotate(path)?;
+        LogFile::open(path)
+    }
+}
+
+// shift kiroku.log to kiroku.log.1, .1 to .2 and so on, dropping the oldest
+pub fn rotate(path: &Path) -> io::Result<()> {
+    let numbered = |n: usize| {
+        let mut name = path.as_os_str().to_os_string();
+        name.push(format!(".{}", n));
+        PathBuf::from(name)
+    };
+
+    for n in (1..KEPT_LOGS).rev() {
+        let from = numbered(n);
+        if from.exists() {
+            fs::rename(&from, numbered(n + 1))?;
+        }
+    }
+    if path.exists() {
+        fs::rename(path, numbered(1))?;
+    }
+    Ok(())
+}
+
+// where the log file lives: $XDG_STATE_HOME/kiroku/kiroku.log
+pub fn log_path() -> Result<PathBuf> {
+    let state_dir = dirs::state_dir()
+        .or_else(dirs::data_local_dir)
+        .ok_or_else(|| anyhow!("could not find state directory"))?;
+    Ok(state_dir.join("kiroku").join("kiroku.log"))
+}
+
+// pick the log level: the flag wins over KIROKU_LOG, which wins over info
+pub fn resolve_level(flag: Option<LevelFilter>, env: Option<&str>) -> Result<LevelFilter> {
+    if let Some(level) = flag {
+        return Ok(level);
+    }
+    match env.map(str::trim).filter(|s| !s.is_empty()) {
+        Some(value) => parse_level(value).map_err(|e| anyhow!("KIROKU_LOG: {}", e)),
+        None => Ok(LevelFilter::Info),
+    }
+}
+
+pub fn parse_level(value: &str) -> Result<LevelFilter> {
+    value.parse().map_err(|_| {
+        anyhow!(
+            "unknown log level '{}' (use off, error, warn, info, debug or trace)",
+            value
+        )
+    })
+}
+
+// install the logger; call once at startup
+pub fn init(level: LevelFilter, file: Option<&Path>) -> Result<()> {
+    let file_error = match file.map(LogFile::open) {
+        Some(Ok(log_file)) => {
+            if let Ok(mut slot) = LOGGER.file.lock() {
+                *slot = Some(log_file);
+            }
+            None
+        }
+        Some(Err(e)) => Some(e),
+        None => None,
+    };
+
+    log::set_logger(&LOGGER)?;
+    log::set_max_level(level);
+    tui_logger::set_default_level(level);
+
+    if let (Some(e), Some(path)) = (file_error, file) {
+        log::warn!("Could not open log file {}: {}", path.display(), e);
+    }
+    Ok(())
+}
+
+// feed the log widget; only the tui has one, so commands skip it
+pub fn start_widget() -> Result<()> {
+    if WIDGET.swap(true, Ordering::SeqCst) {
+        return Ok(());
+    }
+    // the widget only shows what has been moved out of its hot buffer
+    thread::Builder::new().name("log-mover".into()).spawn(|| {
+        loop {
+            thread::sleep(Duration::from_millis(10));
+            tui_logger::move_events();
+        }
+    })?;
+    Ok(())
+}
+
+// the last lines logged, oldest first; none when the logger is busy, since
+// the panic hook may be running while this thread holds the lock
+pub fn recent_lines() -> Option<Vec<String>> {
+    LOGGER
+        .recent
+        .try_lock()
+        .ok()
+        .map(|recent| recent.iter().cloned().collect())
+}
@@ -1,62 +1,122 @@
 use anyhow::Result;
 use arboard::Clipboard;
 use crossterm::{
+    event::{EnableFocusChange, EnableMouseCapture},
     execute,
-    terminal::{EnterAlternateScreen, LeaveAlternateScreen, disable_raw_mode, enable_raw_mode},
+    terminal::{EnterAlternateScreen, enable_raw_mode},
 };
 use kiroku_tui::{
     app::{Action, App, InputMode},
-    config, data,
+    attachments, cli, config, crash, data,
     errors::KirokuError,
-    events::{AppEvent, EventHandler},
-    ops, ui,
+    events::{AppEvent, EventHandler, debounce_paths},
+    logging, ops, state, sync, theme, ui,
 };
 use notify::{RecursiveMode, Watcher};
 use ratatui::{Terminal, backend::CrosstermBackend};
 use std::fs;
 use std::io;
 use std::path::PathBuf;
+use std::time::{Duration, Instant};
 
 // main entry point for the application
 fn main() -> Result<()> {
-    tui_logger::init_logger(log::LevelFilter::Info).unwrap();
-    tui_logger::set_default_level(log::LevelFilter::Info);
-
-    // setup directory using cli arg or default
-    let args: Vec<String> = std::env::args().collect();
-    let kiroku_path = if args.len() > 1 {
-        PathBuf::from(&args[1])
-    } else {
-        let home_dir =
-            dirs::home_dir().ok_or_else(|| anyhow::anyhow!("could not find home directory"))?;
-        home_dir.join("kiroku")
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let cli = match cli::parse_args(&args) {
+        Ok(cli) => cli,
+        Err(e) => {
+            eprintln!("kiroku: {}\n\n{}", e, cli::USAGE);
+            std::process::exit(2);
+        }
+    };
+
+    let env_level = std::env::var("KIROKU_LOG").ok();
+    let level = match logging::resolve_level(cli.log_level, env_level.as_deref()) {
+        Ok(level) => level,
+        Err(e) => {
+            eprintln!("kiroku: {}", e);
+            std::process::exit(2);
+        }
     };
+    logging::init(level, logging::log_path().ok().as_deref())?;
+
+    if cli.command == cli::Command::Help {
+        print!("{}", cli::USAGE);
+        return Ok(());
+    }
+
+    // load configuration, keeping what is wrong with it to show the user
+    let (config, issues) = match config::load_config_checked() {
+        Ok(loaded) => loaded,
+        Err(e) => (
+            config::Config::default(),
+            vec![config::ConfigIssue::error("", format!("{:#}", e))],
+        ),
+    };
+    for issue in &issues {
+        log::warn!("Config: {}", issue);
+    }
+
+    // pick the notebook: --vault name or path, last used vault, or ~/kiroku
+    let last_vault = state::state_path()
+        .ok()
+        .and_then(|path| state::State::load(&path).last_vault);
+    let (vault_name, kiroku_path) =
+        config.resolve_vault(cli.vault.as_deref(), last_vault.as_deref())?;
 
     // create notebook directory if missing
-    if !kiroku_path.exists() {
+    let needs_vault = !matches!(cli.command, cli::Command::Config(_) | cli::Command::Log(_));
+    if !kiroku_path.exists() && needs_vault {
         fs::create_dir_all(&kiroku_path)?;
-        println!("created new notebook directory at {:?}", kiroku_path);
+        eprintln!("created new notebook directory at {:?}", kiroku_path);
     }
-
-    // load configuration
-    let config = match config::load_config() {
-        Ok(c) => c,
-        Err(e) => {
-            log::warn!("Failed to load config: {}", e);
-            config::Config::default()
+    // the watcher reports absolute paths, so the vault path has to be one too
+    let kiroku_path = kiroku_path.canonicalize().unwrap_or(kiroku_path);
+
+    match cli.command {
+        cli::Command::Tui => run_tui(kiroku_path, vault_name, config, issues),
+        command => {
+            if needs_vault {
+                for issue in &issues {
+                    eprintln!("kiroku: config: {}", issue);
+                }
+            }
+            let config = config.effective(vault_name.as_deref(), &kiroku_path);
+            if let Err(e) = cli::run(command, &kiroku_path, &config) {
+                eprintln!("kiroku: {:#}", e);
+                std::process::exit(1);
+            }
+            Ok(())
         }
-    };
+    }
+}
+
+// run the interactive interface
+fn run_tui(
+    kiroku_path: PathBuf,
+    vault_name: Option<String>,
+    global_config: config::Config,
+    config_issues: Vec<config::ConfigIssue>,
+) -> Result<()> {
+    logging::start_widget()?;
 
     // setup terminal
+    crash::install_panic_hook();
+    let mouse = global_config.mouse.unwrap_or(true);
     enable_raw_mode()?;
     let mut stdout = io::stdout();
-    execute!(stdout, EnterAlternateScreen)?;
+    execute!(stdout, EnterAlternateScreen, EnableFocusChange)?;
+    if mouse {
+        execute!(stdout, EnableMouseCapture)?;
+    }
     let backend = CrosstermBackend::new(stdout);
     let mut terminal = Terminal::new(backend)?;
 
     // load notes from directory
     let path_str = kiroku_path.to_string_lossy().to_string();
-    let notes = match data::load_notes(&path_str) {
+    let config = global_config.effective(vault_name.as_deref(), &kiroku_path);
+    let ignore = data::IgnoreRules::from_config(&kiroku_path, &config);
+    let notes = match data::load_notes_with(&path_str, &ignore) {
         Ok(n) => n,
         Err(e) => {
             log::error!("Failed to load notes: {}", e);
@@ -64,45 +124,91 @@ fn main() -> Result<()> {
         }
     };
     let mut app = App::new(notes, kiroku_path.clone(), config);
+    app.vault_name = vault_name;
+    if let Ok(dir) = theme::themes_dir() {
+        app.themes = theme::load_theme_files(&dir);
+    }
+    let color_mode = theme::ColorMode::from_config(&app.config);
+    log::info!("Color mode: {:?}", color_mode);
+    app.set_color_mode(color_mode);
+    app.state_file = state::state_path().ok();
+    app.restore_state();
+    if let Some(first) = config_issues
+        .iter()
+        .find(|i| i.severity == config::Severity::Error)
+        .or(config_issues.first())
+    {
+        app.status_msg = format!(
+            " Config {} ({} problem(s), run `kiroku config check`) ",
+            first,
+            config_issues.len()
+        );
+    }
 
     // setup event handler
     let events = EventHandler::new(250);
 
-    // setup file watcher
-    let tx = events.sender.clone();
+    // setup file watcher, batching changes until they settle
+    let changes = debounce_paths(events.sender.clone(), Duration::from_millis(200));
     let mut watcher = notify::recommended_watcher(move |res: notify::Result<notify::Event>| {
         if let Ok(event) = res
             && (event.kind.is_modify() || event.kind.is_create() || event.kind.is_remove())
         {
-            let _ = tx.send(AppEvent::FileChanged);
+            // git's own bookkeeping is not a change to the notes
+            let paths: Vec<PathBuf> = event
+                .paths
+                .into_iter()
+                .filter(|p| !p.components().any(|c| c.as_os_str() == ".git"))
+                .collect();
+            if !paths.is_empty() {
+                let _ = changes.send(paths);
+            }
         }
     })?;
-    watcher.watch(&kiroku_path, RecursiveMode::NonRecursive)?;
+    watcher.watch(&kiroku_path, RecursiveMode::Recursive)?;
 
     // main loop
     while !app.should_quit {
         terminal.draw(|f| ui::ui(f, &mut app))?;
 
         match events.next()? {
-            AppEvent::Input(key) => {
-                let action = app.handle_input(key);
+            event @ (AppEvent::Input(_) | AppEvent::Mouse(_)) => {
+                let action = match event {
+                    AppEvent::Mouse(mouse) => app.handle_mouse(mouse, Instant::now()),
+                    AppEvent::Input(key) => app.handle_input(key),
+                    _ => Action::None,
+                };
                 match action {
                     Action::Quit => {
+                        // a background commit or push still holds the repo, let it finish
+                        // rather than racing it or exiting halfway through
+                        if app.syncing {
+                            app.status_msg = String::from("Waiting for background sync...");
+                            while app.syncing {
+                                terminal.draw(|f| ui::ui(f, &mut app))?;
+                                match events.next()? {
+                                    AppEvent::AutoSynced(result) => app.finish_auto_sync(result),
+                                    AppEvent::Tick => app.tick(),
+                                    _ => {}
+                                }
+                            }
+                        }
                         if app.config.auto_sync.unwrap_or(false) {
                             app.syncing = true;
 
                             events.pause();
-                            let _ = disable_raw_mode();
-                            let _ = execute!(terminal.backend_mut(), LeaveAlternateScreen);
+                            let _ = ops::suspend_tui(mouse);
                             let _ = terminal.show_cursor();
 
-                            println!("Auto-syncing with git before exit...");
-                            if let Err(e) = ops::run_git_sync(&app.base_path) {
+                            let backend = sync::backend_from_config(&app.config);
+                            println!("Auto-syncing with {} before exit...", backend.name());
+                            if let Err(e) = backend.sync(&app.base_path) {
                                 log::error!("Auto-sync failed: {}", e);
                                 println!("Auto-sync failed: {}", e);
                                 std::thread::sleep(std::time::Duration::from_secs(2));
                             }
                         }
+                        app.save_state();
                         app.quit()
                     }
                     Action::ToggleLogs => {
@@ -116,23 +222,19 @@ fn main() -> Result<()> {
                             std::thread::sleep(std::time::Duration::from_millis(300));
 
                             // suspend tui for shell commands
-                            let _ = disable_raw_mode();
-                            let _ = execute!(terminal.backend_mut(), LeaveAlternateScreen);
+                            let _ = ops::suspend_tui(mouse);
                             let _ = terminal.show_cursor();
 
                             use std::io::Write;
                             let _ = io::stdout().flush();
 
-                            println!("Syncing with git...");
-                            println!("Repository path: {:?}", app.base_path);
-                            println!("(If prompted for password, input will be hidden)");
+                            let backend = sync::backend_from_config(&app.config);
+                            println!("Syncing with {}...", backend.name());
 
-                            // run git sync
-                            let result =
-                                ops::run_git_sync(&app.base_path).map_err(|e| e.to_string());
+                            // run configured sync backend
+                            let result = backend.sync(&app.base_path).map_err(|e| e.to_string());
 
-                            let _ = execute!(terminal.backend_mut(), EnterAlternateScreen);
-                            let _ = enable_raw_mode();
+                            let _ = ops::resume_tui(mouse);
                             let _ = terminal.clear();
 
                             events.resume();
@@ -143,6 +245,7 @@ fn main() -> Result<()> {
                                 Ok(msg) => {
                                     log::info!("Sync successful: {}", msg);
                                     app.status_msg = msg;
+                                    app.last_sync = Some(chrono::Local::now());
                                 }
                                 Err(e) => {
                                     log::error!("Sync failed: {}", e);
@@ -161,19 +264,39 @@ fn main() -> Result<()> {
                         app.input.clear();
                         app.status_msg = String::from("Enter folder name: ");
                     }
+                    Action::AttachFile => {
+                        if app.selected_note().is_some() {
+                            app.input_mode = InputMode::Attaching;
+                            app.input.clear();
+                            app.status_msg = String::from("Enter the path of the file: ");
+                        } else {
+                            app.status_msg = String::from("Select a note to attach to.");
+                        }
+                    }
                     Action::RenameNote => {
                         if let Some(i) = app.list_state.selected() {
+                            // notes are renamed in place, so offer the file name
+                            let stem = |n: &data::Note| {
+                                n.path.file_stem().map(|s| s.to_string_lossy().to_string())
+                            };
                             let title = if !app.search_query.is_empty() {
                                 if i < app.notes.len() {
-                                    Some(app.notes[i].title.clone())
+                                    stem(&app.notes[i])
                                 } else {
                                     None
                                 }
                             } else if i < app.fs_items.len() {
                                 match &app.fs_items[i] {
-                                    data::FileSystemItem::Note(n) => Some(n.title.clone()),
-                                    data::FileSystemItem::Folder(p) => {
-                                        Some(p.file_name().unwrap().to_string_lossy().to_string())
+                                    data::FileSystemItem::Note(n) => stem(n),
+                                    data::FileSystemItem::Folder(p) => Some(
+                                        p.file_name()
+                                            .unwrap_or(p.as_os_str())
+                                            .to_string_lossy()
+                                            .to_string(),
+                                    ),
+                                    // the extension is kept on rename
+                                    data::FileSystemItem::Attachment(p) => {
+                                        p.file_stem().map(|s| s.to_string_lossy().to_string())
                                     }
                                 }
                             } else {
@@ -198,9 +321,13 @@ fn main() -> Result<()> {
                             } else if i < app.fs_items.len() {
                                 match &app.fs_items[i] {
                                     data::FileSystemItem::Note(n) => Some(n.title.clone()),
-                                    data::FileSystemItem::Folder(p) => {
-                                        Some(p.file_name().unwrap().to_string_lossy().to_string())
-                                    }
+                                    data::FileSystemItem::Folder(p)
+                                    | data::FileSystemItem::Attachment(p) => Some(
+                                        p.file_name()
+                                            .unwrap_or(p.as_os_str())
+                                            .to_string_lossy()
+                                            .to_string(),
+                                    ),
                                 }
                             } else {
                                 None
@@ -219,30 +346,45 @@ fn main() -> Result<()> {
                         app.input.pop();
                     }
                     Action::SubmitInput => match app.input_mode {
-                        InputMode::Editing => {
-                            if !app.input.trim().is_empty() {
-                                // create relative to current_dir
-                                let target_path = app.base_path.join(&app.current_dir);
-                                match ops::create_note(&target_path, &app.input) {
-                                    Ok(path) => {
-                                        events.pause();
-                                        if let Err(e) = ops::open_editor(
-                                            &app.base_path,
-                                            Some(&path),
-                                            app.config.editor_cmd.as_deref(),
-                                        ) {
-                                            log::error!("Failed to open editor: {}", e);
+                        InputMode::Editing if !app.input.trim().is_empty() => {
+                            // create relative to current_dir
+                            let target_path = app.base_path.join(&app.current_dir);
+                            match ops::create_note(&target_path, &app.input, &app.config) {
+                                Ok(path) => {
+                                    let date = chrono::Local::now().date_naive();
+                                    match ops::note_template(
+                                        &app.base_path,
+                                        &app.config,
+                                        app.input.trim(),
+                                        date,
+                                    ) {
+                                        Ok(Some(content)) => {
+                                            if let Err(e) = fs::write(&path, content) {
+                                                log::error!("Failed to apply template: {}", e);
+                                            }
                                         }
-                                        events.resume();
-                                        app.input_mode = InputMode::Normal;
-                                        app.status_msg = String::from("Note created.");
-                                        // refresh view to show new file
-                                        app.refresh_fs_view();
-                                        terminal.clear()?;
+                                        Ok(None) => {}
+                                        Err(e) => log::error!("Failed to apply template: {}", e),
                                     }
-                                    Err(e) => {
-                                        app.status_msg = format!("Error: {}", e);
+                                    app.remember_recent(&path);
+                                    events.pause();
+                                    if let Err(e) = ops::open_editor(
+                                        &app.base_path,
+                                        Some(&path),
+                                        app.config.editor_cmd.as_deref(),
+                                        mouse,
+                                    ) {
+                                        log::error!("Failed to open editor: {}", e);
                                     }
+                                    events.resume();
+                                    app.input_mode = InputMode::Normal;
+                                    app.status_msg = String::from("Note created.");
+                                    // refresh view to show new file
+                                    app.refresh_fs_view();
+                                    terminal.clear()?;
+                                }
+                                Err(e) => {
+                                    app.status_msg = format!("Error: {}", e);
                                 }
                             }
                         }
@@ -259,14 +401,15 @@ fn main() -> Result<()> {
                                 } else if i < app.fs_items.len() {
                                     match &app.fs_items[i] {
                                         data::FileSystemItem::Note(n) => Some(n.path.clone()),
-                                        data::FileSystemItem::Folder(p) => Some(p.clone()),
+                                        data::FileSystemItem::Folder(p)
+                                        | data::FileSystemItem::Attachment(p) => Some(p.clone()),
                                     }
                                 } else {
                                     None
                                 };
 
                                 if let Some(path) = old_path {
-                                    match ops::rename_note(&path, &app.input) {
+                                    match ops::rename_note(&path, &app.input, &app.config) {
                                         Ok(_) => {
                                             app.input_mode = InputMode::Normal;
                                             app.status_msg = String::from("Item renamed.");
@@ -290,7 +433,8 @@ fn main() -> Result<()> {
                                 } else if i < app.fs_items.len() {
                                     match &app.fs_items[i] {
                                         data::FileSystemItem::Note(n) => Some(n.path.clone()),
-                                        data::FileSystemItem::Folder(p) => Some(p.clone()),
+                                        data::FileSystemItem::Folder(p)
+                                        | data::FileSystemItem::Attachment(p) => Some(p.clone()),
                                     }
                                 } else {
                                     None
@@ -313,18 +457,50 @@ fn main() -> Result<()> {
                             }
                             app.input_mode = InputMode::Normal;
                         }
-                        InputMode::CreatingFolder => {
-                            if !app.input.trim().is_empty() {
-                                let target_path = app.base_path.join(&app.current_dir);
-                                match ops::create_folder(&target_path, &app.input) {
-                                    Ok(_) => {
-                                        app.input_mode = InputMode::Normal;
-                                        app.status_msg = String::from("Folder created.");
-                                        app.refresh_fs_view();
-                                    }
-                                    Err(e) => {
-                                        app.status_msg = format!("Error: {}", e);
-                                    }
+                        InputMode::Attaching if !app.input.trim().is_empty() => {
+                            let Some(note) = app.selected_note().map(|n| n.path.clone()) else {
+                                app.input_mode = InputMode::Normal;
+                                continue;
+                            };
+                            let source = config::expand_home(app.input.trim());
+                            let attached = attachments::attach_file(
+                                &app.base_path,
+                                &note,
+                                &source,
+                                &app.config,
+                            )
+                            .and_then(|path| {
+                                let link = attachments::link_for(&note, &path);
+                                ops::append_to_note(&note, &link)?;
+                                Ok(path)
+                            });
+                            match attached {
+                                Ok(path) => {
+                                    app.input_mode = InputMode::Normal;
+                                    app.status_msg = format!(
+                                        "Attached {}.",
+                                        path.strip_prefix(&app.base_path)
+                                            .unwrap_or(&path)
+                                            .display()
+                                    );
+                                    app.refresh_notes();
+                                }
+                                Err(e) => {
+                                    log::error!("Failed to attach {:?}: {}", source, e);
+                                    app.status_msg = format!("Attach error: {}", e);
+                                }
+                            }
+                        }
+                        InputMode::CreatingFolder if !app.input.trim().is_empty() => {
+                            let target_path = app.base_path.join(&app.current_dir);
+                            match ops::create_folder(&target_path, &app.input) {
+                                Ok(_) => {
+                                    app.input_mode = InputMode::Normal;
+                                    app.status_msg = String::from("Folder created.");
+                                    app.refresh_fs_view();
+                                }
+                                Err(e) => {
+                                    app.status_msg = format!("Error: {}", e);
                                 }
                             }
                         }
@@ -346,18 +522,21 @@ fn main() -> Result<()> {
                             } else if i < app.fs_items.len() {
                                 match &app.fs_items[i] {
                                     data::FileSystemItem::Note(n) => Some(n.path.clone()),
-                                    data::FileSystemItem::Folder(_) => None, // cannot edit folder
+                                    // folders and attachments are not edited
+                                    _ => None,
                                 }
                             } else {
                                 None
                             };
 
                             if let Some(p) = path {
+                                app.remember_recent(&p);
                                 events.pause();
                                 if let Err(e) = ops::open_editor(
                                     &app.base_path,
                                     Some(&p),
                                     app.config.editor_cmd.as_deref(),
+                                    mouse,
                                 ) {
                                     log::error!("Failed to open editor for {:?}: {}", p, e);
                                     app.status_msg = format!("Editor error: {}", e);
@@ -368,6 +547,74 @@ fn main() -> Result<()> {
                             }
                         }
                     }
+                    Action::OpenNote(p) => {
+                        app.remember_recent(&p);
+                        events.pause();
+                        if let Err(e) = ops::open_editor(
+                            &app.base_path,
+                            Some(&p),
+                            app.config.editor_cmd.as_deref(),
+                            mouse,
+                        ) {
+                            log::error!("Failed to open editor for {:?}: {}", p, e);
+                            app.status_msg = format!("Editor error: {}", e);
+                        } else {
+                            terminal.clear()?;
+                        }
+                        events.resume();
+                    }
+                    Action::OpenLog => {
+                        let Ok(path) = logging::log_path() else {
+                            app.status_msg = String::from("Could not find the log file.");
+                            continue;
+                        };
+                        events.pause();
+                        if let Err(e) = ops::open_editor(
+                            &app.base_path,
+                            Some(&path),
+                            app.config.editor_cmd.as_deref(),
+                            mouse,
+                        ) {
+                            log::error!("Failed to open log file {:?}: {}", path, e);
+                            app.status_msg = format!("Editor error: {}", e);
+                        } else {
+                            terminal.clear()?;
+                        }
+                        events.resume();
+                    }
+                    Action::SwitchVault(name) => {
+                        let Some(vault) = global_config.vault(&name) else {
+                            continue;
+                        };
+                        let path = vault.path();
+                        if let Err(e) = fs::create_dir_all(&path) {
+                            app.status_msg = format!("Cannot open vault '{}': {}", name, e);
+                            continue;
+                        }
+                        let path = path.canonicalize().unwrap_or(path);
+
+                        let config = global_config.effective(Some(&vault.name), &path);
+                        let ignore = data::IgnoreRules::from_config(&path, &config);
+                        let notes = match data::load_notes_with(&path.to_string_lossy(), &ignore) {
+                            Ok(n) => n,
+                            Err(e) => {
+                                log::error!("Failed to load notes: {}", e);
+                                vec![]
+                            }
+                        };
+
+                        // move the watcher over to the new vault
+                        let _ = watcher.unwatch(&app.base_path);
+                        if let Err(e) = watcher.watch(&path, RecursiveMode::Recursive) {
+                            log::error!("Failed to watch {:?}: {}", path, e);
+                        }
+
+                        app.save_state();
+                        app.load_vault(Some(vault.name.clone()), path, notes, config);
+                        app.save_state();
+                        app.status_msg = format!("Switched to vault '{}'.", vault.name);
+                        log::info!("Switched to vault {:?}", vault.name);
+                    }
                     Action::CopyContent => {
                         if let Some(i) = app.list_state.selected() {
                             let content = if !app.search_query.is_empty() {
@@ -379,7 +626,7 @@ fn main() -> Result<()> {
                             } else if i < app.fs_items.len() {
                                 match &app.fs_items[i] {
                                     data::FileSystemItem::Note(n) => n.content.clone(),
-                                    data::FileSystemItem::Folder(_) => None,
+                                    _ => None,
                                 }
                             } else {
                                 None
@@ -421,14 +668,7 @@ fn main() -> Result<()> {
                                     None
                                 }
                             } else if i < app.fs_items.len() {
-                                match &app.fs_items[i] {
-                                    data::FileSystemItem::Note(n) => {
-                                        Some(n.path.to_string_lossy().to_string())
-                                    }
-                                    data::FileSystemItem::Folder(p) => {
-                                        Some(p.to_string_lossy().to_string())
-                                    }
-                                }
+                                Some(app.fs_items[i].path().to_string_lossy().to_string())
                             } else {
                                 None
                             };
@@ -462,30 +702,75 @@ fn main() -> Result<()> {
                     Action::CycleSort => {
                         app.sort_mode = app.sort_mode.next();
                         app.sort_notes();
-                        app.save_config();
+                        app.save_state();
                     }
                     Action::CycleTheme => {
                         app.cycle_theme();
                     }
+                    Action::OpenUrl(target) => match ops::open_external(&target) {
+                        Ok(()) => app.status_msg = format!("Opened {}", target),
+                        Err(e) => {
+                            log::error!("Failed to open {}: {}", target, e);
+                            app.status_msg = format!("Open error: {}", e);
+                        }
+                    },
+                    Action::Suspend => {
+                        events.pause();
+                        let _ = ops::suspend_tui(mouse);
+                        let _ = terminal.show_cursor();
+
+                        if let Err(e) = ops::stop_process() {
+                            log::error!("Failed to suspend: {}", e);
+                        }
+
+                        // back from fg
+                        let _ = ops::resume_tui(mouse);
+                        let _ = terminal.clear();
+                        events.resume();
+                        app.refresh_notes();
+                    }
                     Action::None => {}
                 }
             }
+            AppEvent::Resize(..) => {
+                // the next draw picks up the new size; clear leftovers first
+                terminal.autoresize()?;
+                terminal.clear()?;
+            }
+            AppEvent::FocusGained => {
+                // notes may have been edited elsewhere meanwhile
+                app.refresh_notes();
+            }
+            AppEvent::FocusLost => {}
             AppEvent::Tick => {
                 app.tick();
+
+                // kick off interval auto-commit / auto-push off the ui thread
+                if let Some(job) = app.poll_auto_sync(Instant::now()) {
+                    let tx = events.sender.clone();
+                    let base_path = app.base_path.clone();
+                    let backend = sync::backend_from_config(&app.config);
+                    std::thread::spawn(move || {
+                        let result = backend
+                            .auto_sync(&base_path, job.commit, job.push)
+                            .map_err(|e| e.to_string());
+                        let _ = tx.send(AppEvent::AutoSynced(result));
+                    });
+                }
+            }
+            AppEvent::AutoSynced(result) => {
+                app.finish_auto_sync(result);
             }
-            AppEvent::FileChanged => {
-                let path_str = app.base_path.to_string_lossy().to_string();
-                if let Ok(notes) = data::load_notes(&path_str) {
-                    app.all_notes = notes;
-                    app.update_search();
+            AppEvent::FileChanged(paths) => {
+                if app.apply_file_changes(&paths) {
+                    app.mark_changed(Instant::now());
                 }
             }
         }
     }
 
     // restore terminal state
-    disable_raw_mode()?;
-    execute!(terminal.backend_mut(), LeaveAlternateScreen)?;
+    ops::suspend_tui(mouse)?;
     terminal.show_cursor()?;
 
     Ok(())
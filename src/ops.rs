@@ -1,22 +1,83 @@
+use crate::config::{self, Config};
 use crate::errors::KirokuError;
+use chrono::{DateTime, Local, NaiveDate};
 use crossterm::{
+    event::{DisableFocusChange, DisableMouseCapture, EnableFocusChange, EnableMouseCapture},
     execute,
     terminal::{EnterAlternateScreen, LeaveAlternateScreen, disable_raw_mode, enable_raw_mode},
 };
+use std::fmt::{self, Write as _};
 use std::fs;
-use std::io;
+use std::io::{self, Write};
 use std::path::{Path, PathBuf};
 use std::process::Command;
 
-// open user editor
+// hand the terminal back to the shell
+pub fn suspend_tui(mouse: bool) -> io::Result<()> {
+    disable_raw_mode()?;
+    if mouse {
+        execute!(io::stdout(), DisableMouseCapture)?;
+    }
+    execute!(io::stdout(), DisableFocusChange, LeaveAlternateScreen)
+}
+
+// take the terminal back for the tui
+pub fn resume_tui(mouse: bool) -> io::Result<()> {
+    execute!(io::stdout(), EnterAlternateScreen, EnableFocusChange)?;
+    if mouse {
+        execute!(io::stdout(), EnableMouseCapture)?;
+    }
+    enable_raw_mode()
+}
+
+// stop the process like a shell job on Ctrl+Z; returns once it is
+// continued with fg. raw mode turns Ctrl+Z into a key, so this is
+// raised by hand
+pub fn stop_process() -> Result<(), KirokuError> {
+    #[cfg(unix)]
+    signal_hook::low_level::raise(signal_hook::consts::SIGTSTP)?;
+    Ok(())
+}
+
+// open user editor, suspending the tui
 pub fn open_editor(
     base_path: &Path,
     file_path: Option<&PathBuf>,
     editor_cmd: Option<&str>,
+    mouse: bool,
 ) -> Result<(), KirokuError> {
-    disable_raw_mode()?;
-    execute!(io::stdout(), LeaveAlternateScreen)?;
+    suspend_tui(mouse)?;
 
+    let result = run_editor(base_path, file_path, editor_cmd);
+
+    resume_tui(mouse)?;
+
+    result
+}
+
+// open a url or file with the desktop's default program
+pub fn open_external(target: &str) -> Result<(), KirokuError> {
+    let opener = if cfg!(target_os = "macos") {
+        "open"
+    } else {
+        "xdg-open"
+    };
+    Command::new(opener)
+        .arg(target)
+        .stdin(std::process::Stdio::null())
+        .stdout(std::process::Stdio::null())
+        .stderr(std::process::Stdio::null())
+        .spawn()
+        .map_err(|e| KirokuError::Env(format!("Could not run {}: {}", opener, e)))?;
+    Ok(())
+}
+
+// run user editor in the current terminal
+pub fn run_editor(
+    base_path: &Path,
+    file_path: Option<&PathBuf>,
+    editor_cmd: Option<&str>,
+) -> Result<(), KirokuError> {
     let editor = if let Some(cmd) = editor_cmd {
         cmd.to_string()
     } else {
@@ -32,9 +93,6 @@ pub fn open_editor(
 
     let status = cmd.status().map_err(KirokuError::Io)?;
 
-    execute!(io::stdout(), EnterAlternateScreen)?;
-    enable_raw_mode()?;
-
     if !status.success() {
         return Err(KirokuError::Env(
             "Editor exited with non-zero status".into(),
@@ -44,11 +102,106 @@ pub fn open_editor(
     Ok(())
 }
 
-// create markdown file
-pub fn create_note(base_path: &Path, filename: &str) -> Result<PathBuf, KirokuError> {
+// path of the journal note for a day
+pub fn journal_path(
+    base_path: &Path,
+    config: &Config,
+    date: NaiveDate,
+) -> Result<PathBuf, KirokuError> {
+    let dir = config.journal_dir.as_deref().unwrap_or("journal");
+    let format = config.journal_format.as_deref().unwrap_or("%Y-%m-%d");
+    let name = strftime(date.format(format), format)?;
+    Ok(base_path
+        .join(dir)
+        .join(format!("{}.{}", name, config.default_extension())))
+}
+
+// a chrono date rendered with a user's pattern. to_string() panics on a
+// bad pattern, writing into a string reports it instead
+fn strftime(formatted: impl fmt::Display, format: &str) -> Result<String, KirokuError> {
+    let mut out = String::new();
+    write!(out, "{}", formatted)
+        .map_err(|_| KirokuError::Env(format!("'{}' is not a valid strftime pattern", format)))?;
+    Ok(out)
+}
+
+// create the journal note for a day if missing
+pub fn ensure_journal_note(
+    base_path: &Path,
+    config: &Config,
+    date: NaiveDate,
+) -> Result<PathBuf, KirokuError> {
+    let path = journal_path(base_path, config, date)?;
+    if !path.exists() {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let day = date.format("%Y-%m-%d").to_string();
+        let content = match &config.journal_template {
+            Some(template) => render_template(base_path, template, &day, date)?,
+            None => format!("# {}\n\n", day),
+        };
+        fs::write(&path, content)?;
+    }
+    Ok(path)
+}
+
+// fill a template file from the vault with {title} and {date}
+pub fn render_template(
+    base_path: &Path,
+    template: &str,
+    title: &str,
+    date: NaiveDate,
+) -> Result<String, KirokuError> {
+    // templates may come from a shared vault config, so keep them inside it
+    if !config::stays_inside(template) {
+        return Err(KirokuError::Env(format!(
+            "template {:?} is outside the notebook",
+            template
+        )));
+    }
+    let template_path = base_path.join(template);
+    let content = fs::read_to_string(&template_path).map_err(|e| {
+        KirokuError::Env(format!("cannot read template {:?}: {}", template_path, e))
+    })?;
+    Ok(content
+        .replace("{title}", title)
+        .replace("{date}", &date.format("%Y-%m-%d").to_string()))
+}
+
+// initial content for a new note from the configured template
+pub fn note_template(
+    base_path: &Path,
+    config: &Config,
+    title: &str,
+    date: NaiveDate,
+) -> Result<Option<String>, KirokuError> {
+    match &config.note_template {
+        Some(template) => Ok(Some(render_template(base_path, template, title, date)?)),
+        None => Ok(None),
+    }
+}
+
+// create a note file, with the default extension unless the name has a
+// note extension already
+pub fn create_note(
+    base_path: &Path,
+    filename: &str,
+    config: &Config,
+) -> Result<PathBuf, KirokuError> {
     let mut safe_filename = filename.trim().replace(" ", "_");
-    if !safe_filename.ends_with(".md") {
-        safe_filename.push_str(".md");
+    let has_extension = Path::new(&safe_filename)
+        .extension()
+        .and_then(|e| e.to_str())
+        .is_some_and(|ext| {
+            config
+                .note_extensions()
+                .iter()
+                .any(|e| e.eq_ignore_ascii_case(ext))
+        });
+    if !has_extension {
+        safe_filename.push('.');
+        safe_filename.push_str(&config.default_extension());
     }
 
     let path = base_path.join(&safe_filename);
@@ -68,6 +221,67 @@ pub fn create_note(base_path: &Path, filename: &str) -> Result<PathBuf, KirokuEr
     Ok(path)
 }
 
+// note that captured entries go to
+pub fn capture_path(
+    base_path: &Path,
+    config: &Config,
+    target: Option<&str>,
+    date: NaiveDate,
+) -> Result<PathBuf, KirokuError> {
+    let target = target
+        .or(config.capture_target.as_deref())
+        .unwrap_or("journal");
+
+    if target == "journal" {
+        return ensure_journal_note(base_path, config, date);
+    }
+
+    let mut path = base_path.join(target);
+    if path.extension().is_none() {
+        path.set_extension(config.default_extension());
+    }
+    Ok(path)
+}
+
+// render a capture entry from the configured format
+pub fn format_capture(
+    format: &str,
+    text: &str,
+    now: DateTime<Local>,
+) -> Result<String, KirokuError> {
+    // continuation lines stay inside the entry
+    let text = text.trim_end().replace('\n', "\n  ");
+    Ok(strftime(now.format(format), format)?.replace("{text}", &text))
+}
+
+// append an entry to a note, creating it if missing
+pub fn append_to_note(path: &Path, entry: &str) -> Result<(), KirokuError> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+
+    // start on a fresh line if the file does not end with one
+    let needs_newline = match fs::read(path) {
+        Ok(bytes) => !bytes.is_empty() && !bytes.ends_with(b"\n"),
+        Err(_) => false,
+    };
+
+    let mut chunk = String::new();
+    if needs_newline {
+        chunk.push('\n');
+    }
+    chunk.push_str(entry);
+    chunk.push('\n');
+
+    // single appending write so a running editor or tui never sees half an entry
+    let mut file = fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)?;
+    file.write_all(chunk.as_bytes())?;
+    Ok(())
+}
+
 // create directory
 pub fn create_folder(base_path: &Path, foldername: &str) -> Result<PathBuf, KirokuError> {
     let safe_foldername = foldername.trim().replace(" ", "_");
@@ -90,11 +304,29 @@ pub fn delete_note(path: &Path) -> Result<(), KirokuError> {
     Ok(())
 }
 
-// rename note
-pub fn rename_note(old_path: &Path, new_filename: &str) -> Result<PathBuf, KirokuError> {
+// rename note, keeping its extension
+pub fn rename_note(
+    old_path: &Path,
+    new_filename: &str,
+    config: &Config,
+) -> Result<PathBuf, KirokuError> {
     let mut safe_filename = new_filename.trim().replace(" ", "_");
-    if !safe_filename.ends_with(".md") {
-        safe_filename.push_str(".md");
+    // a typed note extension changes the format, anything else keeps the old one
+    let has_extension = Path::new(&safe_filename)
+        .extension()
+        .and_then(|e| e.to_str())
+        .is_some_and(|ext| {
+            config
+                .note_extensions()
+                .iter()
+                .any(|e| e.eq_ignore_ascii_case(ext))
+        });
+    let extension = old_path
+        .extension()
+        .map(|e| format!(".{}", e.to_string_lossy()))
+        .unwrap_or_default();
+    if !has_extension && !safe_filename.ends_with(&extension) {
+        safe_filename.push_str(&extension);
     }
 
     let parent = old_path
@@ -183,3 +415,55 @@ pub fn run_git_sync(base_path: &Path) -> Result<String, KirokuError> {
         Ok("synced locally (no remote configured or no push needed)".to_string())
     }
 }
+
+// run git quietly, never prompting for credentials
+fn git_quiet(base_path: &Path, args: &[&str]) -> Result<std::process::Output, KirokuError> {
+    Ok(Command::new("git")
+        .args(args)
+        .current_dir(base_path)
+        .env("GIT_TERMINAL_PROMPT", "0")
+        .stdin(std::process::Stdio::null())
+        .output()?)
+}
+
+// background commit and push used by interval auto-sync
+pub fn run_auto_sync(base_path: &Path, commit: bool, push: bool) -> Result<String, KirokuError> {
+    if !base_path.join(".git").exists() {
+        return Err(KirokuError::Git(
+            "not a git repo (run 'git init' in folder)".to_string(),
+        ));
+    }
+
+    let mut done = Vec::new();
+
+    if commit {
+        let status = git_quiet(base_path, &["status", "--porcelain"])?;
+        if !status.stdout.is_empty() {
+            if !git_quiet(base_path, &["add", "."])?.status.success() {
+                return Err(KirokuError::Git("git add failed".to_string()));
+            }
+
+            let commit = git_quiet(base_path, &["commit", "-m", "auto-commit from kiroku"])?;
+            if !commit.status.success() {
+                return Err(KirokuError::Git("git commit failed".to_string()));
+            }
+            done.push("committed");
+        }
+    }
+
+    if push {
+        let ahead = git_quiet(base_path, &["rev-list", "@{u}..HEAD"])?;
+        if ahead.status.success() && !ahead.stdout.is_empty() {
+            if !git_quiet(base_path, &["push"])?.status.success() {
+                return Err(KirokuError::Git("push failed".to_string()));
+            }
+            done.push("pushed");
+        }
+    }
+
+    if done.is_empty() {
+        Ok("already up to date".to_string())
+    } else {
+        Ok(format!("auto-sync: {}", done.join(" and ")))
+    }
+}
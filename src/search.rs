@@ -0,0 +1,85 @@
+use crate::data::{self, Note};
+use fuzzy_matcher::FuzzyMatcher;
+use fuzzy_matcher::skim::SkimMatcherV2;
+use std::cmp::Reverse;
+
+// fuzzy match notes by title, best first
+pub fn by_title(notes: &[Note], query: &str) -> Vec<Note> {
+    let matcher = SkimMatcherV2::default();
+    let mut matches: Vec<(&Note, i64)> = notes
+        .iter()
+        .filter_map(|note| {
+            matcher
+                .fuzzy_match(&note.title, query)
+                .map(|score| (note, score))
+        })
+        .collect();
+
+    matches.sort_by_key(|m| Reverse(m.1));
+    matches.into_iter().map(|(n, _)| n.clone()).collect()
+}
+
+// fuzzy match notes by their best matching tag
+pub fn by_tag(notes: &[Note], query: &str) -> Vec<Note> {
+    let matcher = SkimMatcherV2::default();
+    let mut matches: Vec<(&Note, i64)> = notes
+        .iter()
+        .filter_map(|note| {
+            // Check if any tag matches
+            let best_score = note
+                .tags
+                .iter()
+                .filter_map(|tag| matcher.fuzzy_match(tag, query))
+                .max();
+
+            best_score.map(|score| (note, score))
+        })
+        .collect();
+
+    matches.sort_by_key(|m| Reverse(m.1));
+    matches.into_iter().map(|(n, _)| n.clone()).collect()
+}
+
+// notes whose body contains the query, ranked by title match
+pub fn by_content(notes: &[Note], query: &str) -> Vec<Note> {
+    let matcher = SkimMatcherV2::default();
+    let query = query.to_lowercase();
+
+    let mut matches: Vec<(&Note, i64)> = notes
+        .iter()
+        .filter_map(|note| {
+            let content = if let Some(ref c) = note.content {
+                Some(c.clone())
+            } else {
+                data::read_note_content(&note.path).ok()
+            };
+
+            if let Some(content) = content
+                && content.to_lowercase().contains(&query)
+            {
+                let title_score = matcher.fuzzy_match(&note.title, &query).unwrap_or(0);
+                return Some((note, 100 + title_score));
+            }
+            None
+        })
+        .collect();
+
+    matches.sort_by_key(|m| Reverse(m.1));
+    matches.into_iter().map(|(n, _)| n.clone()).collect()
+}
+
+// fuzzy rank arbitrary items by a text key, best first
+pub fn rank<'a, T>(items: &'a [T], query: &str, text: impl Fn(&T) -> String) -> Vec<&'a T> {
+    let matcher = SkimMatcherV2::default();
+    let mut matches: Vec<(&T, i64)> = items
+        .iter()
+        .filter_map(|item| {
+            matcher
+                .fuzzy_match(&text(item), query)
+                .map(|score| (item, score))
+        })
+        .collect();
+
+    matches.sort_by_key(|m| Reverse(m.1));
+    matches.into_iter().map(|(item, _)| item).collect()
+}
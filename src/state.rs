@@ -0,0 +1,70 @@
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+// ui state kiroku remembers between runs, kept out of config.toml
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+pub struct State {
+    pub theme: Option<String>,
+    pub last_vault: Option<String>,
+    #[serde(default)]
+    pub layout: LayoutState,
+    // keyed by vault path
+    #[serde(default)]
+    pub vaults: BTreeMap<String, VaultState>,
+}
+
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LayoutState {
+    // list pane share of the screen, in percent
+    pub list_width: Option<u16>,
+    pub preview: Option<bool>,
+    pub zen: Option<bool>,
+}
+
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+pub struct VaultState {
+    pub sort_mode: Option<String>,
+    // folder that was open, relative to the vault
+    pub folder: Option<String>,
+}
+
+impl State {
+    // read state, starting fresh if it is missing or unreadable
+    pub fn load(path: &Path) -> State {
+        let Ok(content) = fs::read_to_string(path) else {
+            return State::default();
+        };
+        toml::from_str(&content).unwrap_or_else(|e| {
+            log::warn!("Ignoring unreadable state file {:?}: {}", path, e);
+            State::default()
+        })
+    }
+
+    pub fn save(&self, path: &Path) -> Result<()> {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(path, toml::to_string(self)?)?;
+        Ok(())
+    }
+
+    pub fn vault(&self, vault_path: &Path) -> Option<&VaultState> {
+        self.vaults.get(&vault_path.to_string_lossy().to_string())
+    }
+
+    pub fn vault_mut(&mut self, vault_path: &Path) -> &mut VaultState {
+        self.vaults
+            .entry(vault_path.to_string_lossy().to_string())
+            .or_default()
+    }
+}
+
+// where the state file lives
+pub fn state_path() -> Result<PathBuf> {
+    let data_dir =
+        dirs::data_local_dir().ok_or_else(|| anyhow::anyhow!("could not find data directory"))?;
+    Ok(data_dir.join("kiroku").join("state.toml"))
+}
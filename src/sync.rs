@@ -0,0 +1,373 @@
+use crate::config::Config;
+use crate::errors::KirokuError;
+use crate::ops;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::{BTreeMap, BTreeSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::UNIX_EPOCH;
+use walkdir::WalkDir;
+
+// a way of syncing the vault somewhere else
+pub trait SyncBackend {
+    fn name(&self) -> &str;
+
+    // interactive sync, runs with the tui suspended
+    fn sync(&self, base_path: &Path) -> Result<String, KirokuError>;
+
+    // quiet sync, runs in the background while the tui is up
+    fn auto_sync(&self, base_path: &Path, commit: bool, push: bool) -> Result<String, KirokuError>;
+}
+
+// pick backend from config
+pub fn backend_from_config(config: &Config) -> Box<dyn SyncBackend + Send> {
+    match config.sync_backend.as_deref() {
+        Some("mirror") => Box::new(MirrorBackend {
+            target: config.mirror_path.as_ref().map(PathBuf::from),
+        }),
+        Some("git") | None => Box::new(GitBackend),
+        Some(other) => {
+            log::warn!("Unknown sync backend '{}', falling back to git", other);
+            Box::new(GitBackend)
+        }
+    }
+}
+
+// add, commit and push with git
+pub struct GitBackend;
+
+impl SyncBackend for GitBackend {
+    fn name(&self) -> &str {
+        "git"
+    }
+
+    fn sync(&self, base_path: &Path) -> Result<String, KirokuError> {
+        println!("Repository path: {:?}", base_path);
+        println!("(If prompted for password, input will be hidden)");
+        ops::run_git_sync(base_path)
+    }
+
+    fn auto_sync(&self, base_path: &Path, commit: bool, push: bool) -> Result<String, KirokuError> {
+        ops::run_auto_sync(base_path, commit, push)
+    }
+}
+
+// two-way mirror of the vault into another directory
+pub struct MirrorBackend {
+    pub target: Option<PathBuf>,
+}
+
+impl SyncBackend for MirrorBackend {
+    fn name(&self) -> &str {
+        "mirror"
+    }
+
+    fn sync(&self, base_path: &Path) -> Result<String, KirokuError> {
+        let target = self.target()?;
+        println!("Mirroring {:?} <-> {:?}", base_path, target);
+
+        let report = mirror_sync(base_path, target, &manifest_path(base_path, target)?)?;
+        for path in &report.conflicts {
+            println!("conflict: {}", path);
+        }
+        Ok(report.summary())
+    }
+
+    fn auto_sync(
+        &self,
+        base_path: &Path,
+        _commit: bool,
+        _push: bool,
+    ) -> Result<String, KirokuError> {
+        let target = self.target()?;
+        let report = mirror_sync(base_path, target, &manifest_path(base_path, target)?)?;
+        Ok(report.summary())
+    }
+}
+
+impl MirrorBackend {
+    fn target(&self) -> Result<&Path, KirokuError> {
+        self.target
+            .as_deref()
+            .ok_or_else(|| KirokuError::Mirror("mirror_path is not set in config".to_string()))
+    }
+}
+
+// outcome of a mirror run
+#[derive(Debug, Default)]
+pub struct MirrorReport {
+    pub pushed: usize,
+    pub pulled: usize,
+    pub deleted: usize,
+    pub conflicts: Vec<String>,
+}
+
+impl MirrorReport {
+    pub fn summary(&self) -> String {
+        if self.pushed + self.pulled + self.deleted == 0 && self.conflicts.is_empty() {
+            return "already up to date".to_string();
+        }
+
+        let mut msg = format!(
+            "mirrored: {} out, {} in, {} deleted",
+            self.pushed, self.pulled, self.deleted
+        );
+        if !self.conflicts.is_empty() {
+            msg.push_str(&format!(
+                ", {} conflict(s) left untouched",
+                self.conflicts.len()
+            ));
+        }
+        msg
+    }
+}
+
+// state of a file on both sides after the last sync
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+struct SyncedFile {
+    hash: String,
+    local_mtime: i64,
+    remote_mtime: i64,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct Manifest {
+    #[serde(default)]
+    files: BTreeMap<String, SyncedFile>,
+}
+
+// where the manifest for a vault/target pair lives
+fn manifest_path(base_path: &Path, target: &Path) -> Result<PathBuf, KirokuError> {
+    let data_dir = dirs::data_local_dir()
+        .ok_or_else(|| KirokuError::Env("could not find data directory".to_string()))?;
+
+    let key = Sha256::digest(format!("{}\0{}", base_path.display(), target.display()));
+    let key = format!("{:x}", key);
+    Ok(data_dir
+        .join("kiroku")
+        .join("mirror")
+        .join(format!("{}.toml", &key[..16])))
+}
+
+fn mtime_millis(path: &Path) -> Option<i64> {
+    let modified = fs::metadata(path).ok()?.modified().ok()?;
+    Some(modified.duration_since(UNIX_EPOCH).ok()?.as_millis() as i64)
+}
+
+fn hash_file(path: &Path) -> Result<String, KirokuError> {
+    Ok(format!("{:x}", Sha256::digest(fs::read(path)?)))
+}
+
+// relative paths of all synced files, skipping hidden entries
+fn list_files(root: &Path) -> BTreeSet<String> {
+    let mut files = BTreeSet::new();
+    if !root.is_dir() {
+        return files;
+    }
+
+    for entry in WalkDir::new(root)
+        .into_iter()
+        .filter_entry(|e| {
+            e.depth() == 0
+                || !e
+                    .file_name()
+                    .to_str()
+                    .map(|s| s.starts_with('.'))
+                    .unwrap_or(false)
+        })
+        .filter_map(|e| e.ok())
+    {
+        if entry.file_type().is_file()
+            && let Ok(rel) = entry.path().strip_prefix(root)
+        {
+            files.insert(rel.to_string_lossy().replace('\\', "/"));
+        }
+    }
+    files
+}
+
+// hash of a side, or none when the file is unchanged since the last sync
+fn changed_hash(
+    path: &Path,
+    mtime: i64,
+    base: Option<&SyncedFile>,
+    base_mtime: impl Fn(&SyncedFile) -> i64,
+) -> Result<Option<String>, KirokuError> {
+    if let Some(b) = base
+        && base_mtime(b) == mtime
+    {
+        return Ok(None);
+    }
+    let hash = hash_file(path)?;
+    if base.map(|b| b.hash == hash).unwrap_or(false) {
+        return Ok(None);
+    }
+    Ok(Some(hash))
+}
+
+fn copy_file(from: &Path, to: &Path) -> Result<(), KirokuError> {
+    if let Some(parent) = to.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    fs::copy(from, to)?;
+    Ok(())
+}
+
+// sync two directories, detecting conflicts by mtime and content hash
+pub fn mirror_sync(
+    local_root: &Path,
+    remote_root: &Path,
+    manifest_file: &Path,
+) -> Result<MirrorReport, KirokuError> {
+    let mut manifest: Manifest = match fs::read_to_string(manifest_file) {
+        Ok(content) => toml::from_str(&content).unwrap_or_else(|e| {
+            log::warn!("Ignoring unreadable mirror manifest: {}", e);
+            Manifest::default()
+        }),
+        Err(_) => Manifest::default(),
+    };
+
+    // a mirror that went missing after a sync is most likely an unmounted
+    // drive; syncing against it would delete every note here
+    if !remote_root.is_dir() {
+        if !manifest.files.is_empty() {
+            return Err(KirokuError::Mirror(format!(
+                "{:?} is missing, is it mounted?",
+                remote_root
+            )));
+        }
+        fs::create_dir_all(remote_root)
+            .map_err(|e| KirokuError::Mirror(format!("cannot create {:?}: {}", remote_root, e)))?;
+    }
+
+    let local_files = list_files(local_root);
+    let remote_files = list_files(remote_root);
+    if !manifest.files.is_empty() && remote_files.is_empty() {
+        return Err(KirokuError::Mirror(format!(
+            "{:?} is empty, is it mounted?",
+            remote_root
+        )));
+    }
+    // same for the notes here; a replaced or unmounted notes folder would
+    // otherwise wipe the mirror
+    if !manifest.files.is_empty() && local_files.is_empty() {
+        return Err(KirokuError::Mirror(format!(
+            "{:?} is empty, is it mounted?",
+            local_root
+        )));
+    }
+    let all: BTreeSet<&String> = local_files
+        .iter()
+        .chain(remote_files.iter())
+        .chain(manifest.files.keys())
+        .collect();
+    let all: Vec<String> = all.into_iter().cloned().collect();
+
+    let mut report = MirrorReport::default();
+
+    for rel in all {
+        let local = local_root.join(&rel);
+        let remote = remote_root.join(&rel);
+        let base = manifest.files.get(&rel).cloned();
+
+        let local_mtime = if local_files.contains(&rel) {
+            mtime_millis(&local)
+        } else {
+            None
+        };
+        let remote_mtime = if remote_files.contains(&rel) {
+            mtime_millis(&remote)
+        } else {
+            None
+        };
+
+        match (local_mtime, remote_mtime) {
+            (None, None) => {
+                manifest.files.remove(&rel);
+            }
+            (Some(lm), None) => {
+                let local_changed =
+                    changed_hash(&local, lm, base.as_ref(), |b| b.local_mtime)?.is_some();
+                if base.is_some() && !local_changed {
+                    // deleted on the remote side
+                    fs::remove_file(&local)?;
+                    manifest.files.remove(&rel);
+                    report.deleted += 1;
+                } else {
+                    // new locally, or edited here while deleted there
+                    copy_file(&local, &remote)?;
+                    record(&mut manifest, &rel, &local, &remote)?;
+                    report.pushed += 1;
+                }
+            }
+            (None, Some(rm)) => {
+                let remote_changed =
+                    changed_hash(&remote, rm, base.as_ref(), |b| b.remote_mtime)?.is_some();
+                if base.is_some() && !remote_changed {
+                    // deleted locally
+                    fs::remove_file(&remote)?;
+                    manifest.files.remove(&rel);
+                    report.deleted += 1;
+                } else {
+                    copy_file(&remote, &local)?;
+                    record(&mut manifest, &rel, &local, &remote)?;
+                    report.pulled += 1;
+                }
+            }
+            (Some(lm), Some(rm)) => {
+                let local_change = changed_hash(&local, lm, base.as_ref(), |b| b.local_mtime)?;
+                let remote_change = changed_hash(&remote, rm, base.as_ref(), |b| b.remote_mtime)?;
+
+                match (local_change, remote_change) {
+                    (None, None) => {}
+                    (Some(_), None) => {
+                        copy_file(&local, &remote)?;
+                        record(&mut manifest, &rel, &local, &remote)?;
+                        report.pushed += 1;
+                    }
+                    (None, Some(_)) => {
+                        copy_file(&remote, &local)?;
+                        record(&mut manifest, &rel, &local, &remote)?;
+                        report.pulled += 1;
+                    }
+                    (Some(lh), Some(rh)) => {
+                        if lh == rh {
+                            // same edit on both sides
+                            record(&mut manifest, &rel, &local, &remote)?;
+                        } else {
+                            log::warn!("Mirror conflict: {}", rel);
+                            report.conflicts.push(rel.clone());
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    if let Some(parent) = manifest_file.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    let content = toml::to_string(&manifest)
+        .map_err(|e| KirokuError::Mirror(format!("cannot write manifest: {}", e)))?;
+    fs::write(manifest_file, content)?;
+
+    Ok(report)
+}
+
+fn record(
+    manifest: &mut Manifest,
+    rel: &str,
+    local: &Path,
+    remote: &Path,
+) -> Result<(), KirokuError> {
+    manifest.files.insert(
+        rel.to_string(),
+        SyncedFile {
+            hash: hash_file(local)?,
+            local_mtime: mtime_millis(local).unwrap_or_default(),
+            remote_mtime: mtime_millis(remote).unwrap_or_default(),
+        },
+    );
+    Ok(())
+}
@@ -0,0 +1,201 @@
+use crate::data::{Note, NoteFormat};
+use anyhow::{Result, bail};
+use chrono::NaiveDate;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+// a `- [ ]` checkbox in a note
+#[derive(Debug, Clone, PartialEq)]
+pub struct Task {
+    pub path: PathBuf,
+    // 1-based line of the checkbox
+    pub line: usize,
+    // what follows the checkbox, without the due date
+    pub text: String,
+    pub done: bool,
+    // nearest heading above the task
+    pub heading: Option<String>,
+    pub due: Option<NaiveDate>,
+    // the whole line as it was read, to find it again before writing
+    pub source: String,
+}
+
+impl Task {
+    pub fn is_overdue(&self, today: NaiveDate) -> bool {
+        !self.done && self.due.is_some_and(|due| due < today)
+    }
+}
+
+// which tasks the view and `kiroku tasks` show
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum TaskFilter {
+    Open,
+    Overdue,
+    Done,
+    All,
+}
+
+impl TaskFilter {
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "open" => Some(TaskFilter::Open),
+            "overdue" => Some(TaskFilter::Overdue),
+            "done" => Some(TaskFilter::Done),
+            "all" => Some(TaskFilter::All),
+            _ => None,
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            TaskFilter::Open => "Open",
+            TaskFilter::Overdue => "Overdue",
+            TaskFilter::Done => "Done",
+            TaskFilter::All => "All",
+        }
+    }
+
+    pub fn next(self) -> Self {
+        match self {
+            TaskFilter::Open => TaskFilter::Overdue,
+            TaskFilter::Overdue => TaskFilter::Done,
+            TaskFilter::Done => TaskFilter::All,
+            TaskFilter::All => TaskFilter::Open,
+        }
+    }
+
+    pub fn matches(self, task: &Task, today: NaiveDate) -> bool {
+        match self {
+            TaskFilter::Open => !task.done,
+            TaskFilter::Overdue => task.is_overdue(today),
+            TaskFilter::Done => task.done,
+            TaskFilter::All => true,
+        }
+    }
+}
+
+// every task in the notes, by path and line
+pub fn collect(notes: &[Note]) -> Vec<Task> {
+    let mut tasks = Vec::new();
+    for note in notes {
+        if let Ok(content) = fs::read_to_string(&note.path) {
+            tasks.extend(parse_tasks(&note.path, &content));
+        }
+    }
+    tasks.sort_by(|a, b| a.path.cmp(&b.path).then(a.line.cmp(&b.line)));
+    tasks
+}
+
+// tasks in one note, skipping code blocks
+pub fn parse_tasks(path: &Path, content: &str) -> Vec<Task> {
+    let format = NoteFormat::from_path(path);
+    let mut tasks = Vec::new();
+    let mut heading = None;
+    let mut in_code_block = false;
+
+    for (i, line) in content.lines().enumerate() {
+        if format.is_fence(line) {
+            in_code_block = !in_code_block;
+            continue;
+        }
+        if in_code_block {
+            continue;
+        }
+        if let Some(level) = format.heading_level(line) {
+            heading = Some(line[level..].trim().to_string());
+            continue;
+        }
+        let Some((done, rest)) = checkbox(line) else {
+            continue;
+        };
+
+        let (text, due) = split_due(rest);
+        tasks.push(Task {
+            path: path.to_path_buf(),
+            line: i + 1,
+            text,
+            done,
+            heading: heading.clone(),
+            due,
+            source: line.to_string(),
+        });
+    }
+    tasks
+}
+
+// tick or untick a task in its file, following it if lines moved above it;
+// fails if the task itself changed or can't be told apart from another
+pub fn toggle(task: &mut Task) -> Result<bool> {
+    let content = fs::read_to_string(&task.path)?;
+    let mut lines: Vec<&str> = content.split('\n').collect();
+    let same = |line: &str| line.trim_end_matches('\r') == task.source;
+    let index = if lines.get(task.line - 1).is_some_and(|l| same(l)) {
+        task.line - 1
+    } else {
+        let mut found = lines.iter().enumerate().filter(|(_, l)| same(l));
+        match (found.next(), found.next()) {
+            (Some((i, _)), None) => i,
+            _ => bail!("{} changed, reload the tasks", task.path.display()),
+        }
+    };
+    let line = lines[index];
+    let Some((done, _)) = checkbox(line) else {
+        bail!("{} changed, reload the tasks", task.path.display());
+    };
+
+    // the box is the first [ ] on the line
+    let start = line.find('[').unwrap_or(0);
+    let mark = if done { " " } else { "x" };
+    let toggled = format!("{}[{}]{}", &line[..start], mark, &line[start + 3..]);
+    lines[index] = &toggled;
+    fs::write(&task.path, lines.join("\n"))?;
+
+    task.line = index + 1;
+    task.done = !done;
+    task.source = toggled.trim_end_matches('\r').to_string();
+    Ok(task.done)
+}
+
+// `- [ ] text` with -, * or + bullets and any indent
+fn checkbox(line: &str) -> Option<(bool, &str)> {
+    let rest = line.trim_start();
+    let rest = rest
+        .strip_prefix("- ")
+        .or_else(|| rest.strip_prefix("* "))
+        .or_else(|| rest.strip_prefix("+ "))?
+        .trim_start();
+    let done = match rest.get(..3)? {
+        "[ ]" => false,
+        "[x]" | "[X]" => true,
+        _ => return None,
+    };
+    Some((done, rest[3..].trim()))
+}
+
+// pull `@due(2026-10-20)` or `📅 2026-10-20` out of a task
+fn split_due(text: &str) -> (String, Option<NaiveDate>) {
+    if let Some(start) = text.find("@due(")
+        && let Some(len) = text[start..].find(')')
+    {
+        let date = &text[start + 5..start + len];
+        if let Ok(due) = NaiveDate::parse_from_str(date.trim(), "%Y-%m-%d") {
+            let rest = format!("{}{}", &text[..start], &text[start + len + 1..]);
+            return (tidy(&rest), Some(due));
+        }
+    }
+    if let Some(start) = text.find('📅') {
+        let after = text[start + '📅'.len_utf8()..].trim_start();
+        if let Some(date) = after.get(..10)
+            && let Ok(due) = NaiveDate::parse_from_str(date, "%Y-%m-%d")
+        {
+            let rest = format!("{}{}", &text[..start], &after[10..]);
+            return (tidy(&rest), Some(due));
+        }
+    }
+    (text.to_string(), None)
+}
+
+// collapse the gap a removed due date leaves
+fn tidy(text: &str) -> String {
+    text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
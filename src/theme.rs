@@ -0,0 +1,388 @@
+use crate::config::{self, Theme};
+use ratatui::style::{Color, Modifier, Style};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
+// every color the ui draws with
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ThemeColors {
+    pub accent: Color,
+    pub selection: Color,
+    pub selection_fg: Color,
+    pub header: Color,
+    pub dim: Color,
+    pub bold: Color,
+    pub border: Color,
+    pub folder: Color,
+    pub quote: Color,
+    pub code: Color,
+    pub link: Color,
+    pub tag: Color,
+    pub status: Color,
+    pub status_bg: Color,
+    // no colors at all, modifiers only
+    pub monochrome: bool,
+}
+
+// built-in themes, in cycling order
+pub const BUILTIN_THEMES: [&str; 3] = ["default", "gruvbox", "tokyo-night"];
+
+impl Default for ThemeColors {
+    // Catppuccin Mocha-ish
+    fn default() -> Self {
+        let accent = Color::Rgb(137, 220, 235);
+        Self {
+            accent,
+            selection: Color::Rgb(187, 154, 247),
+            selection_fg: Color::Rgb(17, 17, 27),
+            header: Color::Rgb(137, 180, 250),
+            dim: Color::Rgb(108, 112, 134),
+            bold: Color::Rgb(243, 139, 168),
+            border: accent,
+            folder: Color::Rgb(249, 226, 175),
+            quote: Color::Rgb(166, 227, 161),
+            code: Color::Rgb(250, 179, 135),
+            link: Color::Rgb(116, 199, 236),
+            tag: Color::Rgb(203, 166, 247),
+            status: accent,
+            status_bg: Color::Reset,
+            monochrome: false,
+        }
+    }
+}
+
+impl ThemeColors {
+    pub fn builtin(name: &str) -> Option<ThemeColors> {
+        match name {
+            "default" => Some(ThemeColors::default()),
+            "gruvbox" => {
+                let accent = Color::Rgb(250, 189, 47); // Yellow
+                Some(ThemeColors {
+                    accent,
+                    selection: Color::Rgb(215, 153, 33), // Dark Yellow
+                    selection_fg: Color::Rgb(40, 40, 40),
+                    header: Color::Rgb(251, 73, 52), // Red
+                    dim: Color::Rgb(168, 153, 132),  // Gray
+                    bold: Color::Rgb(254, 128, 25),  // Orange
+                    border: accent,
+                    folder: Color::Rgb(131, 165, 152), // Blue
+                    quote: Color::Rgb(184, 187, 38),   // Green
+                    code: Color::Rgb(142, 192, 124),   // Aqua
+                    link: Color::Rgb(131, 165, 152),
+                    tag: Color::Rgb(211, 134, 155), // Purple
+                    status: accent,
+                    status_bg: Color::Reset,
+                    monochrome: false,
+                })
+            }
+            "tokyo-night" => {
+                let accent = Color::Rgb(122, 162, 247); // Blue
+                Some(ThemeColors {
+                    accent,
+                    selection: Color::Rgb(187, 154, 247), // Purple
+                    selection_fg: Color::Rgb(26, 27, 38),
+                    header: Color::Rgb(125, 207, 255), // Cyan
+                    dim: Color::Rgb(86, 95, 137),      // Dark Blue/Gray
+                    bold: Color::Rgb(247, 118, 142),   // Red/Pink
+                    border: accent,
+                    folder: Color::Rgb(224, 175, 104), // Yellow
+                    quote: Color::Rgb(158, 206, 106),  // Green
+                    code: Color::Rgb(255, 158, 100),   // Orange
+                    link: Color::Rgb(42, 195, 222),
+                    tag: Color::Rgb(187, 154, 247),
+                    status: accent,
+                    status_bg: Color::Reset,
+                    monochrome: false,
+                })
+            }
+            _ => None,
+        }
+    }
+
+    // a theme table from config or a theme file, on top of its base
+    pub fn from_config(theme: &Theme) -> ThemeColors {
+        let base = theme.base.as_deref().unwrap_or("default");
+        let mut colors = ThemeColors::builtin(base).unwrap_or_else(|| {
+            log::warn!("Unknown base theme '{}', using default", base);
+            ThemeColors::default()
+        });
+        colors.apply(theme);
+        colors
+    }
+
+    // override the slots a theme table sets
+    pub fn apply(&mut self, theme: &Theme) {
+        let set = |slot: &mut Color, value: &Option<String>| {
+            if let Some(color) = value.as_deref().and_then(parse_color) {
+                *slot = color;
+            }
+        };
+
+        set(&mut self.accent, &theme.accent);
+        // borders and the status bar follow the accent unless set
+        if theme.border.is_none() {
+            set(&mut self.border, &theme.accent);
+        }
+        if theme.status.is_none() {
+            set(&mut self.status, &theme.accent);
+        }
+
+        set(&mut self.selection, &theme.selection);
+        set(&mut self.selection_fg, &theme.selection_fg);
+        set(&mut self.header, &theme.header);
+        set(&mut self.dim, &theme.dim);
+        set(&mut self.bold, &theme.bold);
+        set(&mut self.border, &theme.border);
+        set(&mut self.folder, &theme.folder);
+        set(&mut self.quote, &theme.quote);
+        set(&mut self.code, &theme.code);
+        set(&mut self.link, &theme.link);
+        set(&mut self.tag, &theme.tag);
+        set(&mut self.status, &theme.status);
+        set(&mut self.status_bg, &theme.status_bg);
+    }
+
+    // map every slot to what the terminal can show
+    pub fn for_mode(mut self, mode: ColorMode) -> ThemeColors {
+        for slot in [
+            &mut self.accent,
+            &mut self.selection,
+            &mut self.selection_fg,
+            &mut self.header,
+            &mut self.dim,
+            &mut self.bold,
+            &mut self.border,
+            &mut self.folder,
+            &mut self.quote,
+            &mut self.code,
+            &mut self.link,
+            &mut self.tag,
+            &mut self.status,
+            &mut self.status_bg,
+        ] {
+            *slot = mode.adapt(*slot);
+        }
+        self.monochrome = mode == ColorMode::Mono;
+        self
+    }
+
+    // a slot's color, or the modifier that stands in for it without colors
+    pub fn style(&self, color: Color, fallback: Modifier) -> Style {
+        if self.monochrome {
+            Style::default().add_modifier(fallback)
+        } else {
+            Style::default().fg(color)
+        }
+    }
+
+    // the highlighted row of a list
+    pub fn selected(&self) -> Style {
+        if self.monochrome {
+            Style::default().add_modifier(Modifier::REVERSED | Modifier::BOLD)
+        } else {
+            Style::default()
+                .bg(self.selection)
+                .fg(self.selection_fg)
+                .add_modifier(Modifier::BOLD)
+        }
+    }
+}
+
+// how many colors the terminal can show
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ColorMode {
+    TrueColor,
+    Ansi256,
+    Ansi16,
+    Mono,
+}
+
+// names accepted by the color_mode option
+pub const COLOR_MODES: [&str; 5] = ["auto", "truecolor", "256", "16", "none"];
+
+impl ColorMode {
+    // None for "auto" and unknown names
+    pub fn from_name(name: &str) -> Option<ColorMode> {
+        match name.to_lowercase().as_str() {
+            "truecolor" | "24bit" => Some(ColorMode::TrueColor),
+            "256" => Some(ColorMode::Ansi256),
+            "16" => Some(ColorMode::Ansi16),
+            "none" | "mono" => Some(ColorMode::Mono),
+            _ => None,
+        }
+    }
+
+    // forced by config, otherwise guessed from the environment
+    pub fn from_config(config: &config::Config) -> ColorMode {
+        config
+            .color_mode
+            .as_deref()
+            .and_then(ColorMode::from_name)
+            .unwrap_or_else(|| {
+                let var = |name| std::env::var(name).ok();
+                ColorMode::detect(var("NO_COLOR"), var("COLORTERM"), var("TERM"))
+            })
+    }
+
+    // NO_COLOR, COLORTERM and TERM
+    pub fn detect(
+        no_color: Option<String>,
+        colorterm: Option<String>,
+        term: Option<String>,
+    ) -> ColorMode {
+        if no_color.is_some_and(|v| !v.is_empty()) {
+            return ColorMode::Mono;
+        }
+        let colorterm = colorterm.unwrap_or_default().to_lowercase();
+        if colorterm == "truecolor" || colorterm == "24bit" {
+            return ColorMode::TrueColor;
+        }
+        let term = term.unwrap_or_default().to_lowercase();
+        if term == "dumb" {
+            ColorMode::Mono
+        } else if term.contains("truecolor") || term.contains("direct") {
+            ColorMode::TrueColor
+        } else if term.contains("256") {
+            ColorMode::Ansi256
+        } else {
+            ColorMode::Ansi16
+        }
+    }
+
+    // a color the terminal can show
+    pub fn adapt(self, color: Color) -> Color {
+        match (self, color) {
+            (_, Color::Reset) | (ColorMode::TrueColor, _) => color,
+            (ColorMode::Mono, _) => Color::Reset,
+            (ColorMode::Ansi256, Color::Rgb(r, g, b)) => Color::Indexed(nearest_256(r, g, b)),
+            (ColorMode::Ansi256, _) => color,
+            (ColorMode::Ansi16, Color::Rgb(r, g, b)) => nearest_16(r, g, b),
+            (ColorMode::Ansi16, Color::Indexed(i)) if i >= 16 => {
+                let (r, g, b) = indexed_rgb(i);
+                nearest_16(r, g, b)
+            }
+            (ColorMode::Ansi16, Color::Indexed(i)) => ANSI_16[i as usize].0,
+            (ColorMode::Ansi16, _) => color,
+        }
+    }
+}
+
+// the 16 ansi colors with their usual xterm values
+const ANSI_16: [(Color, (u8, u8, u8)); 16] = [
+    (Color::Black, (0, 0, 0)),
+    (Color::Red, (205, 0, 0)),
+    (Color::Green, (0, 205, 0)),
+    (Color::Yellow, (205, 205, 0)),
+    (Color::Blue, (0, 0, 238)),
+    (Color::Magenta, (205, 0, 205)),
+    (Color::Cyan, (0, 205, 205)),
+    (Color::Gray, (229, 229, 229)),
+    (Color::DarkGray, (127, 127, 127)),
+    (Color::LightRed, (255, 0, 0)),
+    (Color::LightGreen, (0, 255, 0)),
+    (Color::LightYellow, (255, 255, 0)),
+    (Color::LightBlue, (92, 92, 255)),
+    (Color::LightMagenta, (255, 0, 255)),
+    (Color::LightCyan, (0, 255, 255)),
+    (Color::White, (255, 255, 255)),
+];
+
+// steps of the 6x6x6 color cube
+const CUBE: [u8; 6] = [0, 95, 135, 175, 215, 255];
+
+fn distance(a: (u8, u8, u8), b: (u8, u8, u8)) -> u32 {
+    let d = |x: u8, y: u8| (x as i32 - y as i32).pow(2) as u32;
+    d(a.0, b.0) + d(a.1, b.1) + d(a.2, b.2)
+}
+
+fn indexed_rgb(i: u8) -> (u8, u8, u8) {
+    match i {
+        0..=15 => ANSI_16[i as usize].1,
+        16..=231 => {
+            let i = i - 16;
+            (
+                CUBE[(i / 36) as usize],
+                CUBE[(i / 6 % 6) as usize],
+                CUBE[(i % 6) as usize],
+            )
+        }
+        _ => {
+            let level = 8 + (i - 232) * 10;
+            (level, level, level)
+        }
+    }
+}
+
+// closest of the cube colors and the gray ramp
+fn nearest_256(r: u8, g: u8, b: u8) -> u8 {
+    (16..=255)
+        .min_by_key(|&i| distance((r, g, b), indexed_rgb(i)))
+        .unwrap_or(16)
+}
+
+fn nearest_16(r: u8, g: u8, b: u8) -> Color {
+    ANSI_16
+        .iter()
+        .min_by_key(|(_, rgb)| distance((r, g, b), *rgb))
+        .map(|(color, _)| *color)
+        .unwrap_or(Color::Reset)
+}
+
+// "#rrggbb", a color name like "yellow", or a 256-color index
+pub fn parse_color(value: &str) -> Option<Color> {
+    let value = value.trim();
+    if let Some(hex) = value.strip_prefix('#') {
+        if hex.len() != 6 || !hex.is_ascii() {
+            return None;
+        }
+        let channel = |i: usize| u8::from_str_radix(&hex[i..i + 2], 16).ok();
+        return Some(Color::Rgb(channel(0)?, channel(2)?, channel(4)?));
+    }
+    Color::from_str(value).ok()
+}
+
+// where theme files live
+pub fn themes_dir() -> anyhow::Result<PathBuf> {
+    Ok(config::config_dir()?.join("themes"))
+}
+
+// named themes from *.toml files, sorted by name
+pub fn load_theme_files(dir: &Path) -> Vec<(String, ThemeColors)> {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return Vec::new();
+    };
+
+    let mut themes = Vec::new();
+    for path in entries.filter_map(|e| e.ok()).map(|e| e.path()) {
+        if path.extension().and_then(|e| e.to_str()) != Some("toml") {
+            continue;
+        }
+        let Some(name) = path.file_stem().map(|s| s.to_string_lossy().to_string()) else {
+            continue;
+        };
+
+        let theme: Theme = match fs::read_to_string(&path)
+            .map_err(anyhow::Error::from)
+            .and_then(|content| Ok(toml::from_str(&content)?))
+        {
+            Ok(theme) => theme,
+            Err(e) => {
+                log::warn!("Skipping theme {:?}: {}", path, e);
+                continue;
+            }
+        };
+        for (slot, value) in theme.slots() {
+            if let Some(value) = value
+                && parse_color(value).is_none()
+            {
+                log::warn!("Theme '{}': {} '{}' is not a color", name, slot, value);
+            }
+        }
+
+        themes.push((name, ThemeColors::from_config(&theme)));
+    }
+
+    themes.sort_by(|a, b| a.0.cmp(&b.0));
+    themes
+}
@@ -1,5 +1,9 @@
-use crate::app::{App, InputMode};
-use chrono::{DateTime, Local};
+use crate::app::{App, InputMode, STACK_BELOW_COLUMNS};
+use crate::calendar;
+use crate::config::Severity;
+use crate::keys::KeyAction;
+use crate::theme::ThemeColors;
+use chrono::{DateTime, Datelike, Local};
 use ratatui::{
     Frame,
     layout::{Constraint, Direction, Layout, Rect},
@@ -7,32 +11,35 @@ use ratatui::{
     text::{Line, Span},
     widgets::{Block, BorderType, Borders, Clear, List, ListItem, Paragraph, Wrap},
 };
+use std::path::{Path, PathBuf};
 use tui_logger::TuiLoggerWidget;
+use unicode_width::UnicodeWidthChar;
 
 // render tui interface
 pub fn ui(f: &mut Frame, app: &mut App) {
-    let constraints = if app.show_logs {
-        vec![
-            Constraint::Percentage(70),
-            Constraint::Percentage(30),
-            Constraint::Length(3),
-        ]
+    let show_logs = app.show_logs && !app.zen;
+    // zen mode keeps the status bar only while it is prompting
+    let show_status = !app.zen || app.input_mode != InputMode::Normal;
+
+    let mut constraints = if show_logs {
+        vec![Constraint::Percentage(70), Constraint::Percentage(30)]
     } else {
-        vec![Constraint::Fill(1), Constraint::Length(3)]
+        vec![Constraint::Fill(1)]
     };
+    if show_status {
+        constraints.push(Constraint::Length(3));
+    }
 
     let chunks = Layout::default()
         .direction(Direction::Vertical)
         .constraints(constraints)
         .split(f.area());
 
-    let main_area = chunks[0];
-    let status_area = if app.show_logs { chunks[2] } else { chunks[1] };
-
-    let main_chunks = Layout::default()
-        .direction(Direction::Horizontal)
-        .constraints([Constraint::Percentage(30), Constraint::Percentage(70)])
-        .split(main_area);
+    let log_area = show_logs.then(|| chunks[1]);
+    let status_area = show_status.then(|| chunks[chunks.len() - 1]);
+    let (list_area, preview_area) = pane_areas(app, chunks[0]);
+    app.list_area = list_area;
+    app.preview_area = preview_area;
 
     let items: Vec<ListItem> = if !app.search_query.is_empty() {
         // show filtered notes
@@ -60,7 +67,7 @@ pub fn ui(f: &mut Frame, app: &mut App) {
                     let (folder, name) = note.title.split_at(idx + 1);
                     spans.push(Span::styled(
                         format!(" {}", folder),
-                        Style::default().fg(app.theme.dim),
+                        app.theme.style(app.theme.dim, Modifier::DIM),
                     ));
                     spans.push(Span::raw(name));
                 } else {
@@ -70,7 +77,7 @@ pub fn ui(f: &mut Frame, app: &mut App) {
                 if !tags_display.is_empty() {
                     spans.push(Span::styled(
                         tags_display,
-                        Style::default().fg(app.theme.dim),
+                        app.theme.style(app.theme.tag, Modifier::ITALIC),
                     ));
                 }
 
@@ -78,17 +85,34 @@ pub fn ui(f: &mut Frame, app: &mut App) {
             })
             .collect()
     } else {
+        // attachments no note links to are dimmed
+        let attachment_paths: Vec<PathBuf> = app
+            .fs_items
+            .iter()
+            .filter_map(|item| match item {
+                crate::data::FileSystemItem::Attachment(p) => Some(p.clone()),
+                _ => None,
+            })
+            .collect();
+        let orphaned: Vec<PathBuf> = attachment_paths
+            .into_iter()
+            .filter(|p| app.linked_from(p).is_empty())
+            .collect();
+
         // show file system items
         app.fs_items
             .iter()
             .map(|item| match item {
                 crate::data::FileSystemItem::Folder(path) => {
-                    let name = path.file_name().unwrap().to_string_lossy();
+                    let name = path
+                        .file_name()
+                        .unwrap_or(path.as_os_str())
+                        .to_string_lossy();
                     ListItem::new(Line::from(vec![
                         Span::styled(
                             "> ",
                             Style::default()
-                                .fg(Color::Yellow)
+                                .fg(app.theme.folder)
                                 .add_modifier(Modifier::BOLD),
                         ),
                         Span::styled(
@@ -98,14 +122,36 @@ pub fn ui(f: &mut Frame, app: &mut App) {
                     ]))
                 }
                 crate::data::FileSystemItem::Note(note) => {
-                    let name = note.path.file_name().unwrap().to_string_lossy();
-                    let name = name.strip_suffix(".md").unwrap_or(&name);
+                    let name = note
+                        .path
+                        .file_stem()
+                        .unwrap_or(note.path.as_os_str())
+                        .to_string_lossy();
 
                     ListItem::new(Line::from(vec![
                         Span::styled("  ", Style::default().fg(app.theme.accent)),
                         Span::raw(name.to_string()),
                     ]))
                 }
+                crate::data::FileSystemItem::Attachment(path) => {
+                    let name = path
+                        .file_name()
+                        .unwrap_or(path.as_os_str())
+                        .to_string_lossy();
+                    let kind = crate::data::AttachmentKind::from_path(path);
+                    let name_style = if orphaned.contains(path) {
+                        app.theme.style(app.theme.dim, Modifier::DIM)
+                    } else {
+                        Style::default()
+                    };
+                    ListItem::new(Line::from(vec![
+                        Span::styled(
+                            format!("{} ", kind.icon()),
+                            Style::default().fg(app.theme.tag),
+                        ),
+                        Span::styled(name.to_string(), name_style),
+                    ]))
+                }
             })
             .collect()
     };
@@ -118,7 +164,10 @@ pub fn ui(f: &mut Frame, app: &mut App) {
         } else {
             app.current_dir.to_string_lossy().to_string()
         };
-        format!(" {} ", path_str)
+        match &app.vault_name {
+            Some(vault) => format!(" [{}] {} ", vault, path_str),
+            None => format!(" {} ", path_str),
+        }
     };
 
     let list = List::new(items)
@@ -128,17 +177,14 @@ pub fn ui(f: &mut Frame, app: &mut App) {
                 .title_style(Style::default().add_modifier(Modifier::BOLD))
                 .borders(Borders::ALL)
                 .border_type(BorderType::Rounded)
-                .border_style(Style::default().fg(app.theme.accent)),
-        )
-        .highlight_style(
-            Style::default()
-                .bg(app.theme.selection)
-                .fg(Color::Black)
-                .add_modifier(Modifier::BOLD),
+                .border_style(Style::default().fg(app.theme.border)),
         )
+        .highlight_style(app.theme.selected())
         .highlight_symbol(" ");
 
-    f.render_stateful_widget(list, main_chunks[0], &mut app.list_state);
+    if let Some(area) = list_area {
+        f.render_stateful_widget(list, area, &mut app.list_state);
+    }
 
     // keep content string alive
     // let mut content_string = String::new();
@@ -155,7 +201,7 @@ pub fn ui(f: &mut Frame, app: &mut App) {
         } else if i < app.fs_items.len() {
             match &app.fs_items[i] {
                 crate::data::FileSystemItem::Note(n) => Some(n.clone()),
-                crate::data::FileSystemItem::Folder(_) => None,
+                _ => None,
             }
         } else {
             None
@@ -168,47 +214,44 @@ pub fn ui(f: &mut Frame, app: &mut App) {
                 .unwrap_or_else(|| "Loading...".to_string());
             let content = content_string.as_str();
 
+            let format = crate::data::NoteFormat::from_path(&note.path);
+            let mut in_code_block = false;
             let lines: Vec<Line> = content
                 .lines()
                 .map(|line| {
-                    if line.starts_with("# ") {
+                    if format.is_fence(line) {
+                        in_code_block = !in_code_block;
                         Line::from(Span::styled(
                             line.to_string(),
-                            Style::default()
-                                .fg(app.theme.header)
-                                .add_modifier(Modifier::BOLD),
+                            app.theme.style(app.theme.dim, Modifier::DIM),
                         ))
-                    } else if line.starts_with("## ") {
+                    } else if in_code_block {
                         Line::from(Span::styled(
                             line.to_string(),
-                            Style::default()
-                                .fg(app.theme.accent)
-                                .add_modifier(Modifier::BOLD),
+                            Style::default().fg(app.theme.code),
                         ))
-                    } else if line.starts_with("### ") {
+                    } else if let Some(level @ 1..=3) = format.heading_level(line) {
+                        let color = match level {
+                            1 => app.theme.header,
+                            2 => app.theme.accent,
+                            _ => app.theme.selection,
+                        };
                         Line::from(Span::styled(
                             line.to_string(),
-                            Style::default()
-                                .fg(app.theme.selection)
-                                .add_modifier(Modifier::BOLD),
+                            Style::default().fg(color).add_modifier(Modifier::BOLD),
                         ))
-                    } else if line.starts_with(
-                        "`
-```",
-                    ) {
-                        Line::from(Span::styled(
-                            line.to_string(),
-                            Style::default().fg(app.theme.dim),
-                        ))
-                    } else if line.starts_with("> ") {
+                    } else if format == crate::data::NoteFormat::Markdown && line.starts_with("> ")
+                    {
                         Line::from(Span::styled(
                             line.to_string(),
                             Style::default()
-                                .fg(Color::Rgb(166, 227, 161))
+                                .fg(app.theme.quote)
                                 .add_modifier(Modifier::ITALIC),
                         ))
-                    } else {
+                    } else if format == crate::data::NoteFormat::Text {
                         Line::from(line.to_string())
+                    } else {
+                        inline_spans(line, &app.theme)
                     }
                 })
                 .collect();
@@ -230,16 +273,23 @@ pub fn ui(f: &mut Frame, app: &mut App) {
                             Line::from(Span::styled(
                                 "  > Folder",
                                 Style::default()
-                                    .fg(Color::Yellow)
+                                    .fg(app.theme.folder)
                                     .add_modifier(Modifier::BOLD),
                             )),
-                            Line::from(format!("  {}", p.file_name().unwrap().to_string_lossy())),
+                            Line::from(format!(
+                                "  {}",
+                                p.file_name().unwrap_or(p.as_os_str()).to_string_lossy()
+                            )),
                             Line::from(""),
                             Line::from("  Press 'l' or Enter to open."),
                         ],
                         " Folder Info ".to_string(),
                         String::new(),
                     ),
+                    crate::data::FileSystemItem::Attachment(p) => {
+                        let p = p.clone();
+                        attachment_preview(app, &p)
+                    }
                     _ => (vec![Line::from("")], " Preview ".to_string(), String::new()),
                 }
             } else {
@@ -259,7 +309,7 @@ pub fn ui(f: &mut Frame, app: &mut App) {
         .title_style(Style::default().add_modifier(Modifier::BOLD))
         .borders(Borders::ALL)
         .border_type(BorderType::Rounded)
-        .border_style(Style::default().fg(app.theme.accent));
+        .border_style(Style::default().fg(app.theme.border));
 
     let preview_block = if !preview_footer.is_empty() {
         preview_block
@@ -268,15 +318,21 @@ pub fn ui(f: &mut Frame, app: &mut App) {
         preview_block
     };
 
+    // wrap here rather than in the paragraph, so clicks can be mapped to text
+    let text_width = preview_area.map_or(0, |a| a.width.saturating_sub(2) as usize);
+    let (preview_content, preview_rows) = wrap_lines(preview_content, text_width);
+    app.preview_rows = preview_rows;
+
     let preview = Paragraph::new(preview_content)
         .block(preview_block)
-        .scroll((app.preview_scroll, 0))
-        .wrap(Wrap { trim: false });
+        .scroll((app.preview_scroll, 0));
 
-    f.render_widget(preview, main_chunks[1]);
+    if let Some(area) = preview_area {
+        f.render_widget(preview, area);
+    }
 
     // render logs
-    if app.show_logs {
+    if let Some(area) = log_area {
         let tui_sm = TuiLoggerWidget::default()
             .block(
                 Block::default()
@@ -288,7 +344,7 @@ pub fn ui(f: &mut Frame, app: &mut App) {
             .output_separator('|')
             .output_timestamp(Some("%H:%M:%S".to_string()))
             .style(Style::default().fg(Color::Reset));
-        f.render_widget(tui_sm, chunks[1]);
+        f.render_widget(tui_sm, area);
     }
 
     // render status bar
@@ -313,10 +369,25 @@ pub fn ui(f: &mut Frame, app: &mut App) {
         InputMode::Editing => format!("{} CREATING NOTE: {}", spinner, app.status_msg),
         InputMode::CreatingFolder => format!("{} CREATING FOLDER: {}", spinner, app.status_msg),
         InputMode::Renaming => format!("{} RENAMING NOTE: {}", spinner, app.status_msg),
+        InputMode::Attaching => format!("{} ATTACHING FILE: {}", spinner, app.status_msg),
         InputMode::ConfirmDelete => format!("{} DELETING NOTE: {}", spinner, app.status_msg),
         InputMode::Search => format!("{} SEARCH: {}", spinner, app.search_query),
         InputMode::TagSearch => format!("{} TAG SEARCH: {}", spinner, app.search_query),
         InputMode::ContentSearch => format!("{} CONTENT SEARCH: {}", spinner, app.search_query),
+        InputMode::Palette => format!("{} COMMAND: {}", spinner, app.palette_query),
+        InputMode::VaultSwitcher => format!("{} VAULTS: Enter to switch, Esc to close", spinner),
+        InputMode::Doctor => format!(
+            "{} DOCTOR: {} (Enter to jump, Esc to close)",
+            spinner, app.status_msg
+        ),
+        InputMode::Tasks => format!(
+            "{} TASKS: {} (Space to toggle, Tab to filter, Enter to jump)",
+            spinner, app.status_msg
+        ),
+        InputMode::Calendar => format!(
+            "{} CALENDAR: {} (h/l day, j/k week, H/L month, Enter to open)",
+            spinner, app.status_msg
+        ),
         InputMode::Help => format!("{} HELP: Press Esc to close", spinner),
     };
 
@@ -325,20 +396,34 @@ pub fn ui(f: &mut Frame, app: &mut App) {
         .border_type(BorderType::Rounded)
         .border_style(Style::default().fg(app.theme.dim));
 
+    // last successful sync indicator
+    let status_block = if let Some(synced) = app.last_sync {
+        status_block.title_top(
+            Line::from(format!(" synced {} ", synced.format("%H:%M")))
+                .alignment(ratatui::layout::Alignment::Right),
+        )
+    } else {
+        status_block
+    };
+
     let status = Paragraph::new(status_text.as_str())
         .block(status_block)
         .style(
             Style::default()
-                .fg(app.theme.accent)
+                .fg(app.theme.status)
+                .bg(app.theme.status_bg)
                 .add_modifier(Modifier::BOLD),
         );
 
-    f.render_widget(status, status_area);
+    if let Some(area) = status_area {
+        f.render_widget(status, area);
+    }
 
     // render popups
     if app.input_mode == InputMode::Editing
         || app.input_mode == InputMode::Renaming
         || app.input_mode == InputMode::CreatingFolder
+        || app.input_mode == InputMode::Attaching
     {
         let area = centered_rect(60, 20, f.area());
         f.render_widget(Clear, area);
@@ -347,6 +432,7 @@ pub fn ui(f: &mut Frame, app: &mut App) {
             InputMode::Editing => " New Note ",
             InputMode::Renaming => " Rename Note ",
             InputMode::CreatingFolder => " New Folder ",
+            InputMode::Attaching => " Attach File (path) ",
             _ => "",
         };
 
@@ -354,7 +440,7 @@ pub fn ui(f: &mut Frame, app: &mut App) {
             .title(title)
             .borders(Borders::ALL)
             .border_type(BorderType::Rounded)
-            .border_style(Style::default().fg(Color::Rgb(166, 227, 161)));
+            .border_style(Style::default().fg(app.theme.border));
 
         let input_text = Paragraph::new(app.input.as_str())
             .block(input_block)
@@ -391,7 +477,7 @@ pub fn ui(f: &mut Frame, app: &mut App) {
             ]),
             Line::from(""),
             Line::from(vec![
-                Span::styled("(y)es", Style::default().fg(Color::Rgb(166, 227, 161))),
+                Span::styled("(y)es", Style::default().fg(app.theme.accent)),
                 Span::raw(" / "),
                 Span::styled("(n)o", Style::default().fg(app.theme.bold)),
             ]),
@@ -405,8 +491,197 @@ pub fn ui(f: &mut Frame, app: &mut App) {
         f.render_widget(confirm_text, area);
     }
 
+    if app.input_mode == InputMode::Palette {
+        let area = centered_rect(60, 50, f.area());
+        f.render_widget(Clear, area);
+
+        let chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([Constraint::Length(3), Constraint::Fill(1)])
+            .split(area);
+
+        let input = Paragraph::new(format!("> {}", app.palette_query)).block(
+            Block::default()
+                .title(" Command Palette ")
+                .borders(Borders::ALL)
+                .border_type(BorderType::Rounded)
+                .border_style(Style::default().fg(app.theme.border)),
+        );
+        f.render_widget(input, chunks[0]);
+
+        let items: Vec<ListItem> = app
+            .palette_entries
+            .iter()
+            .map(|entry| {
+                ListItem::new(Line::from(vec![
+                    Span::raw(format!(" {}", entry.label)),
+                    Span::styled(
+                        format!("  {}", entry.detail),
+                        app.theme.style(app.theme.dim, Modifier::DIM),
+                    ),
+                ]))
+            })
+            .collect();
+
+        let list = List::new(items)
+            .block(
+                Block::default()
+                    .borders(Borders::ALL)
+                    .border_type(BorderType::Rounded)
+                    .border_style(Style::default().fg(app.theme.border)),
+            )
+            .highlight_style(app.theme.selected());
+        f.render_stateful_widget(list, chunks[1], &mut app.palette_state);
+    }
+
+    if app.input_mode == InputMode::VaultSwitcher {
+        let area = centered_rect(50, 40, f.area());
+        f.render_widget(Clear, area);
+
+        let items: Vec<ListItem> = app
+            .config
+            .vaults
+            .iter()
+            .flatten()
+            .map(|vault| {
+                let current = app
+                    .vault_name
+                    .as_deref()
+                    .is_some_and(|name| name.eq_ignore_ascii_case(&vault.name));
+                let marker = if current { "* " } else { "  " };
+                ListItem::new(Line::from(vec![
+                    Span::raw(format!(" {}{}", marker, vault.name)),
+                    Span::styled(
+                        format!("  {}", vault.path),
+                        app.theme.style(app.theme.dim, Modifier::DIM),
+                    ),
+                ]))
+            })
+            .collect();
+
+        let list = List::new(items)
+            .block(
+                Block::default()
+                    .title(" Vaults ")
+                    .borders(Borders::ALL)
+                    .border_type(BorderType::Rounded)
+                    .border_style(Style::default().fg(app.theme.border)),
+            )
+            .highlight_style(app.theme.selected());
+        f.render_stateful_widget(list, area, &mut app.vault_state);
+    }
+
+    if app.input_mode == InputMode::Doctor {
+        let area = centered_rect(70, 60, f.area());
+        f.render_widget(Clear, area);
+
+        let errors = app
+            .findings
+            .iter()
+            .filter(|finding| finding.severity == Severity::Error)
+            .count();
+        let warnings = app.findings.len() - errors;
+
+        let mut last = None;
+        let items: Vec<ListItem> = app
+            .findings
+            .iter()
+            .map(|finding| {
+                let mut lines = Vec::new();
+                // each severity group opens with a header
+                if last != Some(finding.severity) {
+                    last = Some(finding.severity);
+                    let header = match finding.severity {
+                        Severity::Error => format!(" Errors ({})", errors),
+                        Severity::Warning => format!(" Warnings ({})", warnings),
+                    };
+                    lines.push(Line::from(Span::styled(
+                        header,
+                        app.theme.style(app.theme.header, Modifier::BOLD),
+                    )));
+                }
+                lines.push(Line::from(vec![
+                    Span::styled(
+                        format!("   {}", finding.location(&app.base_path)),
+                        app.theme.style(app.theme.link, Modifier::empty()),
+                    ),
+                    Span::raw(format!("  {}", finding.message)),
+                ]));
+                ListItem::new(lines)
+            })
+            .collect();
+
+        let list = List::new(items)
+            .block(
+                Block::default()
+                    .title(" Vault Health ")
+                    .borders(Borders::ALL)
+                    .border_type(BorderType::Rounded)
+                    .border_style(Style::default().fg(app.theme.border)),
+            )
+            .highlight_style(app.theme.selected());
+        f.render_stateful_widget(list, area, &mut app.doctor_state);
+    }
+
+    if app.input_mode == InputMode::Tasks {
+        let area = centered_rect(70, 60, f.area());
+        f.render_widget(Clear, area);
+
+        let today = Local::now().date_naive();
+        let items: Vec<ListItem> = app
+            .visible_tasks()
+            .into_iter()
+            .map(|i| {
+                let task = &app.tasks[i];
+                let rel = task.path.strip_prefix(&app.base_path).unwrap_or(&task.path);
+                let mut place = rel.with_extension("").to_string_lossy().to_string();
+                if let Some(heading) = &task.heading {
+                    place.push_str(&format!(" > {}", heading));
+                }
+
+                let (mark, text_style) = if task.done {
+                    ("[x]", app.theme.style(app.theme.dim, Modifier::DIM))
+                } else {
+                    ("[ ]", Style::default())
+                };
+                let mut spans = vec![
+                    Span::styled(format!(" {} ", mark), text_style),
+                    Span::styled(task.text.clone(), text_style),
+                ];
+                if let Some(due) = task.due {
+                    let due_style = if task.is_overdue(today) {
+                        app.theme.style(app.theme.accent, Modifier::BOLD)
+                    } else {
+                        app.theme.style(app.theme.tag, Modifier::empty())
+                    };
+                    spans.push(Span::styled(format!("  due {}", due), due_style));
+                }
+                spans.push(Span::styled(
+                    format!("  {}", place),
+                    app.theme.style(app.theme.dim, Modifier::DIM),
+                ));
+                ListItem::new(Line::from(spans))
+            })
+            .collect();
+
+        let list = List::new(items)
+            .block(
+                Block::default()
+                    .title(format!(" Tasks ({}) ", app.task_filter.label()))
+                    .borders(Borders::ALL)
+                    .border_type(BorderType::Rounded)
+                    .border_style(Style::default().fg(app.theme.border)),
+            )
+            .highlight_style(app.theme.selected());
+        f.render_stateful_widget(list, area, &mut app.tasks_state);
+    }
+
+    if app.input_mode == InputMode::Calendar {
+        draw_calendar(f, app);
+    }
+
     if app.input_mode == InputMode::Help {
-        let area = centered_rect(60, 60, f.area());
+        let area = centered_rect(60, 80, f.area());
         f.render_widget(Clear, area);
 
         let help_block = Block::default()
@@ -415,55 +690,7 @@ pub fn ui(f: &mut Frame, app: &mut App) {
             .border_type(BorderType::Rounded)
             .border_style(Style::default().fg(app.theme.accent));
 
-        let text = vec![
-            Line::from(Span::styled(
-                "Navigation",
-                Style::default()
-                    .fg(app.theme.accent)
-                    .add_modifier(Modifier::BOLD),
-            )),
-            Line::from("  j / k       : Scroll list down / up"),
-            Line::from("  h / l       : Go up / Enter folder"),
-            Line::from("  Ctrl+j / k  : Scroll preview down / up"),
-            Line::from("  Enter       : Edit selected note"),
-            Line::from(""),
-            Line::from(Span::styled(
-                "Actions",
-                Style::default()
-                    .fg(app.theme.accent)
-                    .add_modifier(Modifier::BOLD),
-            )),
-            Line::from("  n           : New note"),
-            Line::from("  f           : New folder"),
-            Line::from("  r           : Rename note"),
-            Line::from("  d           : Delete note"),
-            Line::from("  g           : Sync with git"),
-            Line::from("  s           : Cycle sort mode"),
-            Line::from("  y           : Copy content to clipboard"),
-            Line::from("  Y           : Copy path to clipboard"),
-            Line::from(""),
-            Line::from(Span::styled(
-                "Search",
-                Style::default()
-                    .fg(app.theme.accent)
-                    .add_modifier(Modifier::BOLD),
-            )),
-            Line::from("  /           : Search notes by title"),
-            Line::from("  ?           : Search notes by content"),
-            Line::from("  #           : Search notes by tag"),
-            Line::from("  Esc         : Clear search / Close popup"),
-            Line::from(""),
-            Line::from(Span::styled(
-                "General",
-                Style::default()
-                    .fg(app.theme.accent)
-                    .add_modifier(Modifier::BOLD),
-            )),
-            Line::from("  F1          : Toggle this help"),
-            Line::from("  t           : Cycle themes"),
-            Line::from("  F12         : Toggle logs"),
-            Line::from("  q           : Quit"),
-        ];
+        let text = help_lines(app);
 
         let help_text = Paragraph::new(text)
             .block(help_block)
@@ -474,6 +701,322 @@ pub fn ui(f: &mut Frame, app: &mut App) {
     }
 }
 
+// help text built from the active key bindings
+fn help_lines(app: &App) -> Vec<Line<'static>> {
+    let width = KeyAction::ALL
+        .iter()
+        .map(|a| app.keymap.describe(*a).len())
+        .max()
+        .unwrap_or(0)
+        .max(11);
+
+    let mut lines = Vec::new();
+    for section in ["Navigation", "Actions", "Search", "Layout", "General"] {
+        if !lines.is_empty() {
+            lines.push(Line::from(""));
+        }
+        lines.push(Line::from(Span::styled(
+            section,
+            Style::default()
+                .fg(app.theme.accent)
+                .add_modifier(Modifier::BOLD),
+        )));
+
+        for action in KeyAction::ALL.iter().filter(|a| a.section() == section) {
+            lines.push(Line::from(format!(
+                "  {:<width$} : {}",
+                app.keymap.describe(*action),
+                action.description(),
+            )));
+        }
+
+        if section == "Search" {
+            lines.push(Line::from(format!(
+                "  {:<width$} : Clear search / Close popup",
+                "Esc"
+            )));
+        }
+    }
+    lines
+}
+
+// kind, size and the notes linking to an attachment
+fn attachment_preview(app: &mut App, path: &Path) -> (Vec<Line<'static>>, String, String) {
+    let kind = crate::data::AttachmentKind::from_path(path);
+    let name = path
+        .file_name()
+        .unwrap_or(path.as_os_str())
+        .to_string_lossy()
+        .to_string();
+    let linked_from = app.linked_from(path);
+
+    let mut lines = vec![
+        Line::from(""),
+        Line::from(Span::styled(
+            format!("  {} {}", kind.icon(), kind.label()),
+            Style::default()
+                .fg(app.theme.tag)
+                .add_modifier(Modifier::BOLD),
+        )),
+        Line::from(format!("  {}", name)),
+        Line::from(""),
+    ];
+    if linked_from.is_empty() {
+        lines.push(Line::from(Span::styled(
+            "  Orphaned: no note links to this file.",
+            app.theme.style(app.theme.dim, Modifier::DIM),
+        )));
+    } else {
+        lines.push(Line::from("  Linked from:"));
+        for note in &linked_from {
+            let rel = note.strip_prefix(&app.base_path).unwrap_or(note);
+            lines.push(Line::from(Span::styled(
+                format!("    {}", rel.display()),
+                Style::default().fg(app.theme.link),
+            )));
+        }
+    }
+    lines.push(Line::from(""));
+    lines.push(Line::from("  Press 'l' or Enter to open it."));
+
+    let footer = match std::fs::metadata(path) {
+        Ok(meta) => {
+            let modified = meta
+                .modified()
+                .map(|t| {
+                    DateTime::<Local>::from(t)
+                        .format("%Y-%m-%d %H:%M")
+                        .to_string()
+                })
+                .unwrap_or_default();
+            format!(" {} | {} bytes ", modified, meta.len())
+        }
+        Err(_) => String::new(),
+    };
+    (lines, format!(" {} ", name), footer)
+}
+
+// color inline code, links and #tags in a line of prose
+fn inline_spans(line: &str, theme: &ThemeColors) -> Line<'static> {
+    let mut spans = Vec::new();
+    let mut plain = String::new();
+    let mut rest = line;
+
+    while let Some(c) = rest.chars().next() {
+        let styled_len = match c {
+            '`' => rest[1..]
+                .find('`')
+                .map(|end| (end + 2, theme.style(theme.code, Modifier::BOLD))),
+            '[' if rest.starts_with("[[") => rest
+                .find("]]")
+                .map(|end| (end + 2, theme.style(theme.link, Modifier::UNDERLINED))),
+            '[' => rest.find("](").and_then(|mid| {
+                rest[mid..]
+                    .find(')')
+                    .map(|end| (mid + end + 1, theme.style(theme.link, Modifier::UNDERLINED)))
+            }),
+            '#' if plain.is_empty() || plain.ends_with(char::is_whitespace) => {
+                let len = rest[1..]
+                    .find(|ch: char| !(ch.is_alphanumeric() || "_-/".contains(ch)))
+                    .unwrap_or(rest.len() - 1);
+                (len > 0).then_some((len + 1, theme.style(theme.tag, Modifier::ITALIC)))
+            }
+            _ => None,
+        };
+
+        match styled_len {
+            Some((len, style)) => {
+                if !plain.is_empty() {
+                    spans.push(Span::raw(std::mem::take(&mut plain)));
+                }
+                spans.push(Span::styled(rest[..len].to_string(), style));
+                rest = &rest[len..];
+            }
+            None => {
+                plain.push(c);
+                rest = &rest[c.len_utf8()..];
+            }
+        }
+    }
+
+    if !plain.is_empty() {
+        spans.push(Span::raw(plain));
+    }
+    Line::from(spans)
+}
+
+// word-wrap lines to a width, noting the line and char offset each row starts at
+fn wrap_lines(lines: Vec<Line>, width: usize) -> (Vec<Line<'static>>, Vec<(usize, usize)>) {
+    let mut rows = Vec::new();
+    let mut starts = Vec::new();
+
+    for (index, line) in lines.into_iter().enumerate() {
+        let cells: Vec<(char, Style)> = line
+            .spans
+            .iter()
+            .flat_map(|span| {
+                let style = line.style.patch(span.style);
+                span.content.chars().map(move |c| (c, style))
+            })
+            .collect();
+
+        let mut start = 0;
+        loop {
+            let mut used = 0;
+            let mut end = start;
+            let mut last_space = None;
+            while end < cells.len() {
+                let w = cells[end].0.width().unwrap_or(0);
+                if width > 0 && used + w > width {
+                    break;
+                }
+                if cells[end].0.is_whitespace() {
+                    last_space = Some(end);
+                }
+                used += w;
+                end += 1;
+            }
+
+            // break after the last space, or mid-word if there is none
+            let next = if end == cells.len() {
+                end
+            } else {
+                match last_space {
+                    Some(space) if space > start => space + 1,
+                    _ => end.max(start + 1),
+                }
+            };
+
+            rows.push(styled_row(&cells[start..next]));
+            starts.push((index, start));
+            start = next;
+            if start >= cells.len() {
+                break;
+            }
+        }
+    }
+
+    (rows, starts)
+}
+
+// one row of styled chars, merging runs of the same style
+fn styled_row(cells: &[(char, Style)]) -> Line<'static> {
+    let mut spans: Vec<Span<'static>> = Vec::new();
+    for &(c, style) in cells {
+        match spans.last_mut() {
+            Some(span) if span.style == style => span.content.to_mut().push(c),
+            _ => spans.push(Span::styled(c.to_string(), style)),
+        }
+    }
+    Line::from(spans)
+}
+
+// list and preview areas for the current layout
+pub fn pane_areas(app: &App, area: Rect) -> (Option<Rect>, Option<Rect>) {
+    if app.zen {
+        return (None, Some(area));
+    }
+    if !app.show_preview {
+        return (Some(area), None);
+    }
+
+    // narrow terminals get the list above the preview
+    let direction = if area.width < STACK_BELOW_COLUMNS {
+        Direction::Vertical
+    } else {
+        Direction::Horizontal
+    };
+    let chunks = Layout::default()
+        .direction(direction)
+        .constraints([
+            Constraint::Percentage(app.list_width),
+            Constraint::Percentage(100 - app.list_width),
+        ])
+        .split(area);
+    (Some(chunks[0]), Some(chunks[1]))
+}
+
+// month grid with the picked day's notes below it
+fn draw_calendar(f: &mut Frame, app: &App) {
+    const SHOWN_NOTES: usize = 5;
+    let date = app.calendar_date;
+    let today = Local::now().date_naive();
+    let weeks = calendar::month_weeks(date);
+    let notes = app.calendar_days.get(&date).map_or(&[][..], Vec::as_slice);
+
+    let mut lines = vec![Line::from(Span::styled(
+        " Mo  Tu  We  Th  Fr  Sa  Su",
+        app.theme.style(app.theme.header, Modifier::BOLD),
+    ))];
+    for week in &weeks {
+        let mut spans = Vec::new();
+        for day in week {
+            let Some(day) = *day else {
+                spans.push(Span::raw("    "));
+                continue;
+            };
+            // a dot marks days with notes
+            let has_notes = app.calendar_days.contains_key(&day);
+            let marker = if has_notes { "•" } else { " " };
+            let mut style = if has_notes {
+                app.theme.style(app.theme.accent, Modifier::BOLD)
+            } else {
+                Style::default()
+            };
+            if day == today {
+                style = style.add_modifier(Modifier::UNDERLINED);
+            }
+            if day == date {
+                style = app.theme.selected();
+            }
+            spans.push(Span::styled(format!("{:>3}{}", day.day(), marker), style));
+        }
+        lines.push(Line::from(spans));
+    }
+
+    lines.push(Line::from(""));
+    if notes.is_empty() {
+        lines.push(Line::from(Span::styled(
+            " No notes, Enter starts the journal",
+            app.theme.style(app.theme.dim, Modifier::DIM),
+        )));
+    }
+    for path in notes.iter().take(SHOWN_NOTES) {
+        let rel = path.strip_prefix(&app.base_path).unwrap_or(path);
+        lines.push(Line::from(format!(" {}", rel.with_extension("").display())));
+    }
+    if notes.len() > SHOWN_NOTES {
+        lines.push(Line::from(Span::styled(
+            format!(" and {} more", notes.len() - SHOWN_NOTES),
+            app.theme.style(app.theme.dim, Modifier::DIM),
+        )));
+    }
+
+    let height = lines.len() as u16 + 2;
+    let area = fixed_rect(40, height, f.area());
+    f.render_widget(Clear, area);
+    let calendar = Paragraph::new(lines).block(
+        Block::default()
+            .title(format!(" {} ", date.format("%B %Y")))
+            .borders(Borders::ALL)
+            .border_type(BorderType::Rounded)
+            .border_style(Style::default().fg(app.theme.border)),
+    );
+    f.render_widget(calendar, area);
+}
+
+// a popup of a set size in the middle of r, shrunk to fit
+fn fixed_rect(width: u16, height: u16, r: Rect) -> Rect {
+    let width = width.min(r.width);
+    let height = height.min(r.height);
+    Rect::new(
+        r.x + (r.width - width) / 2,
+        r.y + (r.height - height) / 2,
+        width,
+        height,
+    )
+}
+
 // center rect helper
 fn centered_rect(percent_x: u16, percent_y: u16, r: Rect) -> Rect {
     let popup_layout = Layout::default()
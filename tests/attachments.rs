@@ -0,0 +1,146 @@
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use kiroku_tui::app::{Action, App};
+use kiroku_tui::attachments;
+use kiroku_tui::config::Config;
+use kiroku_tui::data::{self, AttachmentKind, FileSystemItem, IgnoreRules};
+use std::fs;
+
+#[test]
+fn test_browser_lists_attachments_after_notes() {
+    let dir = tempfile::tempdir().unwrap();
+    let root = dir.path();
+    fs::create_dir_all(root.join("docs")).unwrap();
+    fs::write(root.join("b.md"), "").unwrap();
+    fs::write(root.join("a.png"), "").unwrap();
+    fs::write(root.join("manual.pdf"), "").unwrap();
+
+    let items = data::load_all_items(&root.to_string_lossy()).unwrap();
+    let kinds: Vec<&str> = items
+        .iter()
+        .map(|item| match item {
+            FileSystemItem::Folder(_) => "folder",
+            FileSystemItem::Note(_) => "note",
+            FileSystemItem::Attachment(_) => "attachment",
+        })
+        .collect();
+    assert_eq!(kinds, vec!["folder", "note", "attachment", "attachment"]);
+    assert_eq!(items[2].path(), root.join("a.png"));
+
+    assert_eq!(
+        AttachmentKind::from_path(&root.join("a.PNG")),
+        AttachmentKind::Image
+    );
+    assert_eq!(
+        AttachmentKind::from_path(&root.join("manual.pdf")),
+        AttachmentKind::Pdf
+    );
+
+    // opening an attachment hands it to the desktop
+    let notes = data::load_notes(&root.to_string_lossy()).unwrap();
+    let mut app = App::new(notes, root.into(), Config::default());
+    app.list_state.select(Some(2));
+    let action = app.handle_input(KeyEvent::new(KeyCode::Enter, KeyModifiers::NONE));
+    let expected = root.join("a.png").to_string_lossy().to_string();
+    assert!(matches!(action, Action::OpenUrl(url) if url == expected));
+}
+
+#[test]
+fn test_attach_file_copies_and_links() {
+    let dir = tempfile::tempdir().unwrap();
+    let root = dir.path();
+    let outside = tempfile::tempdir().unwrap();
+    let source = outside.path().join("diagram.png");
+    fs::write(&source, "png").unwrap();
+    fs::create_dir_all(root.join("work")).unwrap();
+    let note = root.join("work/plan.md");
+    fs::write(&note, "").unwrap();
+
+    // vault-wide folder, without overwriting an earlier copy
+    let config = Config::default();
+    let first = attachments::attach_file(root, &note, &source, &config).unwrap();
+    let second = attachments::attach_file(root, &note, &source, &config).unwrap();
+    assert_eq!(first, root.join("attachments/diagram.png"));
+    assert_eq!(second, root.join("attachments/diagram-1.png"));
+    assert_eq!(
+        attachments::link_for(&note, &first),
+        "![diagram.png](../attachments/diagram.png)"
+    );
+
+    // per-note folder next to the note
+    let config = Config {
+        attachment_dir: Some("./{note}.assets".to_string()),
+        ..Config::default()
+    };
+    let local = attachments::attach_file(root, &note, &source, &config).unwrap();
+    assert_eq!(local, root.join("work/plan.assets/diagram.png"));
+    assert_eq!(
+        attachments::link_for(&note, &local),
+        "![diagram.png](plan.assets/diagram.png)"
+    );
+
+    let pdf = root.join("work/my report.pdf");
+    assert_eq!(
+        attachments::link_for(&note, &pdf),
+        "[my report.pdf](<my report.pdf>)"
+    );
+    assert_eq!(
+        attachments::link_for(&root.join("work/plan.org"), &local),
+        "[[file:plan.assets/diagram.png]]"
+    );
+    assert_eq!(
+        attachments::link_for(&root.join("work/plan.adoc"), &pdf),
+        "link:my report.pdf[my report.pdf]"
+    );
+}
+
+#[test]
+fn test_orphaned_attachments() {
+    let dir = tempfile::tempdir().unwrap();
+    let root = dir.path();
+    fs::create_dir_all(root.join("attachments")).unwrap();
+    fs::create_dir_all(root.join("notes")).unwrap();
+    for file in [
+        "linked.png",
+        "wiki.jpg",
+        "org.pdf",
+        "rooted.zip",
+        "lonely.png",
+    ] {
+        fs::write(root.join("attachments").join(file), "").unwrap();
+    }
+    fs::write(
+        root.join("notes/a.md"),
+        "![x](../attachments/linked.png)\n![[wiki.jpg]]\n[site](https://example.com/lonely.png)\n",
+    )
+    .unwrap();
+    fs::write(
+        root.join("notes/b.org"),
+        "[[file:../attachments/org.pdf][report]]\n",
+    )
+    .unwrap();
+    fs::write(
+        root.join("c.md"),
+        "[zip](/attachments/rooted.zip)\n`![no](attachments/lonely.png)`\n",
+    )
+    .unwrap();
+
+    let config = Config {
+        note_extensions: Some(vec!["md".to_string(), "org".to_string()]),
+        ..Config::default()
+    };
+    let ignore = IgnoreRules::from_config(root, &config);
+    let notes = data::load_notes_with(&root.to_string_lossy(), &ignore).unwrap();
+
+    let orphans = attachments::orphans(root, &notes, &ignore);
+    assert_eq!(orphans, vec![root.join("attachments/lonely.png")]);
+
+    let mut app = App::new(notes, root.into(), config);
+    assert_eq!(
+        app.linked_from(&root.join("attachments/linked.png")),
+        vec![root.join("notes/a.md")]
+    );
+    assert!(
+        app.linked_from(&root.join("attachments/lonely.png"))
+            .is_empty()
+    );
+}
@@ -0,0 +1,106 @@
+use chrono::{Local, NaiveDate};
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use kiroku_tui::app::{Action, App, InputMode};
+use kiroku_tui::calendar;
+use kiroku_tui::config::Config;
+use kiroku_tui::data;
+use std::fs;
+
+fn date(s: &str) -> NaiveDate {
+    NaiveDate::parse_from_str(s, "%Y-%m-%d").unwrap()
+}
+
+#[test]
+fn test_note_dates_from_name_frontmatter_and_mtime() {
+    let dir = tempfile::tempdir().unwrap();
+    let root = dir.path();
+    fs::create_dir_all(root.join("diary/2026")).unwrap();
+    fs::write(root.join("diary/2026/17.10.md"), "").unwrap();
+    fs::write(root.join("meeting 2026-10-02.md"), "").unwrap();
+    fs::write(root.join("trip.md"), "---\ndate: 2026-09-30T08:00\n---\n").unwrap();
+    fs::write(root.join("loose.md"), "no date\n").unwrap();
+
+    let config = Config {
+        journal_dir: Some("diary".into()),
+        journal_format: Some("%Y/%d.%m".into()),
+        ..Config::default()
+    };
+    let notes = data::load_notes(&root.to_string_lossy()).unwrap();
+    let days = calendar::note_dates(root, &notes, &config);
+
+    assert_eq!(
+        days[&date("2026-10-17")],
+        vec![root.join("diary/2026/17.10.md")]
+    );
+    assert_eq!(
+        days[&date("2026-10-02")],
+        vec![root.join("meeting 2026-10-02.md")]
+    );
+    assert_eq!(days[&date("2026-09-30")], vec![root.join("trip.md")]);
+    assert_eq!(
+        days[&Local::now().date_naive()],
+        vec![root.join("loose.md")]
+    );
+}
+
+#[test]
+fn test_month_grid_and_moves() {
+    // october 2026 starts on a thursday
+    let weeks = calendar::month_weeks(date("2026-10-18"));
+    assert_eq!(weeks.len(), 5);
+    assert_eq!(weeks[0][..3], [None, None, None]);
+    assert_eq!(weeks[0][3], Some(date("2026-10-01")));
+    assert_eq!(weeks[4][5], Some(date("2026-10-31")));
+    assert_eq!(weeks[4][6], None);
+
+    assert_eq!(
+        calendar::add_months(date("2026-01-31"), 1),
+        date("2026-02-28")
+    );
+    assert_eq!(
+        calendar::add_months(date("2026-03-15"), -3),
+        date("2025-12-15")
+    );
+}
+
+#[test]
+fn test_calendar_view_opens_or_creates_notes() {
+    let dir = tempfile::tempdir().unwrap();
+    let root = dir.path();
+    let today = Local::now().date_naive();
+    let yesterday = today.pred_opt().unwrap();
+    let named = root.join(format!("standup {}.md", yesterday.format("%Y-%m-%d")));
+    fs::write(&named, "").unwrap();
+
+    let notes = data::load_notes(&root.to_string_lossy()).unwrap();
+    let mut app = App::new(notes, root.into(), Config::default());
+    app.refresh_fs_view();
+    let key = |code| KeyEvent::new(code, KeyModifiers::NONE);
+
+    app.handle_input(key(KeyCode::Char('c')));
+    assert!(app.input_mode == InputMode::Calendar);
+    assert_eq!(app.calendar_date, today);
+
+    // by day, week and month
+    app.handle_input(key(KeyCode::Char('j')));
+    app.handle_input(key(KeyCode::Char('L')));
+    app.handle_input(key(KeyCode::Char('H')));
+    app.handle_input(key(KeyCode::Char('k')));
+    assert_eq!(app.calendar_date, today);
+
+    // a day with a note opens it
+    app.handle_input(key(KeyCode::Char('h')));
+    let action = app.handle_input(key(KeyCode::Enter));
+    assert!(matches!(action, Action::OpenNote(p) if p == named));
+    assert!(app.input_mode == InputMode::Normal);
+
+    // an empty day gets a journal note
+    app.handle_input(key(KeyCode::Char('c')));
+    app.handle_input(key(KeyCode::Char('l')));
+    let action = app.handle_input(key(KeyCode::Enter));
+    let tomorrow = today.succ_opt().unwrap();
+    let journal = root.join(format!("journal/{}.md", tomorrow.format("%Y-%m-%d")));
+    assert!(matches!(action, Action::OpenNote(p) if p == journal));
+    assert!(journal.exists());
+    assert_eq!(app.selected_path(), Some(journal));
+}
@@ -0,0 +1,243 @@
+use kiroku_tui::cli::{Command, ConfigCommand, LogCommand, parse_args, run};
+use kiroku_tui::config::Config;
+use kiroku_tui::data;
+use kiroku_tui::export::OutputFormat;
+use kiroku_tui::tasks::TaskFilter;
+use std::path::PathBuf;
+
+fn args(s: &[&str]) -> Vec<String> {
+    s.iter().map(|a| a.to_string()).collect()
+}
+
+#[test]
+fn test_parse_subcommands() {
+    let cli = parse_args(&args(&[])).unwrap();
+    assert_eq!(cli.command, Command::Tui);
+    assert_eq!(cli.vault, None);
+
+    // legacy positional vault path still opens the tui
+    let cli = parse_args(&args(&["/tmp/notes"])).unwrap();
+    assert_eq!(cli.command, Command::Tui);
+    assert_eq!(cli.vault, Some(PathBuf::from("/tmp/notes")));
+
+    let cli = parse_args(&args(&[
+        "--vault", "/v", "new", "Weekly", "sync", "--tag", "work", "--folder", "meetings",
+    ]))
+    .unwrap();
+    assert_eq!(cli.vault, Some(PathBuf::from("/v")));
+    assert_eq!(
+        cli.command,
+        Command::New {
+            title: "Weekly sync".to_string(),
+            tags: vec!["work".to_string()],
+            folder: Some("meetings".to_string()),
+        }
+    );
+
+    let cli = parse_args(&args(&["list", "--tag", "work", "--json"])).unwrap();
+    assert_eq!(
+        cli.command,
+        Command::List {
+            tag: Some("work".to_string()),
+            format: OutputFormat::Json,
+        }
+    );
+
+    let cli = parse_args(&args(&["--help", "search", "todo"])).unwrap();
+    assert_eq!(cli.command, Command::Help);
+}
+
+#[test]
+fn test_global_options_come_before_the_command() {
+    let new = |title: &str| Command::New {
+        title: title.to_string(),
+        tags: vec![],
+        folder: None,
+    };
+
+    // after the command they are the note's title or an error, never global
+    assert_eq!(
+        parse_args(&args(&["new", "-h"])).unwrap().command,
+        new("-h")
+    );
+    assert!(parse_args(&args(&["new", "--help"])).is_err());
+    let cli = parse_args(&args(&["capture", "move", "--vault", "/v"]));
+    assert!(cli.is_err());
+
+    // `--` ends the options
+    let cli = parse_args(&args(&["new", "--", "--help", "me"])).unwrap();
+    assert_eq!(cli.command, new("--help me"));
+    let cli = parse_args(&args(&["capture", "--", "try", "--vault", "/v"])).unwrap();
+    assert_eq!(cli.vault, None);
+    assert_eq!(
+        cli.command,
+        Command::Capture {
+            text: Some("try --vault /v".to_string()),
+            to: None,
+        }
+    );
+    let cli = parse_args(&args(&["--vault", "/v", "--", "today"])).unwrap();
+    assert_eq!(cli.vault, Some(PathBuf::from("/v")));
+    assert_eq!(cli.command, Command::Today);
+}
+
+#[test]
+fn test_parse_errors() {
+    assert!(parse_args(&args(&["new"])).is_err());
+    assert!(parse_args(&args(&["list", "--folder", "x"])).is_err());
+    assert!(parse_args(&args(&["sync", "extra"])).is_err());
+    assert!(parse_args(&args(&["--vault"])).is_err());
+    assert!(parse_args(&args(&["--vault", "/v", "bogus"])).is_err());
+    // a typo is not taken for a new notebook
+    let err = parse_args(&args(&["tsks"])).unwrap_err();
+    assert!(err.to_string().contains("unknown command 'tsks'"));
+    let cli = parse_args(&args(&["notes/"])).unwrap();
+    assert_eq!(cli.vault, Some(PathBuf::from("notes/")));
+    assert!(parse_args(&args(&["config"])).is_err());
+    assert!(parse_args(&args(&["config", "edit"])).is_err());
+}
+
+#[test]
+fn test_parse_config_commands() {
+    let cli = parse_args(&args(&["config", "check"])).unwrap();
+    assert_eq!(cli.command, Command::Config(ConfigCommand::Check));
+    let cli = parse_args(&args(&["config", "path"])).unwrap();
+    assert_eq!(cli.command, Command::Config(ConfigCommand::Path));
+}
+
+#[test]
+fn test_parse_log_options() {
+    use log::LevelFilter;
+
+    let cli = parse_args(&args(&["--log-level", "debug"])).unwrap();
+    assert_eq!(cli.command, Command::Tui);
+    assert_eq!(cli.log_level, Some(LevelFilter::Debug));
+    let cli = parse_args(&args(&["--log-level=TRACE", "list"])).unwrap();
+    assert_eq!(cli.log_level, Some(LevelFilter::Trace));
+    assert_eq!(parse_args(&args(&[])).unwrap().log_level, None);
+
+    assert!(parse_args(&args(&["--log-level"])).is_err());
+    assert!(parse_args(&args(&["--log-level", "loud"])).is_err());
+
+    let cli = parse_args(&args(&["log"])).unwrap();
+    assert_eq!(cli.command, Command::Log(LogCommand::Open));
+    let cli = parse_args(&args(&["log", "path"])).unwrap();
+    assert_eq!(cli.command, Command::Log(LogCommand::Path));
+    assert!(parse_args(&args(&["log", "tail"])).is_err());
+}
+
+#[test]
+fn test_capture() {
+    use chrono::{Local, TimeZone};
+    use kiroku_tui::config::Config;
+    use kiroku_tui::ops;
+    use std::fs;
+
+    let dir = tempfile::tempdir().unwrap();
+    let now = Local.with_ymd_and_hms(2026, 10, 18, 9, 5, 0).unwrap();
+
+    let cli = parse_args(&args(&["capture", "--to", "inbox"])).unwrap();
+    assert_eq!(
+        cli.command,
+        Command::Capture {
+            text: None,
+            to: Some("inbox".to_string()),
+        }
+    );
+
+    // journal target is created with its heading
+    let config = Config::default();
+    let journal = ops::capture_path(dir.path(), &config, None, now.date_naive()).unwrap();
+    assert_eq!(journal, dir.path().join("journal/2026-10-18.md"));
+
+    let entry = ops::format_capture("- %H:%M {text}", "piped\noutput\n", now).unwrap();
+    assert_eq!(entry, "- 09:05 piped\n  output");
+    ops::append_to_note(&journal, &entry).unwrap();
+    assert_eq!(
+        fs::read_to_string(&journal).unwrap(),
+        "# 2026-10-18\n\n- 09:05 piped\n  output\n"
+    );
+
+    // a bad pattern, e.g. from a [[vaults]] entry, is an error and not a panic
+    assert!(ops::format_capture("- %Q {text}", "x", now).is_err());
+    let config = Config {
+        journal_format: Some("%Y-%Q".to_string()),
+        ..Config::default()
+    };
+    assert!(ops::journal_path(dir.path(), &config, now.date_naive()).is_err());
+    assert!(ops::capture_path(dir.path(), &config, None, now.date_naive()).is_err());
+
+    // other notes are created on demand and keep line boundaries
+    let inbox = ops::capture_path(dir.path(), &config, Some("inbox"), now.date_naive()).unwrap();
+    assert_eq!(inbox, dir.path().join("inbox.md"));
+    fs::write(&inbox, "no trailing newline").unwrap();
+    ops::append_to_note(&inbox, "- entry").unwrap();
+    assert_eq!(
+        fs::read_to_string(&inbox).unwrap(),
+        "no trailing newline\n- entry\n"
+    );
+}
+
+#[test]
+fn test_parse_attachments() {
+    let cli = parse_args(&args(&["attachments", "--orphans", "--jsonl"])).unwrap();
+    assert_eq!(
+        cli.command,
+        Command::Attachments {
+            orphans: true,
+            format: OutputFormat::JsonLines,
+        }
+    );
+    assert!(parse_args(&args(&["attachments", "extra"])).is_err());
+}
+
+#[test]
+fn test_parse_check() {
+    let cli = parse_args(&args(&["check", "--json"])).unwrap();
+    assert_eq!(
+        cli.command,
+        Command::Check {
+            format: OutputFormat::Json,
+        }
+    );
+    assert!(parse_args(&args(&["check", "--orphans"])).is_err());
+}
+
+#[test]
+fn test_parse_tasks() {
+    let cli = parse_args(&args(&["tasks"])).unwrap();
+    assert_eq!(
+        cli.command,
+        Command::Tasks {
+            filter: TaskFilter::Open,
+            format: OutputFormat::Text,
+        }
+    );
+    let cli = parse_args(&args(&["tasks", "--overdue", "--jsonl"])).unwrap();
+    assert_eq!(
+        cli.command,
+        Command::Tasks {
+            filter: TaskFilter::Overdue,
+            format: OutputFormat::JsonLines,
+        }
+    );
+    assert!(parse_args(&args(&["tasks", "--orphans"])).is_err());
+}
+
+#[test]
+fn test_new_quotes_tags() {
+    let dir = tempfile::tempdir().unwrap();
+    let command = Command::New {
+        title: "Links".to_string(),
+        tags: vec!["a:b".to_string(), "#c".to_string()],
+        folder: None,
+    };
+    run(command, dir.path(), &Config::default()).unwrap();
+
+    let path = dir.path().join("Links.md");
+    let fm = data::read_frontmatter(&path).unwrap().unwrap();
+    assert_eq!(fm["tags"][0].as_str(), Some("a:b"));
+    assert_eq!(fm["tags"][1].as_str(), Some("#c"));
+    let note = data::Note::from_path(path, dir.path()).unwrap();
+    assert_eq!(note.tags, vec!["a:b", "#c"]);
+}
@@ -0,0 +1,90 @@
+use kiroku_tui::config::{ConfigIssue, Severity, parse_config};
+
+fn fields(issues: &[ConfigIssue], severity: Severity) -> Vec<&str> {
+    issues
+        .iter()
+        .filter(|i| i.severity == severity)
+        .map(|i| i.field.as_str())
+        .collect()
+}
+
+#[test]
+fn test_type_errors_name_the_field_and_line() {
+    let (config, issues) = parse_config("auto_sync = false\n\n[theme]\naccent = 3\n");
+    assert!(config.is_none());
+    assert_eq!(issues.len(), 1);
+    assert_eq!(issues[0].severity, Severity::Error);
+    assert_eq!(issues[0].field, "theme.accent");
+    assert_eq!(issues[0].line, Some(4));
+    assert!(
+        issues[0]
+            .to_string()
+            .starts_with("error: line 4: theme.accent: ")
+    );
+
+    let (config, issues) = parse_config("sort_mode = \"Date\"\nauto_sync = \n");
+    assert!(config.is_none());
+    assert_eq!(issues[0].line, Some(2));
+}
+
+#[test]
+fn test_unknown_keys_and_invalid_values() {
+    let content = r##"
+editr_cmd = "vim"
+sort_mode = "Newest"
+sync_backend = "mirror"
+journal_format = "%Y-%Q"
+
+[theme]
+accent = "#12345g"
+colour = "#ffffff"
+
+[keys]
+sync = "S"
+fly = "x"
+
+[[saved_searches]]
+name = "Todo"
+query = "todo"
+kind = "body"
+
+[[vaults]]
+name = "work"
+path = "~/work"
+
+[[vaults]]
+name = "Work"
+path = ""
+"##;
+
+    let (config, issues) = parse_config(content);
+    let config = config.expect("values are checked after parsing");
+    // rejected values fall back to the defaults
+    assert_eq!(config.sort_mode, None);
+    assert_eq!(config.journal_format, None);
+    assert_eq!(config.sync_backend.as_deref(), Some("mirror"));
+
+    assert_eq!(
+        fields(&issues, Severity::Warning),
+        vec![
+            "editr_cmd",
+            "theme.colour",
+            "keys",
+            "saved_searches[0].kind"
+        ]
+    );
+    assert_eq!(
+        fields(&issues, Severity::Error),
+        vec![
+            "sort_mode",
+            "mirror_path",
+            "journal_format",
+            "theme.accent",
+            "vaults[1]",
+            "vaults[1]"
+        ]
+    );
+
+    let (_, issues) = parse_config("auto_sync = true\nsort_mode = \"Name\"\n");
+    assert!(issues.is_empty());
+}
@@ -0,0 +1,39 @@
+use kiroku_tui::crash::write_report;
+use std::fs;
+
+#[test]
+fn test_crash_report_contents() {
+    let dir = tempfile::tempdir().unwrap();
+    let reports = dir.path().join("crashes");
+    let lines = vec![
+        "12:00:00 INFO  kiroku: Color mode: TrueColor".to_string(),
+        "12:00:01 ERROR kiroku: Sync failed".to_string(),
+    ];
+
+    let path = write_report(
+        &reports,
+        "panicked at src/ui.rs:1:1:\nboom",
+        "0: kiroku::ui::ui",
+        Some(&lines),
+    )
+    .unwrap();
+    assert!(path.starts_with(&reports));
+    assert!(
+        path.file_name()
+            .unwrap()
+            .to_string_lossy()
+            .starts_with("crash-")
+    );
+
+    let report = fs::read_to_string(&path).unwrap();
+    assert!(report.contains(env!("CARGO_PKG_VERSION")));
+    assert!(report.contains("panicked at src/ui.rs:1:1:\nboom"));
+    assert!(report.contains("backtrace:\n0: kiroku::ui::ui"));
+    assert!(report.ends_with("recent log:\n12:00:00 INFO  kiroku: Color mode: TrueColor\n12:00:01 ERROR kiroku: Sync failed\n"));
+
+    // no log section when the lines could not be read
+    let path = write_report(&dir.path().join("busy"), "boom", "0: main", None).unwrap();
+    let report = fs::read_to_string(&path).unwrap();
+    assert!(report.ends_with("backtrace:\n0: main\n"));
+    assert!(!report.contains("recent log"));
+}
@@ -0,0 +1,93 @@
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use kiroku_tui::app::{App, InputMode};
+use kiroku_tui::config::{Config, Severity};
+use kiroku_tui::data::{self, IgnoreRules};
+use kiroku_tui::doctor;
+use std::fs;
+
+#[test]
+fn test_check_vault_finds_problems() {
+    let dir = tempfile::tempdir().unwrap();
+    let root = dir.path();
+    fs::create_dir_all(root.join("work")).unwrap();
+    fs::create_dir_all(root.join("attachments")).unwrap();
+    fs::write(root.join("attachments/chart.png"), "png").unwrap();
+    fs::write(
+        root.join("index.md"),
+        "[[plan]] and [[missing]]\n\
+         ![chart](attachments/chart.png) ![gone](attachments/gone.png)\n\
+         ![[chart.png]] [[photo.jpg]]\n\
+         [away](nowhere.md) [web](https://example.com) [self](#top)\n\
+         ```\n[[inside code]]\n```\n",
+    )
+    .unwrap();
+    fs::write(root.join("work/plan.md"), "# Plan\n[up](../index.md)\n").unwrap();
+    fs::write(root.join("bad.md"), "---\ntags: [a\n---\nbody\n").unwrap();
+    fs::write(root.join("empty.md"), "  \n").unwrap();
+    fs::write(root.join("binary.md"), [0xff, 0xfe, 0x00]).unwrap();
+    fs::write(root.join("meeting.md"), "notes\n").unwrap();
+    fs::write(root.join("standup.org"), "#+title: Meeting\ntext\n").unwrap();
+
+    let config = Config {
+        note_extensions: Some(vec!["md".into(), "org".into()]),
+        ..Config::default()
+    };
+    let ignore = IgnoreRules::from_config(root, &config);
+    let notes = data::load_notes_with(&root.to_string_lossy(), &ignore).unwrap();
+    let findings = doctor::check_vault(root, &notes, &ignore);
+    let report: Vec<String> = findings
+        .iter()
+        .map(|f| format!("{} {}", f.location(root), f))
+        .collect();
+
+    assert!(report[0].starts_with("bad.md:1 error: invalid frontmatter: "));
+    assert_eq!(
+        report[1..],
+        [
+            "binary.md error: not valid UTF-8 (bad byte at offset 0)".to_string(),
+            "index.md:1 error: broken wiki link [[missing]]".to_string(),
+            "index.md:2 error: missing attachment attachments/gone.png".to_string(),
+            "index.md:3 error: missing attachment [[photo.jpg]]".to_string(),
+            "index.md:4 error: broken link to nowhere.md".to_string(),
+            "empty.md warning: empty note".to_string(),
+            "meeting.md warning: duplicate title 'meeting' (also standup.org)".to_string(),
+            "standup.org warning: duplicate title 'Meeting' (also meeting.md)".to_string(),
+        ]
+    );
+}
+
+#[test]
+fn test_doctor_view_jumps_to_note() {
+    let dir = tempfile::tempdir().unwrap();
+    let root = dir.path();
+    fs::create_dir_all(root.join("work")).unwrap();
+    fs::write(root.join("top.md"), "fine\n").unwrap();
+    fs::write(
+        root.join("work/plan.md"),
+        "one\ntwo\nthree\nfour\n[[nowhere]]\n",
+    )
+    .unwrap();
+
+    let notes = data::load_notes(&root.to_string_lossy()).unwrap();
+    let mut app = App::new(notes, root.into(), Config::default());
+    app.refresh_fs_view();
+
+    let key = |c| KeyEvent::new(KeyCode::Char(c), KeyModifiers::NONE);
+    app.handle_input(key('!'));
+    assert!(app.input_mode == InputMode::Doctor);
+    assert_eq!(app.findings.len(), 1);
+    assert_eq!(app.findings[0].severity, Severity::Error);
+    assert_eq!(app.findings[0].line, Some(5));
+
+    app.handle_input(KeyEvent::new(KeyCode::Enter, KeyModifiers::NONE));
+    assert!(app.input_mode == InputMode::Normal);
+    assert_eq!(app.current_dir, std::path::PathBuf::from("work"));
+    assert_eq!(app.selected_path(), Some(root.join("work/plan.md")));
+    assert_eq!(app.preview_scroll, 2);
+
+    // a clean vault has nothing to show
+    fs::write(root.join("work/plan.md"), "fixed\n").unwrap();
+    app.handle_input(key('!'));
+    assert!(app.input_mode == InputMode::Normal);
+    assert_eq!(app.status_msg, "No problems found.");
+}
@@ -0,0 +1,49 @@
+use kiroku_tui::data::Note;
+use kiroku_tui::export::{NoteRecord, OutputFormat, SCHEMA_VERSION, TagRecord, write_records};
+use std::fs;
+use tempfile::tempdir;
+
+#[test]
+fn test_note_records() {
+    let dir = tempdir().unwrap();
+    let path = dir.path().join("meeting.md");
+    fs::write(&path, "---\ntags: [work]\nauthor: sam\n---\n# Meeting\n").unwrap();
+
+    let note = Note::from_path(path.clone(), dir.path()).unwrap();
+    let record = NoteRecord::from_note(&note);
+    assert_eq!(record.title, "meeting");
+    assert_eq!(record.tags, vec!["work".to_string()]);
+    assert_eq!(record.frontmatter["author"], "sam");
+    assert!(record.modified.ends_with('Z'));
+
+    // one document with a version and the records under a key
+    let mut out = Vec::new();
+    write_records(&mut out, "notes", &[record], OutputFormat::Json).unwrap();
+    let doc: serde_json::Value = serde_json::from_slice(&out).unwrap();
+    assert_eq!(doc["schema_version"], SCHEMA_VERSION);
+    assert_eq!(doc["notes"][0]["path"], path.to_string_lossy().as_ref());
+    assert_eq!(doc["notes"][0]["size"], note.size);
+
+    // one versioned object per line
+    let tags = vec![
+        TagRecord {
+            tag: "work".to_string(),
+            count: 2,
+        },
+        TagRecord {
+            tag: "home".to_string(),
+            count: 1,
+        },
+    ];
+    let mut out = Vec::new();
+    write_records(&mut out, "tags", &tags, OutputFormat::JsonLines).unwrap();
+    let lines: Vec<serde_json::Value> = String::from_utf8(out)
+        .unwrap()
+        .lines()
+        .map(|l| serde_json::from_str(l).unwrap())
+        .collect();
+    assert_eq!(lines.len(), 2);
+    assert_eq!(lines[1]["schema_version"], SCHEMA_VERSION);
+    assert_eq!(lines[1]["tag"], "home");
+    assert_eq!(lines[1]["count"], 1);
+}
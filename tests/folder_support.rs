@@ -1,3 +1,4 @@
+use kiroku_tui::config::Config;
 use kiroku_tui::data;
 use kiroku_tui::ops;
 use tempfile::tempdir;
@@ -8,7 +9,7 @@ fn test_folder_support() {
     let root = dir.path();
 
     // 1. Test create_note with folder
-    let note_path = ops::create_note(root, "work/project_a").unwrap();
+    let note_path = ops::create_note(root, "work/project_a", &Config::default()).unwrap();
     assert!(note_path.exists());
     assert!(root.join("work").exists());
     assert!(root.join("work/project_a.md").exists());
@@ -28,7 +29,7 @@ fn test_folder_support() {
     assert_eq!(note.title, "work/project_a");
 
     // 3. Test deep nesting
-    let deep_note_path = ops::create_note(root, "a/b/c/deep").unwrap();
+    let deep_note_path = ops::create_note(root, "a/b/c/deep", &Config::default()).unwrap();
     assert!(deep_note_path.exists());
     assert!(root.join("a/b/c/deep.md").exists());
 
@@ -42,13 +43,13 @@ fn test_folder_support() {
     assert_eq!(deep_note.title, "a/b/c/deep");
 
     // 4. Rename into folder
-    let new_path = ops::rename_note(&note_path, "subdir/project_b").unwrap();
+    let new_path = ops::rename_note(&note_path, "subdir/project_b", &Config::default()).unwrap();
     assert!(new_path.exists());
     assert!(!note_path.exists());
     assert!(root.join("work/subdir/project_b.md").exists());
 
     // 5. Test rename with '..' to move up
-    let moved_path = ops::rename_note(&new_path, "../../moved").unwrap();
+    let moved_path = ops::rename_note(&new_path, "../../moved", &Config::default()).unwrap();
     assert!(root.join("moved.md").exists());
     assert!(moved_path.exists());
 
@@ -0,0 +1,110 @@
+use kiroku_tui::app::App;
+use kiroku_tui::config::Config;
+use kiroku_tui::data::{self, IgnoreRules};
+use std::fs;
+use std::path::Path;
+
+fn titles(root: &Path, ignore: &IgnoreRules) -> Vec<String> {
+    let mut titles: Vec<String> = data::load_notes_with(&root.to_string_lossy(), ignore)
+        .unwrap()
+        .into_iter()
+        .map(|n| n.title)
+        .collect();
+    titles.sort();
+    titles
+}
+
+fn vault() -> tempfile::TempDir {
+    let dir = tempfile::tempdir().unwrap();
+    let root = dir.path();
+    for folder in [".git", "node_modules/pkg", "drafts", "build"] {
+        fs::create_dir_all(root.join(folder)).unwrap();
+    }
+    for file in [
+        "keep.md",
+        ".git/COMMIT_EDITMSG.md",
+        "node_modules/pkg/README.md",
+        "drafts/wip.md",
+        "drafts/ready.md",
+        "build/out.md",
+    ] {
+        fs::write(root.join(file), "").unwrap();
+    }
+    fs::write(
+        root.join(".kirokuignore"),
+        "node_modules/\ndrafts/*\n!drafts/ready.md\n",
+    )
+    .unwrap();
+    fs::write(root.join(".gitignore"), "build/\n").unwrap();
+    dir
+}
+
+#[test]
+fn test_kirokuignore_and_dot_folders() {
+    let dir = vault();
+    let root = dir.path();
+
+    let ignore = IgnoreRules::from_config(root, &Config::default());
+    assert_eq!(
+        titles(root, &ignore),
+        vec!["build/out", "drafts/ready", "keep"]
+    );
+
+    // the plain loader leaves out dot-folders too
+    let all = data::load_notes(&root.to_string_lossy()).unwrap();
+    assert!(!all.iter().any(|n| n.path.starts_with(root.join(".git"))));
+}
+
+#[test]
+fn test_gitignore_is_opt_in() {
+    let dir = vault();
+    let root = dir.path();
+
+    let config = Config {
+        respect_gitignore: Some(true),
+        ignore: Some(vec!["!build/".to_string(), "keep.md".to_string()]),
+        ..Config::default()
+    };
+    let ignore = IgnoreRules::from_config(root, &config);
+    // config patterns come last, so they can bring things back
+    assert_eq!(titles(root, &ignore), vec!["build/out", "drafts/ready"]);
+
+    let config = Config {
+        respect_gitignore: Some(true),
+        ..Config::default()
+    };
+    let ignore = IgnoreRules::from_config(root, &config);
+    assert_eq!(titles(root, &ignore), vec!["drafts/ready", "keep"]);
+
+    let items = data::load_all_items_with(&root.to_string_lossy(), &ignore).unwrap();
+    assert!(
+        !items
+            .iter()
+            .any(|i| matches!(i, data::FileSystemItem::Folder(p) if p.ends_with("build")))
+    );
+}
+
+#[test]
+fn test_editing_kirokuignore_rescans() {
+    let dir = vault();
+    let root = dir.path();
+    let config = Config::default();
+    let ignore = IgnoreRules::from_config(root, &config);
+    let notes = data::load_notes_with(&root.to_string_lossy(), &ignore).unwrap();
+    let mut app = App::new(notes, root.into(), config);
+    assert_eq!(app.all_notes.len(), 3);
+
+    fs::write(root.join(".kirokuignore"), "build/\n").unwrap();
+    assert!(app.apply_file_changes(&[root.join(".kirokuignore")]));
+    let mut titles: Vec<&str> = app.all_notes.iter().map(|n| n.title.as_str()).collect();
+    titles.sort();
+    assert_eq!(
+        titles,
+        vec![
+            "drafts/ready",
+            "drafts/wip",
+            "keep",
+            "node_modules/pkg/README"
+        ]
+    );
+}
@@ -163,7 +163,7 @@ fn test_note_from_path() -> anyhow::Result<()> {
 #[test]
 fn test_create_note_safe_filename() -> anyhow::Result<()> {
     let temp = TempDir::new()?;
-    let path = create_note(temp.path(), "My Note")?;
+    let path = create_note(temp.path(), "My Note", &Config::default())?;
     assert!(path.exists());
     assert!(path.to_str().unwrap().contains("My_Note.md"));
     Ok(())
@@ -175,7 +175,7 @@ fn test_rename_note() -> anyhow::Result<()> {
     let old_path = temp.path().join("old.md");
     fs::File::create(&old_path)?;
 
-    let new_path = rename_note(&old_path, "new name")?;
+    let new_path = rename_note(&old_path, "new name", &Config::default())?;
     assert!(!old_path.exists());
     assert!(new_path.exists());
     assert!(new_path.to_str().unwrap().contains("new_name.md"));
@@ -200,3 +200,40 @@ fn test_load_notes() -> anyhow::Result<()> {
 
     Ok(())
 }
+
+#[test]
+fn test_auto_sync_debounce() {
+    use std::time::{Duration, Instant};
+
+    let config = Config {
+        auto_commit_minutes: Some(5),
+        auto_push_minutes: Some(30),
+        ..Config::default()
+    };
+    let mut app = App::new(vec![], PathBuf::from("/tmp"), config);
+    let start = Instant::now();
+
+    // a burst of saves only moves the debounce window
+    app.mark_changed(start);
+    app.mark_changed(start + Duration::from_secs(60));
+    assert_eq!(app.poll_auto_sync(start + Duration::from_secs(300)), None);
+
+    let job = app
+        .poll_auto_sync(start + Duration::from_secs(360))
+        .expect("commit due");
+    assert!(job.commit);
+    assert!(!job.push);
+
+    // nothing else runs while a sync is in flight
+    app.mark_changed(start + Duration::from_secs(400));
+    assert_eq!(app.poll_auto_sync(start + Duration::from_secs(2000)), None);
+
+    app.finish_auto_sync(Ok("committed".to_string()));
+    assert!(app.last_sync.is_some());
+
+    let job = app
+        .poll_auto_sync(start + Duration::from_secs(2000))
+        .expect("push due");
+    assert!(job.commit);
+    assert!(job.push);
+}
@@ -0,0 +1,107 @@
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use kiroku_tui::app::{Action, App};
+use kiroku_tui::config::Config;
+use kiroku_tui::keys::{KeyAction, KeyChord, KeyLookup, KeySpec, Keymap, parse_sequence};
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+
+fn key(c: char) -> KeyEvent {
+    KeyEvent::new(KeyCode::Char(c), KeyModifiers::NONE)
+}
+
+#[test]
+fn test_parse_key_sequences() {
+    let seq = parse_sequence("ctrl+j").unwrap();
+    assert_eq!(
+        seq,
+        vec![KeyChord::new(KeyCode::Char('j'), KeyModifiers::CONTROL)]
+    );
+
+    let seq = parse_sequence("gg").unwrap();
+    assert_eq!(seq.len(), 2);
+
+    let seq = parse_sequence("ctrl+x n").unwrap();
+    assert_eq!(seq.len(), 2);
+
+    assert_eq!(
+        parse_sequence("F1").unwrap(),
+        vec![KeyChord::new(KeyCode::F(1), KeyModifiers::NONE)]
+    );
+    // shifted letters match what the terminal sends
+    assert_eq!(
+        parse_sequence("shift+y").unwrap(),
+        vec![KeyChord::new(KeyCode::Char('Y'), KeyModifiers::SHIFT)]
+    );
+    assert!(parse_sequence("ctrl+nope").is_err());
+}
+
+#[test]
+fn test_keymap_overrides_and_conflicts() {
+    let mut keys = BTreeMap::new();
+    keys.insert("top".to_string(), KeySpec::One("gg".to_string()));
+    keys.insert("bogus".to_string(), KeySpec::One("x".to_string()));
+    keys.insert(
+        "quit".to_string(),
+        KeySpec::Many(vec!["q".to_string(), "g".to_string()]),
+    );
+
+    let (keymap, warnings) = Keymap::from_config(Some(&keys));
+
+    // unknown action, top's 'gg' against quit's 'g' (earlier key wins),
+    // and sync's default 'g'
+    assert_eq!(warnings.len(), 3, "{:?}", warnings);
+    assert!(warnings.iter().any(|w| w.contains("bogus")));
+    assert!(warnings.iter().any(|w| w.contains("'gg' for top")));
+    assert!(warnings.iter().any(|w| w.contains("sync")));
+
+    assert_eq!(keymap.describe(KeyAction::Top), "unbound");
+    assert_eq!(keymap.describe(KeyAction::Sync), "unbound");
+    assert_eq!(keymap.describe(KeyAction::Quit), "q / g");
+    assert_eq!(
+        keymap.lookup(&parse_sequence("g").unwrap()),
+        KeyLookup::Action(KeyAction::Quit)
+    );
+}
+
+#[test]
+fn test_handle_input_with_sequences() {
+    let mut keys = BTreeMap::new();
+    keys.insert("top".to_string(), KeySpec::One("gg".to_string()));
+    keys.insert("sync".to_string(), KeySpec::One("S".to_string()));
+    let config = Config {
+        keys: Some(keys),
+        ..Config::default()
+    };
+
+    let dir = tempfile::tempdir().unwrap();
+    for name in ["a.md", "b.md", "c.md"] {
+        std::fs::write(dir.path().join(name), "").unwrap();
+    }
+    let mut app = App::new(vec![], dir.path().to_path_buf(), config);
+    assert_eq!(app.fs_items.len(), 3);
+
+    app.handle_input(key('j'));
+    app.handle_input(key('j'));
+    assert_eq!(app.list_state.selected(), Some(2));
+
+    assert!(matches!(app.handle_input(key('g')), Action::None));
+    assert_eq!(app.list_state.selected(), Some(2));
+    app.handle_input(key('g'));
+    assert_eq!(app.list_state.selected(), Some(0));
+
+    // an interrupted sequence falls back to the last key
+    app.handle_input(key('g'));
+    app.handle_input(key('j'));
+    assert_eq!(app.list_state.selected(), Some(1));
+
+    assert!(matches!(app.handle_input(key('S')), Action::Sync));
+    assert!(app.help_hint().contains("F1"));
+
+    let mut default_app = App::new(vec![], PathBuf::from("/tmp"), Config::default());
+    assert!(matches!(
+        default_app.keymap.lookup(&parse_sequence("g").unwrap()),
+        KeyLookup::Action(KeyAction::Sync)
+    ));
+    let ctrl_z = KeyEvent::new(KeyCode::Char('z'), KeyModifiers::CONTROL);
+    assert!(matches!(default_app.handle_input(ctrl_z), Action::Suspend));
+}
@@ -0,0 +1,74 @@
+use kiroku_tui::app::{App, LIST_WIDTH_MAX, LIST_WIDTH_MIN};
+use kiroku_tui::config::Config;
+use kiroku_tui::keys::KeyAction;
+use kiroku_tui::state::State;
+use kiroku_tui::ui::pane_areas;
+use ratatui::layout::Rect;
+
+#[test]
+fn test_list_pane_resizes_within_bounds() {
+    let mut app = App::new(vec![], "/tmp".into(), Config::default());
+    let wide = Rect::new(0, 0, 100, 30);
+
+    let (list, preview) = pane_areas(&app, wide);
+    assert_eq!(list.unwrap().width, 30);
+    assert_eq!(preview.unwrap().width, 70);
+
+    app.perform(KeyAction::GrowList);
+    assert_eq!(pane_areas(&app, wide).0.unwrap().width, 35);
+
+    for _ in 0..20 {
+        app.perform(KeyAction::GrowList);
+    }
+    assert_eq!(app.list_width, LIST_WIDTH_MAX);
+    for _ in 0..20 {
+        app.perform(KeyAction::ShrinkList);
+    }
+    assert_eq!(app.list_width, LIST_WIDTH_MIN);
+}
+
+#[test]
+fn test_preview_toggle_zen_and_stacking() {
+    let mut app = App::new(vec![], "/tmp".into(), Config::default());
+    let wide = Rect::new(0, 0, 100, 30);
+
+    // narrow terminals stack the list above the preview
+    let (list, preview) = pane_areas(&app, Rect::new(0, 0, 60, 40));
+    let (list, preview) = (list.unwrap(), preview.unwrap());
+    assert_eq!(list.width, 60);
+    assert_eq!(list.height, 12);
+    assert_eq!(preview.y, list.bottom());
+
+    app.perform(KeyAction::TogglePreview);
+    assert_eq!(pane_areas(&app, wide), (Some(wide), None));
+
+    // zen shows the preview alone, even if it was off
+    app.perform(KeyAction::ZenMode);
+    assert_eq!(pane_areas(&app, wide), (None, Some(wide)));
+
+    app.perform(KeyAction::ZenMode);
+    assert!(pane_areas(&app, wide).0.is_some());
+    assert!(pane_areas(&app, wide).1.is_some());
+}
+
+#[test]
+fn test_layout_is_remembered() {
+    let dir = tempfile::tempdir().unwrap();
+    let state_file = dir.path().join("state.toml");
+
+    let mut app = App::new(vec![], dir.path().into(), Config::default());
+    app.state_file = Some(state_file.clone());
+    app.perform(KeyAction::ShrinkList);
+    app.perform(KeyAction::TogglePreview);
+
+    let state = State::load(&state_file);
+    assert_eq!(state.layout.list_width, Some(25));
+    assert_eq!(state.layout.preview, Some(false));
+
+    let mut app = App::new(vec![], dir.path().into(), Config::default());
+    app.state_file = Some(state_file);
+    app.restore_state();
+    assert_eq!(app.list_width, 25);
+    assert!(!app.show_preview);
+    assert!(!app.zen);
+}
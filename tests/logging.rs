@@ -0,0 +1,40 @@
+use kiroku_tui::logging::{KEPT_LOGS, resolve_level, rotate};
+use log::LevelFilter;
+use std::fs;
+
+#[test]
+fn test_resolve_level() {
+    assert_eq!(resolve_level(None, None).unwrap(), LevelFilter::Info);
+    assert_eq!(resolve_level(None, Some("")).unwrap(), LevelFilter::Info);
+    assert_eq!(
+        resolve_level(None, Some("warn")).unwrap(),
+        LevelFilter::Warn
+    );
+    // the flag beats the environment
+    assert_eq!(
+        resolve_level(Some(LevelFilter::Trace), Some("error")).unwrap(),
+        LevelFilter::Trace
+    );
+    assert!(resolve_level(None, Some("chatty")).is_err());
+}
+
+#[test]
+fn test_rotate_keeps_newest_files() {
+    let dir = tempfile::tempdir().unwrap();
+    let log = dir.path().join("kiroku.log");
+    let numbered = |n: usize| dir.path().join(format!("kiroku.log.{}", n));
+
+    // write and rotate one more time than files are kept
+    for i in 0..=KEPT_LOGS {
+        fs::write(&log, format!("run {}", i)).unwrap();
+        rotate(&log).unwrap();
+    }
+
+    assert!(!log.exists());
+    assert_eq!(
+        fs::read_to_string(numbered(1)).unwrap(),
+        format!("run {}", KEPT_LOGS)
+    );
+    assert_eq!(fs::read_to_string(numbered(KEPT_LOGS)).unwrap(), "run 1");
+    assert!(!numbered(KEPT_LOGS + 1).exists());
+}
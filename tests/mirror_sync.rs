@@ -0,0 +1,107 @@
+use kiroku_tui::sync::mirror_sync;
+use std::fs;
+use tempfile::tempdir;
+
+#[test]
+fn test_mirror_sync() {
+    let vault = tempdir().unwrap();
+    let mirror = tempdir().unwrap();
+    let state = tempdir().unwrap();
+    let manifest = state.path().join("manifest.toml");
+
+    // 1. New files flow both ways, hidden folders are skipped
+    fs::write(vault.path().join("local.md"), "local").unwrap();
+    fs::create_dir_all(vault.path().join(".git")).unwrap();
+    fs::write(vault.path().join(".git/HEAD"), "ref").unwrap();
+    fs::create_dir_all(mirror.path().join("work")).unwrap();
+    fs::write(mirror.path().join("work/remote.md"), "remote").unwrap();
+
+    let report = mirror_sync(vault.path(), mirror.path(), &manifest).unwrap();
+    assert_eq!(report.pushed, 1);
+    assert_eq!(report.pulled, 1);
+    assert!(mirror.path().join("local.md").exists());
+    assert!(!mirror.path().join(".git").exists());
+    assert_eq!(
+        fs::read_to_string(vault.path().join("work/remote.md")).unwrap(),
+        "remote"
+    );
+
+    // 2. Second run is a no-op
+    let report = mirror_sync(vault.path(), mirror.path(), &manifest).unwrap();
+    assert_eq!(report.summary(), "already up to date");
+
+    // 3. Deletes propagate
+    fs::remove_file(vault.path().join("local.md")).unwrap();
+    let report = mirror_sync(vault.path(), mirror.path(), &manifest).unwrap();
+    assert_eq!(report.deleted, 1);
+    assert!(!mirror.path().join("local.md").exists());
+
+    // 4. Edits on both sides are reported and left alone
+    fs::write(vault.path().join("work/remote.md"), "edited here").unwrap();
+    fs::write(mirror.path().join("work/remote.md"), "edited there").unwrap();
+    let report = mirror_sync(vault.path(), mirror.path(), &manifest).unwrap();
+    assert_eq!(report.conflicts, vec!["work/remote.md".to_string()]);
+    assert_eq!(
+        fs::read_to_string(vault.path().join("work/remote.md")).unwrap(),
+        "edited here"
+    );
+    assert_eq!(
+        fs::read_to_string(mirror.path().join("work/remote.md")).unwrap(),
+        "edited there"
+    );
+}
+
+#[test]
+fn test_mirror_sync_keeps_notes_when_target_disappears() {
+    let vault = tempdir().unwrap();
+    let mirror = tempdir().unwrap();
+    let state = tempdir().unwrap();
+    let manifest = state.path().join("manifest.toml");
+    let target = mirror.path().join("drive");
+    fs::write(vault.path().join("a.md"), "a").unwrap();
+    fs::create_dir_all(vault.path().join("work")).unwrap();
+    fs::write(vault.path().join("work/b.md"), "b").unwrap();
+
+    mirror_sync(vault.path(), &target, &manifest).unwrap();
+    assert!(target.join("work/b.md").exists());
+
+    // an emptied mount point
+    fs::remove_dir_all(&target).unwrap();
+    fs::create_dir_all(&target).unwrap();
+    assert!(mirror_sync(vault.path(), &target, &manifest).is_err());
+    assert!(vault.path().join("a.md").exists());
+    assert!(vault.path().join("work/b.md").exists());
+
+    // an unmounted one is not recreated
+    fs::remove_dir_all(&target).unwrap();
+    assert!(mirror_sync(vault.path(), &target, &manifest).is_err());
+    assert!(!target.exists());
+    assert!(vault.path().join("a.md").exists());
+    assert!(vault.path().join("work/b.md").exists());
+}
+
+#[test]
+fn test_mirror_sync_keeps_mirror_when_vault_is_empty() {
+    let vault = tempdir().unwrap();
+    let mirror = tempdir().unwrap();
+    let state = tempdir().unwrap();
+    let manifest = state.path().join("manifest.toml");
+    fs::write(vault.path().join("a.md"), "a").unwrap();
+    fs::create_dir_all(vault.path().join("work")).unwrap();
+    fs::write(vault.path().join("work/b.md"), "b").unwrap();
+
+    mirror_sync(vault.path(), mirror.path(), &manifest).unwrap();
+    assert!(mirror.path().join("work/b.md").exists());
+
+    // a notes folder that was replaced with an empty one
+    fs::remove_dir_all(vault.path()).unwrap();
+    fs::create_dir_all(vault.path()).unwrap();
+    assert!(mirror_sync(vault.path(), mirror.path(), &manifest).is_err());
+    assert!(mirror.path().join("a.md").exists());
+    assert!(mirror.path().join("work/b.md").exists());
+
+    // or one that is not there at all
+    fs::remove_dir_all(vault.path()).unwrap();
+    assert!(mirror_sync(vault.path(), mirror.path(), &manifest).is_err());
+    assert!(mirror.path().join("a.md").exists());
+}
@@ -0,0 +1,123 @@
+use crossterm::event::{KeyModifiers, MouseButton, MouseEvent, MouseEventKind};
+use kiroku_tui::app::{Action, App};
+use kiroku_tui::config::Config;
+use kiroku_tui::data::{self, find_links};
+use ratatui::{Terminal, backend::TestBackend};
+use std::fs;
+use std::time::{Duration, Instant};
+
+fn mouse(kind: MouseEventKind, column: u16, row: u16) -> MouseEvent {
+    MouseEvent {
+        kind,
+        column,
+        row,
+        modifiers: KeyModifiers::NONE,
+    }
+}
+
+fn click(column: u16, row: u16) -> MouseEvent {
+    mouse(MouseEventKind::Down(MouseButton::Left), column, row)
+}
+
+// app over a small notebook, drawn once so it knows its panes
+fn drawn_app(dir: &std::path::Path) -> (App, Terminal<TestBackend>) {
+    fs::create_dir_all(dir.join("projects")).unwrap();
+    fs::write(
+        dir.join("links.md"),
+        "# Links\nsee [[Other]] and [site](https://example.com) here\n",
+    )
+    .unwrap();
+    fs::write(dir.join("other.md"), "other").unwrap();
+
+    let notes = data::load_notes(&dir.to_string_lossy()).unwrap();
+    let mut app = App::new(notes, dir.into(), Config::default());
+    let mut terminal = Terminal::new(TestBackend::new(100, 20)).unwrap();
+    terminal.draw(|f| kiroku_tui::ui::ui(f, &mut app)).unwrap();
+    (app, terminal)
+}
+
+#[test]
+fn test_click_selects_and_double_click_opens() {
+    let dir = tempfile::tempdir().unwrap();
+    let (mut app, _) = drawn_app(dir.path());
+    // rows below the border: projects/, links, other
+    assert_eq!(app.list_state.selected(), Some(0));
+
+    let now = Instant::now();
+    app.handle_mouse(click(5, 2), now);
+    assert_eq!(app.list_state.selected(), Some(1));
+
+    // clicks on the border do nothing
+    app.handle_mouse(click(5, 0), now);
+    assert_eq!(app.list_state.selected(), Some(1));
+
+    // a slow second click is just another click
+    let action = app.handle_mouse(click(5, 1), now);
+    assert!(matches!(action, Action::None));
+    let action = app.handle_mouse(click(5, 1), now + Duration::from_secs(2));
+    assert!(matches!(action, Action::None));
+    assert_eq!(app.current_dir, std::path::PathBuf::new());
+
+    // double-clicking the folder enters it
+    app.handle_mouse(click(5, 1), now + Duration::from_millis(2100));
+    assert_eq!(app.current_dir, std::path::PathBuf::from("projects"));
+}
+
+#[test]
+fn test_scroll_wheel_moves_list_and_preview() {
+    let dir = tempfile::tempdir().unwrap();
+    let (mut app, _) = drawn_app(dir.path());
+    let now = Instant::now();
+
+    app.handle_mouse(mouse(MouseEventKind::ScrollDown, 5, 5), now);
+    app.handle_mouse(mouse(MouseEventKind::ScrollDown, 5, 5), now);
+    app.handle_mouse(mouse(MouseEventKind::ScrollDown, 5, 5), now);
+    // stops at the end instead of wrapping
+    assert_eq!(app.list_state.selected(), Some(2));
+
+    app.handle_mouse(mouse(MouseEventKind::ScrollDown, 50, 5), now);
+    assert_eq!(app.preview_scroll, 3);
+    app.handle_mouse(mouse(MouseEventKind::ScrollUp, 50, 5), now);
+    assert_eq!(app.preview_scroll, 0);
+    assert_eq!(app.list_state.selected(), Some(2));
+}
+
+#[test]
+fn test_clicking_preview_links() {
+    let dir = tempfile::tempdir().unwrap();
+    let (mut app, mut terminal) = drawn_app(dir.path());
+    let now = Instant::now();
+
+    // select links.md and draw its preview
+    app.handle_mouse(click(5, 2), now);
+    terminal.draw(|f| kiroku_tui::ui::ui(f, &mut app)).unwrap();
+    let preview = app.preview_area.unwrap();
+    let (x, y) = (preview.x + 1, preview.y + 2);
+
+    // "see [[Other]] and [site](https://example.com) here"
+    match app.handle_mouse(click(x + 6, y), now) {
+        Action::OpenNote(path) => assert_eq!(path, dir.path().join("other.md")),
+        _ => panic!("expected the wiki link to open a note"),
+    }
+    match app.handle_mouse(click(x + 20, y), now) {
+        Action::OpenUrl(url) => assert_eq!(url, "https://example.com"),
+        _ => panic!("expected the link to open a url"),
+    }
+    assert!(matches!(
+        app.handle_mouse(click(x + 1, y), now),
+        Action::None
+    ));
+}
+
+#[test]
+fn test_find_links() {
+    let links =
+        find_links("a [[Note#Part|label]] `[[code]]` [b](<my file.md> \"t\") [c](x%20y.md)");
+    let targets: Vec<(&str, bool)> = links.iter().map(|l| (l.target.as_str(), l.wiki)).collect();
+    assert_eq!(
+        targets,
+        vec![("Note", true), ("my file.md", false), ("x y.md", false)]
+    );
+    assert_eq!(links[0].start, 2);
+    assert_eq!(links[0].end, 21);
+}
@@ -0,0 +1,147 @@
+use kiroku_tui::config::Config;
+use kiroku_tui::data::{self, IgnoreRules, NoteFormat};
+use kiroku_tui::ops;
+use std::fs;
+
+fn config(extensions: &[&str], default: Option<&str>) -> Config {
+    Config {
+        note_extensions: Some(extensions.iter().map(|e| e.to_string()).collect()),
+        default_extension: default.map(String::from),
+        ..Config::default()
+    }
+}
+
+#[test]
+fn test_configured_extensions_are_loaded() {
+    let dir = tempfile::tempdir().unwrap();
+    let root = dir.path();
+    for file in [
+        "a.md",
+        "b.markdown",
+        "c.txt",
+        "d.org",
+        "e.adoc",
+        "f.pdf",
+        "G.MD",
+    ] {
+        fs::write(root.join(file), "").unwrap();
+    }
+
+    let load = |config: &Config| {
+        let ignore = IgnoreRules::from_config(root, config);
+        let mut titles: Vec<String> = data::load_notes_with(&root.to_string_lossy(), &ignore)
+            .unwrap()
+            .into_iter()
+            .map(|n| n.title)
+            .collect();
+        titles.sort();
+        titles
+    };
+
+    assert_eq!(load(&Config::default()), vec!["G", "a"]);
+    assert_eq!(
+        load(&config(&[".md", "markdown", "TXT", "org", "adoc"], None)),
+        vec!["G", "a", "b", "c", "d", "e"]
+    );
+}
+
+#[test]
+fn test_titles_and_tags_per_format() {
+    let dir = tempfile::tempdir().unwrap();
+    let root = dir.path();
+    fs::create_dir_all(root.join("work")).unwrap();
+    fs::write(
+        root.join("work/plan.org"),
+        "#+TITLE: Quarterly Plan\n#+filetags: :work:planning:\n\n* Goals\n",
+    )
+    .unwrap();
+    fs::write(
+        root.join("guide.adoc"),
+        "= Install Guide\n:author: me\n:tags: docs, setup\n\n== Steps\n",
+    )
+    .unwrap();
+    fs::write(root.join("log.txt"), "---\ntags: [daily]\n---\nplain\n").unwrap();
+    fs::write(root.join("bare.org"), "* Just a heading\n").unwrap();
+
+    let note = |name: &str| data::Note::from_path(root.join(name), root).unwrap();
+
+    let plan = note("work/plan.org");
+    assert_eq!(plan.title, "work/Quarterly Plan");
+    assert_eq!(plan.tags, vec!["work", "planning"]);
+
+    let guide = note("guide.adoc");
+    assert_eq!(guide.title, "Install Guide");
+    assert_eq!(guide.tags, vec!["docs", "setup"]);
+
+    let log = note("log.txt");
+    assert_eq!(log.title, "log");
+    assert_eq!(log.tags, vec!["daily"]);
+
+    assert_eq!(note("bare.org").title, "bare");
+
+    // wiki links may name the file with its extension
+    let notes = vec![plan, guide, log];
+    let found = data::resolve_wiki_link(&notes, "plan.org").unwrap();
+    assert_eq!(found.path, root.join("work/plan.org"));
+}
+
+#[test]
+fn test_preview_markup_per_format() {
+    assert_eq!(NoteFormat::Markdown.heading_level("## Notes"), Some(2));
+    assert_eq!(NoteFormat::Markdown.heading_level("#tag"), None);
+    assert_eq!(NoteFormat::Org.heading_level("*** Deep"), Some(3));
+    assert_eq!(NoteFormat::AsciiDoc.heading_level("= Title"), Some(1));
+    assert_eq!(NoteFormat::Text.heading_level("# not a heading"), None);
+
+    assert!(NoteFormat::Markdown.is_fence("```rust"));
+    assert!(NoteFormat::Org.is_fence("#+BEGIN_SRC sh"));
+    assert!(NoteFormat::AsciiDoc.is_fence("----"));
+    assert!(!NoteFormat::Text.is_fence("```"));
+
+    assert_eq!(
+        NoteFormat::from_path(std::path::Path::new("a.markdown")),
+        NoteFormat::Markdown
+    );
+    assert_eq!(
+        NoteFormat::from_path(std::path::Path::new("a.rst")),
+        NoteFormat::Text
+    );
+}
+
+#[test]
+fn test_new_notes_use_default_extension() {
+    let dir = tempfile::tempdir().unwrap();
+    let root = dir.path();
+    let config = config(&["md", "org"], Some(".org"));
+
+    let path = ops::create_note(root, "Weekly Review", &config).unwrap();
+    assert_eq!(path, root.join("Weekly_Review.org"));
+    // an explicit note extension is kept
+    let path = ops::create_note(root, "readme.md", &config).unwrap();
+    assert_eq!(path, root.join("readme.md"));
+
+    let date = chrono::NaiveDate::from_ymd_opt(2026, 10, 18).unwrap();
+    assert_eq!(
+        ops::journal_path(root, &config, date).unwrap(),
+        root.join("journal/2026-10-18.org")
+    );
+
+    // renaming keeps the note's own extension
+    let renamed = ops::rename_note(&root.join("Weekly_Review.org"), "review", &config).unwrap();
+    assert_eq!(renamed, root.join("review.org"));
+    // unless a note extension is typed
+    fs::write(root.join("idea.txt"), "").unwrap();
+    let renamed = ops::rename_note(&root.join("idea.txt"), "idea.md", &config).unwrap();
+    assert_eq!(renamed, root.join("idea.md"));
+    // a dot in the name is not an extension
+    let renamed = ops::rename_note(&root.join("review.org"), "v1.2", &config).unwrap();
+    assert_eq!(renamed, root.join("v1.2.org"));
+
+    // the first configured extension is the fallback
+    assert_eq!(default_for(&["txt", "md"]), "txt");
+    assert_eq!(Config::default().default_extension(), "md");
+}
+
+fn default_for(extensions: &[&str]) -> String {
+    config(extensions, None).default_extension()
+}
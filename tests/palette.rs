@@ -0,0 +1,73 @@
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use kiroku_tui::app::{Action, App, InputMode, PaletteItem};
+use kiroku_tui::config::{Config, SavedSearch};
+use kiroku_tui::data;
+use kiroku_tui::keys::KeyAction;
+
+fn key(c: char) -> KeyEvent {
+    KeyEvent::new(KeyCode::Char(c), KeyModifiers::NONE)
+}
+
+fn typed(app: &mut App, text: &str) {
+    for c in text.chars() {
+        app.handle_input(key(c));
+    }
+}
+
+#[test]
+fn test_palette_actions_searches_and_recent_notes() {
+    let dir = tempfile::tempdir().unwrap();
+    std::fs::write(dir.path().join("groceries.md"), "milk").unwrap();
+    std::fs::write(
+        dir.path().join("standup.md"),
+        "---\ntags: [work]\n---\nnotes",
+    )
+    .unwrap();
+
+    let config = Config {
+        saved_searches: Some(vec![SavedSearch {
+            name: "Work notes".to_string(),
+            query: "work".to_string(),
+            kind: Some("tag".to_string()),
+        }]),
+        ..Config::default()
+    };
+    let notes = data::load_notes(&dir.path().to_string_lossy()).unwrap();
+    let mut app = App::new(notes, dir.path().to_path_buf(), config);
+
+    // actions are listed with their current binding
+    app.handle_input(key(':'));
+    assert!(app.input_mode == InputMode::Palette);
+    typed(&mut app, "cycle theme");
+    let first = &app.palette_entries[0];
+    assert!(matches!(
+        first.item,
+        PaletteItem::Action(KeyAction::CycleTheme)
+    ));
+    assert_eq!(first.detail, "t");
+    app.handle_input(KeyEvent::new(KeyCode::Esc, KeyModifiers::NONE));
+    assert!(app.input_mode == InputMode::Normal);
+
+    // saved searches apply their query
+    app.handle_input(key(':'));
+    typed(&mut app, "work notes");
+    app.handle_input(KeyEvent::new(KeyCode::Enter, KeyModifiers::NONE));
+    assert!(app.input_mode == InputMode::Normal);
+    assert_eq!(app.search_query, "work");
+    assert_eq!(app.notes.len(), 1);
+    assert_eq!(app.notes[0].title, "standup");
+
+    // recently opened notes come first and open directly
+    let path = dir.path().join("groceries.md");
+    app.remember_recent(&path);
+    app.handle_input(KeyEvent::new(KeyCode::Char('p'), KeyModifiers::CONTROL));
+    assert!(app.input_mode == InputMode::Palette);
+    assert!(matches!(
+        app.palette_entries[0].item,
+        PaletteItem::RecentNote(_)
+    ));
+    match app.handle_input(KeyEvent::new(KeyCode::Enter, KeyModifiers::NONE)) {
+        Action::OpenNote(p) => assert_eq!(p, path),
+        _ => panic!("expected the recent note to open"),
+    }
+}
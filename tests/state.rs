@@ -0,0 +1,60 @@
+use kiroku_tui::app::{App, SortMode};
+use kiroku_tui::config::{Config, Theme};
+use kiroku_tui::state::State;
+use std::fs;
+
+#[test]
+fn test_ui_state_round_trip() {
+    let dir = tempfile::tempdir().unwrap();
+    let vault = dir.path().join("vault");
+    fs::create_dir_all(vault.join("projects")).unwrap();
+    fs::write(vault.join("projects/plan.md"), "").unwrap();
+    let state_file = dir.path().join("data/state.toml");
+
+    let mut app = App::new(vec![], vault.clone(), Config::default());
+    app.state_file = Some(state_file.clone());
+    app.vault_name = Some("work".to_string());
+    app.current_dir = "projects".into();
+    app.sort_mode = SortMode::Size;
+    app.cycle_theme();
+    assert_eq!(app.theme_name, "gruvbox");
+
+    let state = State::load(&state_file);
+    assert_eq!(state.theme.as_deref(), Some("gruvbox"));
+    assert_eq!(state.last_vault.as_deref(), Some("work"));
+    let saved = state.vault(&vault).unwrap();
+    assert_eq!(saved.sort_mode.as_deref(), Some("Size"));
+    assert_eq!(saved.folder.as_deref(), Some("projects"));
+
+    // a fresh session picks up where the last one stopped
+    let mut app = App::new(vec![], vault.clone(), Config::default());
+    app.state_file = Some(state_file);
+    app.restore_state();
+    assert_eq!(app.theme_name, "gruvbox");
+    assert!(app.sort_mode == SortMode::Size);
+    assert_eq!(app.current_dir, std::path::PathBuf::from("projects"));
+    assert_eq!(app.fs_items.len(), 1);
+}
+
+#[test]
+fn test_theme_cycle_includes_config_theme() {
+    let config = Config {
+        theme: Some(Theme {
+            accent: Some("#ff0000".to_string()),
+            ..Theme::default()
+        }),
+        ..Config::default()
+    };
+    let mut app = App::new(vec![], "/tmp".into(), config);
+    assert_eq!(app.theme_name, "custom");
+
+    let mut seen = vec![app.theme_name.clone()];
+    for _ in 0..4 {
+        app.cycle_theme();
+        seen.push(app.theme_name.clone());
+    }
+    assert_eq!(
+        seen,
+        vec!["custom", "default", "gruvbox", "tokyo-night", "custom"]
+    );
+}
@@ -0,0 +1,169 @@
+use chrono::NaiveDate;
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use kiroku_tui::app::{App, InputMode};
+use kiroku_tui::config::Config;
+use kiroku_tui::data;
+use kiroku_tui::tasks::{self, TaskFilter};
+use std::fs;
+use std::path::Path;
+
+#[test]
+fn test_parse_tasks_with_headings_and_due_dates() {
+    let content = "\
+- [ ] before any heading
+# Work
+- [ ] ship it @due(2026-10-20) today
+  * [x] nested and done
++ [X] plus bullet 📅 2026-09-01
+## Later
+- [] not a task
+- [ ] bad date @due(soon)
+```
+- [ ] inside code
+```
+";
+    let path = Path::new("/vault/plan.md");
+    let tasks = tasks::parse_tasks(path, content);
+    let summary: Vec<_> = tasks
+        .iter()
+        .map(|t| (t.line, t.text.as_str(), t.done, t.heading.as_deref(), t.due))
+        .collect();
+
+    let date = |s| NaiveDate::parse_from_str(s, "%Y-%m-%d").ok();
+    assert_eq!(
+        summary,
+        vec![
+            (1, "before any heading", false, None, None),
+            (3, "ship it today", false, Some("Work"), date("2026-10-20")),
+            (4, "nested and done", true, Some("Work"), None),
+            (5, "plus bullet", true, Some("Work"), date("2026-09-01")),
+            (8, "bad date @due(soon)", false, Some("Later"), None),
+        ]
+    );
+
+    // org headings give the context there
+    let org = tasks::parse_tasks(Path::new("/vault/plan.org"), "* Errands\n- [ ] milk\n");
+    assert_eq!(org[0].heading.as_deref(), Some("Errands"));
+}
+
+#[test]
+fn test_task_filters() {
+    let tasks = tasks::parse_tasks(
+        Path::new("/vault/a.md"),
+        "- [ ] late @due(2026-01-01)\n- [ ] later @due(2027-01-01)\n- [x] done @due(2026-01-01)\n",
+    );
+    let today = NaiveDate::from_ymd_opt(2026, 6, 1).unwrap();
+    let count = |filter: TaskFilter| tasks.iter().filter(|t| filter.matches(t, today)).count();
+
+    assert_eq!(count(TaskFilter::Open), 2);
+    assert_eq!(count(TaskFilter::Overdue), 1);
+    assert_eq!(count(TaskFilter::Done), 1);
+    assert_eq!(count(TaskFilter::All), 3);
+    assert_eq!(TaskFilter::All.next(), TaskFilter::Open);
+}
+
+#[test]
+fn test_toggle_writes_back() {
+    let dir = tempfile::tempdir().unwrap();
+    let note = dir.path().join("todo.md");
+    fs::write(&note, "# Todo\r\n- [ ] one\r\n  - [x] two\r\n").unwrap();
+
+    let mut found = tasks::parse_tasks(&note, &fs::read_to_string(&note).unwrap());
+    let stale = found[0].clone();
+    assert!(tasks::toggle(&mut found[0]).unwrap());
+    assert!(!tasks::toggle(&mut found[1]).unwrap());
+    assert_eq!(
+        fs::read_to_string(&note).unwrap(),
+        "# Todo\r\n- [x] one\r\n  - [ ] two\r\n"
+    );
+    // the task follows its own writes
+    assert!(!tasks::toggle(&mut found[0]).unwrap());
+    assert!(tasks::toggle(&mut found[0]).unwrap());
+
+    // a stale task is refused rather than flipping the wrong line
+    assert!(tasks::toggle(&mut stale.clone()).is_err());
+    fs::write(&note, "rewritten\n").unwrap();
+    assert!(tasks::toggle(&mut found[1]).is_err());
+}
+
+#[test]
+fn test_toggle_after_the_file_was_edited() {
+    let dir = tempfile::tempdir().unwrap();
+    let note = dir.path().join("todo.md");
+    fs::write(&note, "- [ ] one\n- [ ] two\n- [ ] three\n").unwrap();
+    let mut found = tasks::collect(&data::load_notes(&dir.path().to_string_lossy()).unwrap());
+
+    // a line added above moves every task down
+    fs::write(&note, "# Todo\n- [ ] one\n- [ ] two\n- [ ] three\n").unwrap();
+    assert!(tasks::toggle(&mut found[1]).unwrap());
+    assert_eq!(found[1].line, 3);
+    assert_eq!(
+        fs::read_to_string(&note).unwrap(),
+        "# Todo\n- [ ] one\n- [x] two\n- [ ] three\n"
+    );
+
+    // a removed line moves them up
+    fs::write(&note, "- [ ] one\n- [x] two\n- [ ] three\n").unwrap();
+    assert!(tasks::toggle(&mut found[2]).unwrap());
+    assert_eq!(
+        fs::read_to_string(&note).unwrap(),
+        "- [ ] one\n- [x] two\n- [x] three\n"
+    );
+
+    // an edited task is not found again
+    fs::write(&note, "- [ ] one, reworded\n- [x] two\n- [x] three\n").unwrap();
+    assert!(tasks::toggle(&mut found[0]).is_err());
+
+    // nor is one that now appears twice
+    let twice = "# Todo\n- [ ] one\n- [ ] one\n";
+    fs::write(&note, twice).unwrap();
+    assert!(tasks::toggle(&mut found[0]).is_err());
+    assert_eq!(fs::read_to_string(&note).unwrap(), twice);
+}
+
+#[test]
+fn test_tasks_view() {
+    let dir = tempfile::tempdir().unwrap();
+    let root = dir.path();
+    fs::create_dir_all(root.join("work")).unwrap();
+    fs::write(root.join("a.md"), "- [x] finished\n").unwrap();
+    fs::write(
+        root.join("work/plan.md"),
+        "# Plan\n\n\n\n- [ ] write report @due(2000-01-01)\n",
+    )
+    .unwrap();
+
+    let notes = data::load_notes(&root.to_string_lossy()).unwrap();
+    let mut app = App::new(notes, root.into(), Config::default());
+    app.refresh_fs_view();
+    let key = |code| KeyEvent::new(code, KeyModifiers::NONE);
+
+    app.handle_input(key(KeyCode::Char('T')));
+    assert!(app.input_mode == InputMode::Tasks);
+    assert_eq!(app.tasks.len(), 2);
+    assert_eq!(app.visible_tasks().len(), 1);
+
+    // open -> overdue keeps the late task
+    app.handle_input(key(KeyCode::Tab));
+    assert_eq!(app.task_filter, TaskFilter::Overdue);
+    assert_eq!(app.visible_tasks().len(), 1);
+
+    // ticking it takes it off the overdue list
+    app.handle_input(key(KeyCode::Char(' ')));
+    assert!(
+        fs::read_to_string(root.join("work/plan.md"))
+            .unwrap()
+            .contains("- [x] write report")
+    );
+    assert!(app.visible_tasks().is_empty());
+    assert_eq!(app.tasks_state.selected(), None);
+
+    // done shows both, and Enter jumps to the note
+    app.handle_input(key(KeyCode::Tab));
+    assert_eq!(app.visible_tasks().len(), 2);
+    app.handle_input(key(KeyCode::Char('j')));
+    app.handle_input(key(KeyCode::Enter));
+    assert!(app.input_mode == InputMode::Normal);
+    assert_eq!(app.selected_path(), Some(root.join("work/plan.md")));
+    assert_eq!(app.preview_scroll, 2);
+}
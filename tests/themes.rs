@@ -0,0 +1,143 @@
+use kiroku_tui::app::App;
+use kiroku_tui::config::{Config, Theme, parse_config};
+use kiroku_tui::theme::{ColorMode, ThemeColors, load_theme_files, parse_color};
+use ratatui::style::{Color, Modifier, Style};
+use std::fs;
+
+#[test]
+fn test_parse_colors() {
+    assert_eq!(parse_color("#89dceb"), Some(Color::Rgb(137, 220, 235)));
+    assert_eq!(parse_color("yellow"), Some(Color::Yellow));
+    assert_eq!(parse_color("reset"), Some(Color::Reset));
+    assert_eq!(parse_color("#12345"), None);
+    assert_eq!(parse_color("#12345g"), None);
+    assert_eq!(parse_color("blurple"), None);
+    // six bytes, but not six hex digits
+    assert_eq!(parse_color("#ééé"), None);
+}
+
+#[test]
+fn test_theme_slots_fall_back_to_base() {
+    let theme = Theme {
+        base: Some("gruvbox".to_string()),
+        accent: Some("#010203".to_string()),
+        quote: Some("green".to_string()),
+        ..Theme::default()
+    };
+    let colors = ThemeColors::from_config(&theme);
+    let gruvbox = ThemeColors::builtin("gruvbox").unwrap();
+
+    assert_eq!(colors.accent, Color::Rgb(1, 2, 3));
+    // border and status bar follow the accent unless set
+    assert_eq!(colors.border, Color::Rgb(1, 2, 3));
+    assert_eq!(colors.status, Color::Rgb(1, 2, 3));
+    assert_eq!(colors.quote, Color::Green);
+    assert_eq!(colors.folder, gruvbox.folder);
+    assert_eq!(colors.selection_fg, gruvbox.selection_fg);
+
+    let (_, issues) = parse_config("[theme]\nbase = \"solarized\"\nlink = \"nope\"\n");
+    let fields: Vec<&str> = issues.iter().map(|i| i.field.as_str()).collect();
+    assert_eq!(fields, vec!["theme.link", "theme.base"]);
+}
+
+#[test]
+fn test_theme_files_join_the_cycle() {
+    let dir = tempfile::tempdir().unwrap();
+    fs::write(
+        dir.path().join("nord.toml"),
+        "accent = \"#88c0d0\"\nfolder = \"#ebcb8b\"\n",
+    )
+    .unwrap();
+    fs::write(dir.path().join("gruvbox.toml"), "accent = \"#000000\"\n").unwrap();
+    fs::write(dir.path().join("broken.toml"), "accent = [").unwrap();
+    fs::write(dir.path().join("notes.txt"), "").unwrap();
+
+    let themes = load_theme_files(dir.path());
+    let names: Vec<&str> = themes.iter().map(|(n, _)| n.as_str()).collect();
+    assert_eq!(names, vec!["gruvbox", "nord"]);
+
+    let mut app = App::new(vec![], "/tmp".into(), Config::default());
+    app.themes = themes;
+    assert_eq!(
+        app.theme_names(),
+        vec!["default", "gruvbox", "tokyo-night", "nord"]
+    );
+
+    // a theme file with a built-in name replaces it
+    app.cycle_theme();
+    assert_eq!(app.theme_name, "gruvbox");
+    assert_eq!(app.theme.accent, Color::Rgb(0, 0, 0));
+
+    app.cycle_theme();
+    app.cycle_theme();
+    assert_eq!(app.theme_name, "nord");
+    assert_eq!(app.theme.folder, Color::Rgb(235, 203, 139));
+
+    app.cycle_theme();
+    assert_eq!(app.theme_name, "default");
+}
+
+#[test]
+fn test_color_mode_detection() {
+    let s = |v: &str| Some(v.to_string());
+    assert_eq!(
+        ColorMode::detect(None, s("truecolor"), s("xterm-256color")),
+        ColorMode::TrueColor
+    );
+    assert_eq!(
+        ColorMode::detect(None, None, s("xterm-256color")),
+        ColorMode::Ansi256
+    );
+    assert_eq!(ColorMode::detect(None, None, s("linux")), ColorMode::Ansi16);
+    assert_eq!(ColorMode::detect(None, None, s("dumb")), ColorMode::Mono);
+    assert_eq!(
+        ColorMode::detect(s("1"), s("truecolor"), s("xterm")),
+        ColorMode::Mono
+    );
+    // an empty NO_COLOR does not count
+    assert_eq!(
+        ColorMode::detect(s(""), s("24bit"), None),
+        ColorMode::TrueColor
+    );
+
+    // config wins over the environment
+    let config = Config {
+        color_mode: s("16"),
+        ..Config::default()
+    };
+    assert_eq!(ColorMode::from_config(&config), ColorMode::Ansi16);
+
+    let (_, issues) = parse_config("color_mode = \"8\"\n");
+    assert_eq!(issues[0].field, "color_mode");
+}
+
+#[test]
+fn test_colors_map_down_to_the_terminal() {
+    let mode = ColorMode::Ansi256;
+    assert_eq!(mode.adapt(Color::Rgb(255, 0, 0)), Color::Indexed(196));
+    assert_eq!(mode.adapt(Color::Rgb(128, 128, 128)), Color::Indexed(244));
+    assert_eq!(mode.adapt(Color::Yellow), Color::Yellow);
+
+    let mode = ColorMode::Ansi16;
+    assert_eq!(mode.adapt(Color::Rgb(250, 10, 5)), Color::LightRed);
+    assert_eq!(mode.adapt(Color::Rgb(10, 10, 20)), Color::Black);
+    assert_eq!(mode.adapt(Color::Indexed(21)), Color::Blue);
+    assert_eq!(mode.adapt(Color::Indexed(3)), Color::Yellow);
+    assert_eq!(mode.adapt(Color::Reset), Color::Reset);
+
+    let colors = ThemeColors::default().for_mode(ColorMode::Mono);
+    assert!(colors.monochrome);
+    assert_eq!(colors.accent, Color::Reset);
+    assert_eq!(
+        colors.style(colors.link, Modifier::UNDERLINED),
+        Style::default().add_modifier(Modifier::UNDERLINED)
+    );
+    assert!(colors.selected().add_modifier.contains(Modifier::REVERSED));
+
+    // switching themes keeps the mode
+    let mut app = App::new(vec![], "/tmp".into(), Config::default());
+    app.set_color_mode(ColorMode::Ansi16);
+    app.cycle_theme();
+    assert_eq!(app.theme_name, "gruvbox");
+    assert!(!matches!(app.theme.accent, Color::Rgb(..)));
+}
@@ -0,0 +1,164 @@
+use chrono::NaiveDate;
+use kiroku_tui::config::{Config, Vault};
+use kiroku_tui::{data, ops};
+use std::fs;
+
+const VAULT_CONFIG: &str = r#"
+editor_cmd = "rm -rf"
+journal_dir = "daily"
+sort_mode = "Name"
+sync_backend = "mirror"
+note_template = ".kiroku/templates/note.md"
+journal_template = ".kiroku/templates/journal.md"
+ignore = ["archive/", "*.draft.md"]
+
+[[saved_searches]]
+name = "Team"
+query = "team"
+"#;
+
+#[test]
+fn test_vault_config_precedence() {
+    let dir = tempfile::tempdir().unwrap();
+    let vault = dir.path();
+    fs::create_dir_all(vault.join(".kiroku")).unwrap();
+    fs::write(vault.join(".kiroku/config.toml"), VAULT_CONFIG).unwrap();
+
+    let mut global = Config {
+        editor_cmd: Some("vim".to_string()),
+        journal_dir: Some("journal".to_string()),
+        journal_format: Some("%d-%m-%Y".to_string()),
+        ..Config::default()
+    };
+
+    let config = global.effective(None, vault);
+    assert_eq!(config.editor_cmd.as_deref(), Some("vim"));
+    assert_eq!(config.journal_dir.as_deref(), Some("daily"));
+    assert_eq!(config.journal_format.as_deref(), Some("%d-%m-%Y"));
+    assert_eq!(config.sort_mode.as_deref(), Some("Name"));
+    // nor where the notes are synced to
+    assert_eq!(config.sync_backend, None);
+    assert_eq!(config.saved_searches.unwrap()[0].name, "Team");
+
+    // a [[vaults]] entry in the global config has the last word
+    global.vaults = Some(vec![Vault {
+        name: "team".to_string(),
+        path: vault.to_string_lossy().to_string(),
+        sync_backend: Some("git".to_string()),
+        ..Vault::default()
+    }]);
+    let config = global.effective(Some("team"), vault);
+    assert_eq!(config.sync_backend.as_deref(), Some("git"));
+    assert_eq!(config.journal_dir.as_deref(), Some("daily"));
+
+    // a vault without its own file keeps the global settings
+    let other = tempfile::tempdir().unwrap();
+    let config = global.effective(None, other.path());
+    assert_eq!(config.journal_dir.as_deref(), Some("journal"));
+}
+
+#[test]
+fn test_vault_ignore_and_templates() {
+    let dir = tempfile::tempdir().unwrap();
+    let vault = dir.path();
+    fs::create_dir_all(vault.join(".kiroku/templates")).unwrap();
+    fs::create_dir_all(vault.join("archive")).unwrap();
+    fs::write(vault.join(".kiroku/config.toml"), VAULT_CONFIG).unwrap();
+    fs::write(
+        vault.join(".kiroku/templates/note.md"),
+        "# {title}\n\n{date}\n",
+    )
+    .unwrap();
+    fs::write(vault.join(".kiroku/templates/journal.md"), "## {date}\n").unwrap();
+    fs::write(vault.join("keep.md"), "").unwrap();
+    fs::write(vault.join("idea.draft.md"), "").unwrap();
+    fs::write(vault.join("archive/old.md"), "").unwrap();
+
+    let config = Config::default().effective(None, vault);
+    let ignore = data::IgnoreRules::from_config(vault, &config);
+
+    let notes = data::load_notes_with(&vault.to_string_lossy(), &ignore).unwrap();
+    let titles: Vec<&str> = notes.iter().map(|n| n.title.as_str()).collect();
+    assert!(titles.contains(&"keep"));
+    assert!(!titles.contains(&"idea.draft"));
+    assert!(!titles.contains(&"archive/old"));
+
+    let items = data::load_all_items_with(&vault.to_string_lossy(), &ignore).unwrap();
+    assert!(
+        !items
+            .iter()
+            .any(|i| matches!(i, data::FileSystemItem::Folder(p) if p.ends_with("archive")))
+    );
+
+    let date = NaiveDate::from_ymd_opt(2026, 10, 18).unwrap();
+    let body = ops::note_template(vault, &config, "Plan", date).unwrap();
+    assert_eq!(body.as_deref(), Some("# Plan\n\n2026-10-18\n"));
+
+    let journal = ops::ensure_journal_note(vault, &config, date).unwrap();
+    assert!(journal.starts_with(vault.join("daily")));
+    assert_eq!(fs::read_to_string(journal).unwrap(), "## 2026-10-18\n");
+}
+
+#[test]
+fn test_templates_stay_inside_the_vault() {
+    let dir = tempfile::tempdir().unwrap();
+    let vault = dir.path().join("vault");
+    fs::create_dir_all(vault.join("templates")).unwrap();
+    fs::write(dir.path().join("secret"), "key").unwrap();
+    fs::write(vault.join("templates/note.md"), "# {title}\n").unwrap();
+
+    let date = NaiveDate::from_ymd_opt(2026, 10, 18).unwrap();
+    let secret = dir.path().join("secret");
+    for template in [
+        "../secret",
+        "templates/../../secret",
+        secret.to_str().unwrap(),
+    ] {
+        assert!(ops::render_template(&vault, template, "x", date).is_err());
+    }
+    let body = ops::render_template(&vault, "./templates/../templates/note.md", "x", date);
+    assert_eq!(body.unwrap(), "# x\n");
+}
+
+#[test]
+fn test_vault_paths_stay_inside_the_vault() {
+    let dir = tempfile::tempdir().unwrap();
+    let vault = dir.path().join("vault");
+    fs::create_dir_all(vault.join(".kiroku")).unwrap();
+    fs::write(
+        vault.join(".kiroku/config.toml"),
+        r#"
+capture_target = "../../x"
+journal_dir = "/tmp/daily"
+journal_format = "../%Y-%m-%d"
+attachment_dir = "./../../files"
+"#,
+    )
+    .unwrap();
+
+    let global = Config {
+        capture_target: Some("inbox".to_string()),
+        ..Config::default()
+    };
+    let config = global.effective(None, &vault);
+    assert_eq!(config.capture_target.as_deref(), Some("inbox"));
+    assert_eq!(config.journal_dir, None);
+    assert_eq!(config.journal_format, None);
+    assert_eq!(config.attachment_dir, None);
+
+    let date = NaiveDate::from_ymd_opt(2026, 10, 18).unwrap();
+    let target = ops::capture_path(&vault, &config, None, date).unwrap();
+    assert_eq!(target, vault.join("inbox.md"));
+
+    // paths that stay inside are still taken
+    fs::write(
+        vault.join(".kiroku/config.toml"),
+        "capture_target = \"notes/../inbox/today\"\n",
+    )
+    .unwrap();
+    let config = global.effective(None, &vault);
+    assert_eq!(
+        config.capture_target.as_deref(),
+        Some("notes/../inbox/today")
+    );
+}
@@ -0,0 +1,122 @@
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use kiroku_tui::app::{Action, App, InputMode, SortMode};
+use kiroku_tui::config::{Config, Vault};
+use kiroku_tui::data;
+use std::path::Path;
+
+fn key(code: KeyCode) -> KeyEvent {
+    KeyEvent::new(code, KeyModifiers::NONE)
+}
+
+fn vault(name: &str, path: &Path) -> Vault {
+    Vault {
+        name: name.to_string(),
+        path: path.to_string_lossy().to_string(),
+        ..Vault::default()
+    }
+}
+
+#[test]
+fn test_resolve_and_override_vaults() {
+    let dir = tempfile::tempdir().unwrap();
+    let work = dir.path().join("work");
+    let personal = dir.path().join("personal");
+
+    let config = Config {
+        journal_dir: Some("journal".to_string()),
+        vaults: Some(vec![
+            vault("personal", &personal),
+            Vault {
+                journal_dir: Some("daily".to_string()),
+                sort_mode: Some("Name".to_string()),
+                ..vault("Work", &work)
+            },
+        ]),
+        ..Config::default()
+    };
+
+    // first configured vault, unless another was used last
+    let (name, path) = config.resolve_vault(None, None).unwrap();
+    assert_eq!(name.as_deref(), Some("personal"));
+    assert_eq!(path, personal);
+
+    let (name, path) = config.resolve_vault(None, Some("work")).unwrap();
+    assert_eq!(name.as_deref(), Some("Work"));
+    assert_eq!(path, work);
+
+    // --vault takes a name or a plain path
+    let (name, _) = config
+        .resolve_vault(Some(Path::new("personal")), Some("work"))
+        .unwrap();
+    assert_eq!(name.as_deref(), Some("personal"));
+    let (name, path) = config
+        .resolve_vault(Some(Path::new("/tmp/other")), None)
+        .unwrap();
+    assert_eq!(name, None);
+    assert_eq!(path, Path::new("/tmp/other"));
+
+    // vault settings win over global ones
+    let effective = config.effective(Some("work"), &work);
+    assert_eq!(effective.journal_dir.as_deref(), Some("daily"));
+    assert_eq!(effective.sort_mode.as_deref(), Some("Name"));
+    let effective = config.effective(Some("personal"), &personal);
+    assert_eq!(effective.journal_dir.as_deref(), Some("journal"));
+}
+
+#[test]
+fn test_vault_switcher_reloads_app() {
+    let dir = tempfile::tempdir().unwrap();
+    let work = dir.path().join("work");
+    let personal = dir.path().join("personal");
+    std::fs::create_dir_all(work.join("projects")).unwrap();
+    std::fs::create_dir_all(&personal).unwrap();
+    std::fs::write(work.join("plan.md"), "").unwrap();
+    std::fs::write(personal.join("diary.md"), "").unwrap();
+
+    let config = Config {
+        vaults: Some(vec![
+            vault("personal", &personal),
+            Vault {
+                sort_mode: Some("Size".to_string()),
+                ..vault("work", &work)
+            },
+        ]),
+        ..Config::default()
+    };
+
+    let notes = data::load_notes(&personal.to_string_lossy()).unwrap();
+    let mut app = App::new(
+        notes,
+        personal.clone(),
+        config.effective(Some("personal"), &personal),
+    );
+    app.vault_name = Some("personal".to_string());
+
+    app.handle_input(key(KeyCode::Char('v')));
+    assert!(app.input_mode == InputMode::VaultSwitcher);
+    assert_eq!(app.vault_state.selected(), Some(0));
+
+    app.handle_input(key(KeyCode::Char('j')));
+    let name = match app.handle_input(key(KeyCode::Enter)) {
+        Action::SwitchVault(name) => name,
+        _ => panic!("expected a vault switch"),
+    };
+    assert_eq!(name, "work");
+    assert!(app.input_mode == InputMode::Normal);
+
+    let chosen = config.vault(&name).unwrap();
+    let notes = data::load_notes(&work.to_string_lossy()).unwrap();
+    app.load_vault(
+        Some(chosen.name.clone()),
+        chosen.path(),
+        notes,
+        config.for_vault(chosen),
+    );
+
+    assert_eq!(app.base_path, work);
+    assert_eq!(app.all_notes.len(), 1);
+    assert_eq!(app.all_notes[0].title, "plan");
+    assert_eq!(app.fs_items.len(), 2);
+    assert_eq!(app.list_state.selected(), Some(0));
+    assert!(app.sort_mode == SortMode::Size);
+}
@@ -0,0 +1,158 @@
+use kiroku_tui::app::App;
+use kiroku_tui::config::Config;
+use kiroku_tui::data::{self, FileSystemItem};
+use kiroku_tui::events::{AppEvent, debounce_paths};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::mpsc;
+use std::time::Duration;
+
+fn listed(app: &App) -> Vec<String> {
+    app.fs_items
+        .iter()
+        .map(|item| match item {
+            FileSystemItem::Note(n) => n.title.clone(),
+            FileSystemItem::Folder(p) => format!("{}/", p.file_name().unwrap().to_string_lossy()),
+            FileSystemItem::Attachment(p) => p.file_name().unwrap().to_string_lossy().to_string(),
+        })
+        .collect()
+}
+
+fn open_app(dir: &Path, config: Config) -> App {
+    let notes = data::load_notes(&dir.to_string_lossy()).unwrap();
+    App::new(notes, dir.into(), config)
+}
+
+#[test]
+fn test_changes_update_notes_and_keep_selection() {
+    let dir = tempfile::tempdir().unwrap();
+    let root = dir.path();
+    fs::create_dir_all(root.join("projects")).unwrap();
+    fs::write(root.join("alpha.md"), "a").unwrap();
+    fs::write(root.join("beta.md"), "b").unwrap();
+    fs::write(root.join("projects/plan.md"), "p").unwrap();
+
+    let mut app = open_app(root, Config::default());
+    assert_eq!(listed(&app), vec!["projects/", "alpha", "beta"]);
+    app.list_state.select(Some(2));
+
+    // a new note sorts in ahead of the selected one
+    fs::write(root.join("aardvark.md"), "new").unwrap();
+    assert!(app.apply_file_changes(&[root.join("aardvark.md")]));
+    assert_eq!(listed(&app), vec!["projects/", "aardvark", "alpha", "beta"]);
+    assert_eq!(app.list_state.selected(), Some(3));
+
+    // edits in subfolders reach the note list
+    fs::write(root.join("projects/plan.md"), "longer plan").unwrap();
+    fs::write(root.join("projects/todo.md"), "t").unwrap();
+    app.apply_file_changes(&[root.join("projects/plan.md"), root.join("projects/todo.md")]);
+    let todo = app
+        .all_notes
+        .iter()
+        .find(|n| n.title == "projects/todo")
+        .map(|n| n.size);
+    assert_eq!(todo, Some(1));
+    assert_eq!(app.all_notes.len(), 5);
+    assert_eq!(app.notes.len(), 5);
+    assert_eq!(listed(&app).len(), 4);
+
+    // removing the selected note moves to its neighbour
+    fs::remove_file(root.join("beta.md")).unwrap();
+    app.apply_file_changes(&[root.join("beta.md")]);
+    assert_eq!(listed(&app), vec!["projects/", "aardvark", "alpha"]);
+    assert_eq!(app.list_state.selected(), Some(2));
+
+    // removing a folder drops its notes
+    fs::remove_dir_all(root.join("projects")).unwrap();
+    app.apply_file_changes(&[root.join("projects")]);
+    assert_eq!(app.all_notes.len(), 2);
+    assert_eq!(listed(&app), vec!["aardvark", "alpha"]);
+}
+
+#[test]
+fn test_refresh_keeps_selection() {
+    let dir = tempfile::tempdir().unwrap();
+    let root = dir.path();
+    fs::write(root.join("b.md"), "b").unwrap();
+    fs::write(root.join("c.md"), "c").unwrap();
+
+    let mut app = open_app(root, Config::default());
+    app.list_state.select(Some(1));
+
+    // changed behind kiroku's back, e.g. while the terminal was unfocused
+    fs::write(root.join("a.md"), "a").unwrap();
+    fs::remove_file(root.join("b.md")).unwrap();
+    app.refresh_notes();
+
+    assert_eq!(listed(&app), vec!["a", "c"]);
+    assert_eq!(app.list_state.selected(), Some(1));
+    assert_eq!(app.all_notes.len(), 2);
+}
+
+#[test]
+fn test_ignored_and_git_paths_are_skipped() {
+    let dir = tempfile::tempdir().unwrap();
+    let root = dir.path();
+    fs::create_dir_all(root.join(".git")).unwrap();
+    fs::create_dir_all(root.join("archive")).unwrap();
+    fs::write(root.join(".git/index"), "").unwrap();
+    fs::write(root.join("archive/old.md"), "").unwrap();
+
+    let config = Config {
+        ignore: Some(vec!["archive/".to_string()]),
+        ..Config::default()
+    };
+    let ignore = data::IgnoreRules::from_config(root, &config);
+    let notes = data::load_notes_with(&root.to_string_lossy(), &ignore).unwrap();
+    let mut app = App::new(notes, root.into(), config);
+    assert!(!app.apply_file_changes(&[root.join(".git/index")]));
+    assert!(!app.apply_file_changes(&[root.join("archive/old.md")]));
+    assert!(!app.apply_file_changes(&[PathBuf::from("/elsewhere/note.md")]));
+    assert!(app.all_notes.is_empty());
+}
+
+#[test]
+fn test_changes_are_batched() {
+    let (tx, rx) = mpsc::channel();
+    let changes = debounce_paths(tx, Duration::from_millis(50));
+    changes.send(vec![PathBuf::from("b.md")]).unwrap();
+    changes
+        .send(vec![PathBuf::from("a.md"), PathBuf::from("b.md")])
+        .unwrap();
+
+    match rx.recv_timeout(Duration::from_secs(2)).unwrap() {
+        AppEvent::FileChanged(paths) => {
+            assert_eq!(paths, vec![PathBuf::from("a.md"), PathBuf::from("b.md")])
+        }
+        _ => panic!("expected a FileChanged batch"),
+    }
+    assert!(rx.recv_timeout(Duration::from_millis(150)).is_err());
+}
+
+#[test]
+fn test_changes_reach_a_vault_opened_by_relative_path() {
+    let dir = tempfile::tempdir().unwrap();
+    let root = dir.path().canonicalize().unwrap();
+    fs::write(root.join("alpha.md"), "a").unwrap();
+
+    // the same folder, spelled relative to where the tests run
+    let cwd = std::env::current_dir().unwrap().canonicalize().unwrap();
+    let mut rel = PathBuf::new();
+    for _ in cwd.components().skip(1) {
+        rel.push("..");
+    }
+    rel.push(root.strip_prefix("/").unwrap());
+
+    let mut app = open_app(&rel, Config::default());
+    assert_eq!(listed(&app), vec!["alpha"]);
+
+    // notify hands back absolute paths
+    fs::write(root.join("beta.md"), "b").unwrap();
+    assert!(app.apply_file_changes(&[root.join("beta.md")]));
+    assert_eq!(listed(&app), vec!["alpha", "beta"]);
+
+    fs::remove_file(root.join("alpha.md")).unwrap();
+    assert!(app.apply_file_changes(&[root.join("alpha.md")]));
+    assert_eq!(listed(&app), vec!["beta"]);
+    assert_eq!(app.all_notes.len(), 1);
+}